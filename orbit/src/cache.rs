@@ -0,0 +1,146 @@
+//! On-disk cache of GET responses keyed by request, revalidated with
+//! `If-None-Match`/`If-Modified-Since`.
+//!
+//! Entries are readable by the user only. Responses from endpoints that
+//! carry secrets are never stored, and the oldest entries are evicted once
+//! the cache outgrows [`MAX_CACHE_BYTES`].
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Size the cache directory is trimmed back to, oldest entries first.
+const MAX_CACHE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Path segments, matched case-insensitively as substrings, of endpoints
+/// whose responses are never cached.
+const UNCACHED_SEGMENTS: &[&str] = &[
+    "secret",
+    "apikey",
+    "api-key",
+    "credential",
+    "token",
+    "password",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Next-page target from the `Link` header, replayed on a 304.
+    pub link: Option<String>,
+    pub body: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self {
+            dir: crate::config::orbit_dir().join("cache"),
+        }
+    }
+
+    /// Derives a file-safe key from everything that can change a response:
+    /// the URL plus the tenant, namespace, and credential it was fetched with.
+    /// SHA-256 keeps keys stable across builds, and only a digest of the
+    /// credential reaches the file name.
+    pub fn key(parts: &[Option<&str>]) -> String {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            match part {
+                Some(part) => {
+                    hasher.update([1]);
+                    hasher.update((part.len() as u64).to_le_bytes());
+                    hasher.update(part.as_bytes());
+                }
+                None => hasher.update([0]),
+            }
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Whether responses from `path` may be stored: not for endpoints
+    /// listing secrets, API keys, credentials or tokens.
+    pub fn cacheable(path: &str) -> bool {
+        let path = path.to_ascii_lowercase();
+        !path
+            .split('/')
+            .any(|segment| UNCACHED_SEGMENTS.iter().any(|s| segment.contains(s)))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    pub fn load(&self, key: &str) -> Option<CacheEntry> {
+        let content = std::fs::read_to_string(self.path(key)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Stores an entry; failures are ignored since the cache is best-effort.
+    pub fn store(&self, key: &str, entry: &CacheEntry) {
+        if create_private_dir(&self.dir).is_err() {
+            return;
+        }
+        if let Ok(content) = serde_json::to_vec(entry)
+            && write_private(&self.path(key), &content).is_ok()
+        {
+            self.evict();
+        }
+    }
+
+    /// Removes the least recently written entries while the cache is over
+    /// [`MAX_CACHE_BYTES`].
+    fn evict(&self) {
+        let Ok(dir) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut entries: Vec<_> = dir
+            .filter_map(|e| {
+                let e = e.ok()?;
+                let meta = e.metadata().ok()?;
+                Some((meta.modified().ok()?, meta.len(), e.path()))
+            })
+            .collect();
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= MAX_CACHE_BYTES {
+            return;
+        }
+        entries.sort();
+        for (_, len, path) in entries {
+            if total <= MAX_CACHE_BYTES {
+                break;
+            }
+            if std::fs::remove_file(path).is_ok() {
+                total -= len;
+            }
+        }
+    }
+}
+
+fn create_private_dir(dir: &std::path::Path) -> std::io::Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
+
+/// Writes `content` to `path` readable and writable by the user only.
+fn write_private(path: &std::path::Path, content: &[u8]) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    // The mode only applies to new files; tighten ones left by older
+    // versions too.
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(content)
+}
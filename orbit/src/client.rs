@@ -1,13 +1,260 @@
+use crate::config::{DeployLock, StoredToken};
 use crate::error::{OrbitError, Result};
-use reqwest::{Client, Method, Response};
+use crate::middleware::{self, Credentials, Middleware, Next};
+use crate::signing::HmacSigner;
+use crate::trace::HttpTracer;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use futures_util::StreamExt;
+use reqwest::{Client, Method, RequestBuilder, Response};
 use serde_json::Value;
+use std::future::Future;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
 
+pub type WsStream =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Splits a `KEY=VALUE` header argument, checking that both halves are valid
+/// HTTP header syntax.
+pub fn parse_header(raw: &str) -> Result<(String, String)> {
+    let (name, value) = raw
+        .split_once('=')
+        .ok_or_else(|| OrbitError::Input(format!("Invalid header '{raw}': expected KEY=VALUE")))?;
+    let name = name.trim();
+    reqwest::header::HeaderName::from_bytes(name.as_bytes())
+        .map_err(|_| OrbitError::Input(format!("Invalid header name '{name}'")))?;
+    reqwest::header::HeaderValue::from_str(value)
+        .map_err(|_| OrbitError::Input(format!("Invalid value for header '{name}'")))?;
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// Transport-level settings applied when building the underlying HTTP client.
+#[derive(Debug, Default, Clone)]
+pub struct ClientOptions {
+    /// Per-request timeout; `None` waits indefinitely. Event streams and
+    /// multipart uploads are only bounded while connecting.
+    pub timeout: Option<Duration>,
+    /// Follow pagination in `get_paged` instead of returning only the first page.
+    pub fetch_all: bool,
+    /// Explicit proxy for all traffic. `HTTP_PROXY`/`HTTPS_PROXY` are honored
+    /// when unset; `NO_PROXY` applies either way.
+    pub proxy: Option<String>,
+    /// PEM client certificate presented for mutual TLS.
+    pub client_cert: Option<String>,
+    /// PKCS#8 PEM private key matching `client_cert`.
+    pub client_key: Option<String>,
+    /// Additional PEM root certificate trusted for the server.
+    pub ca_cert: Option<String>,
+    /// Extra `KEY=VALUE` headers sent on every request.
+    pub headers: Vec<String>,
+    /// HTTP trace verbosity (`-v` count); 0 disables tracing.
+    pub verbosity: u8,
+    /// Revalidate GET responses against the cache in `~/.orbit/cache`.
+    pub cache: bool,
+    /// Gzip large request bodies sent with the `*_compressed` methods.
+    pub compress: bool,
+    /// OAuth token from `orbit login`; sent as a bearer token instead of the
+    /// API key and refreshed when expired.
+    pub token: Option<StoredToken>,
+    /// Active change freeze; mutating requests fail unless overridden.
+    pub lock: Option<DeployLock>,
+    /// Reason given with `--override-lock`; each overridden request is logged.
+    pub lock_override: Option<String>,
+    /// Print mutating API requests instead of sending them, answering each
+    /// with a synthetic success.
+    pub dry_run: bool,
+    /// Poll operations the server accepts with 202 and a `Location` until
+    /// they finish, returning the final state instead of the receipt.
+    pub wait: bool,
+    /// Sign every request with HMAC instead of sending an API key or token.
+    pub hmac: Option<HmacSigner>,
+    /// Config context in use; refreshed tokens are saved back to it.
+    pub context: Option<String>,
+}
+
+fn read_pem(path: &str, label: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| OrbitError::Config(format!("Cannot read {label} {path}: {e}")))
+}
+
+/// TLS material loaded from `ClientOptions`, shared by the HTTP and WebSocket
+/// transports.
+#[derive(Default)]
+struct TlsMaterial {
+    identity: Option<(Vec<u8>, Vec<u8>)>,
+    ca_cert: Option<Vec<u8>>,
+}
+
+impl TlsMaterial {
+    fn load(options: &ClientOptions) -> Result<Self> {
+        let identity = match (&options.client_cert, &options.client_key) {
+            (Some(cert), Some(key)) => Some((
+                read_pem(cert, "client certificate")?,
+                read_pem(key, "client key")?,
+            )),
+            (None, None) => None,
+            _ => {
+                return Err(OrbitError::Config(
+                    "--client-cert and --client-key must be provided together".into(),
+                ));
+            }
+        };
+        let ca_cert = match &options.ca_cert {
+            Some(path) => Some(read_pem(path, "CA certificate")?),
+            None => None,
+        };
+        Ok(Self { identity, ca_cert })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.identity.is_none() && self.ca_cert.is_none()
+    }
+
+    fn native_connector(&self) -> Result<native_tls::TlsConnector> {
+        let tls_err =
+            |e: native_tls::Error| OrbitError::Config(format!("Invalid TLS settings: {e}"));
+        let mut builder = native_tls::TlsConnector::builder();
+        if let Some((cert, key)) = &self.identity {
+            builder.identity(native_tls::Identity::from_pkcs8(cert, key).map_err(tls_err)?);
+        }
+        if let Some(ca) = &self.ca_cert {
+            builder.add_root_certificate(native_tls::Certificate::from_pem(ca).map_err(tls_err)?);
+        }
+        builder.build().map_err(tls_err)
+    }
+}
+
+#[derive(Clone)]
 pub struct NovaClient {
     client: Client,
     base_url: String,
-    api_key: Option<String>,
     tenant: Option<String>,
     namespace: Option<String>,
+    fetch_all: bool,
+    timeout: Option<Duration>,
+    ws_tls: Option<native_tls::TlsConnector>,
+    unix_socket: bool,
+    tracer: HttpTracer,
+    dry_run: bool,
+    wait: bool,
+    compress: bool,
+    signer: Option<HmacSigner>,
+    context: Option<String>,
+    auth_method: &'static str,
+    credentials: Arc<Credentials>,
+    /// Middleware every request is sent through, outermost first.
+    layers: Arc<[Arc<dyn Middleware>]>,
+    /// Index of the first layer below `credentials`, which token refreshes
+    /// are sent through.
+    below_credentials: usize,
+}
+
+/// Incremental reader over a `text/event-stream` response.
+///
+/// Each call to [`EventStream::next`] yields the `data` payload of the next
+/// event, parsed as JSON when possible and as a plain string otherwise.
+pub struct EventStream {
+    resp: Response,
+    buffer: Vec<u8>,
+}
+
+impl EventStream {
+    pub async fn next(&mut self) -> Option<Result<Value>> {
+        loop {
+            if let Some(event) = self.take_event() {
+                return Some(Ok(event));
+            }
+            match self.resp.chunk().await {
+                Ok(Some(bytes)) => self.buffer.extend(bytes.iter().filter(|b| **b != b'\r')),
+                Ok(None) => {
+                    if self.buffer.is_empty() {
+                        return None;
+                    }
+                    self.buffer.extend_from_slice(b"\n\n");
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+
+    fn take_event(&mut self) -> Option<Value> {
+        loop {
+            let end = self.buffer.windows(2).position(|w| w == b"\n\n")?;
+            let block: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            let block = String::from_utf8_lossy(&block);
+            let data: Vec<&str> = block
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|d| d.strip_prefix(' ').unwrap_or(d))
+                .collect();
+            if data.is_empty() {
+                continue;
+            }
+            let data = data.join("\n");
+            return Some(serde_json::from_str(&data).unwrap_or(Value::String(data)));
+        }
+    }
+}
+
+/// Upper bound on pages followed by `get_paged`, guarding against servers that
+/// keep returning the same cursor.
+const MAX_PAGES: usize = 1000;
+
+/// Request bodies smaller than this are sent uncompressed.
+const COMPRESS_MIN_BYTES: usize = 1024;
+
+/// Requests kept in flight by bulk commands using [`NovaClient::batch`].
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// How often a long-running operation is polled.
+const OPERATION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Operation states after which polling stops, split by outcome. A paused
+/// operation only moves on once resumed, so waiting on it stops too.
+const OPERATION_DONE: &[&str] = &[
+    "succeeded",
+    "success",
+    "completed",
+    "complete",
+    "done",
+    "published",
+    "ready",
+];
+const OPERATION_FAILED: &[&str] = &["failed", "error", "dlq", "cancelled", "canceled"];
+const OPERATION_PAUSED: &[&str] = &["paused"];
+
+/// Returns `path` with the query parameter `key` set to `value`, replacing any
+/// existing occurrence.
+fn with_query_param(path: &str, key: &str, value: &str) -> String {
+    let (base, query) = path.split_once('?').unwrap_or((path, ""));
+    let mut params: Vec<&str> = query
+        .split('&')
+        .filter(|p| !p.is_empty() && p.split('=').next() != Some(key))
+        .collect();
+    let pair = format!("{key}={value}");
+    params.push(&pair);
+    format!("{base}?{}", params.join("&"))
+}
+
+/// Extracts the `rel="next"` target from an RFC 8288 `Link` header.
+pub fn next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|part| {
+        let mut segments = part.split(';');
+        let target = segments.next()?.trim();
+        let is_next = segments.any(|s| {
+            let s = s.trim();
+            s == "rel=\"next\"" || s == "rel=next"
+        });
+        is_next.then(|| {
+            target
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
 }
 
 impl NovaClient {
@@ -16,83 +263,639 @@ impl NovaClient {
         api_key: Option<String>,
         tenant: Option<String>,
         namespace: Option<String>,
-    ) -> Self {
-        Self {
-            client: Client::new(),
-            base_url: base_url.trim_end_matches('/').to_string(),
-            api_key,
+        options: ClientOptions,
+    ) -> Result<Self> {
+        let mut builder = Client::builder();
+        // `unix:///path/to/nova.sock` sends every request over a local socket;
+        // the host in the request URL is then only used for the Host header.
+        let unix_socket = base_url.strip_prefix("unix://").map(str::to_string);
+        let base_url = match &unix_socket {
+            Some(_) => "http://localhost".to_string(),
+            None => base_url,
+        };
+        // The timeout bounds connecting here and each whole request in
+        // `send`, except for streams and uploads that may legitimately run
+        // for longer.
+        if let Some(timeout) = options.timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy_url) = &options.proxy {
+            let mut proxy = reqwest::Proxy::all(proxy_url)
+                .map_err(|e| OrbitError::Config(format!("Invalid proxy '{proxy_url}': {e}")))?;
+            if let Ok(url) = reqwest::Url::parse(proxy_url)
+                && !url.username().is_empty()
+            {
+                proxy = proxy.basic_auth(url.username(), url.password().unwrap_or_default());
+            }
+            builder = builder.proxy(proxy.no_proxy(reqwest::NoProxy::from_env()));
+        }
+        let tls = TlsMaterial::load(&options)?;
+        let tls_err = |e: reqwest::Error| OrbitError::Config(format!("Invalid TLS settings: {e}"));
+        if let Some((cert, key)) = &tls.identity {
+            let identity = reqwest::Identity::from_pkcs8_pem(cert, key).map_err(tls_err)?;
+            builder = builder.identity(identity);
+        }
+        if let Some(ca) = &tls.ca_cert {
+            let ca = reqwest::Certificate::from_pem(ca).map_err(tls_err)?;
+            builder = builder.add_root_certificate(ca);
+        }
+        if let Some(path) = &unix_socket {
+            #[cfg(unix)]
+            {
+                builder = builder.unix_socket(path.as_str());
+            }
+            #[cfg(not(unix))]
+            {
+                return Err(OrbitError::Config(format!(
+                    "Unix socket '{path}' is not supported on this platform"
+                )));
+            }
+        }
+        let ws_tls = if tls.is_empty() {
+            None
+        } else {
+            Some(tls.native_connector()?)
+        };
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let base = reqwest::Url::parse(&base_url)
+            .map_err(|e| OrbitError::Config(format!("Invalid server URL '{base_url}': {e}")))?;
+
+        let auth_method = if options.hmac.is_some() {
+            "hmac"
+        } else if options.token.is_some() {
+            "login"
+        } else if api_key.is_some() {
+            "api-key"
+        } else {
+            "none"
+        };
+        let mut headers = Vec::new();
+        if let Some(key) = api_key.filter(|_| options.token.is_none()) {
+            headers.push(("X-API-Key".to_string(), key));
+        }
+        if let Some(t) = &tenant {
+            headers.push(("X-Tenant-ID".to_string(), t.clone()));
+        }
+        if let Some(ns) = &namespace {
+            headers.push(("X-Namespace".to_string(), ns.clone()));
+        }
+        for header in &options.headers {
+            headers.push(parse_header(header)?);
+        }
+        let credentials = Arc::new(Credentials::new(
+            base.clone(),
+            headers,
+            options.token,
+            options.context.clone(),
+            options.timeout,
+        ));
+
+        let mut layers: Vec<Arc<dyn Middleware>> = Vec::new();
+        if options.dry_run {
+            layers.push(Arc::new(middleware::DryRun::new(base.clone())));
+        }
+        if let Some(lock) = options.lock {
+            layers.push(Arc::new(middleware::ChangeFreeze::new(
+                base.clone(),
+                lock,
+                options.lock_override,
+            )));
+        }
+        layers.push(credentials.clone());
+        let below_credentials = layers.len();
+        if options.cache {
+            let signer = options.hmac.as_ref().map(HmacSigner::identity);
+            layers.push(Arc::new(middleware::Cache::new(base.clone(), signer)));
+        }
+        layers.push(Arc::new(middleware::RateLimitRetry));
+        if let Some(signer) = &options.hmac {
+            layers.push(Arc::new(middleware::HmacSigning::new(base, signer.clone())));
+        }
+        let tracer = HttpTracer::new(options.verbosity);
+        layers.push(Arc::new(middleware::Tracing(tracer)));
+
+        Ok(Self {
+            client: builder.build()?,
+            base_url,
             tenant,
             namespace,
-        }
+            fetch_all: options.fetch_all,
+            timeout: options.timeout,
+            ws_tls,
+            unix_socket: unix_socket.is_some(),
+            tracer,
+            dry_run: options.dry_run,
+            wait: options.wait,
+            compress: options.compress,
+            signer: options.hmac,
+            context: options.context,
+            auth_method,
+            credentials,
+            layers: layers.into(),
+            below_credentials,
+        })
     }
 
-    fn build_request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
-        let url = format!("{}{}", self.base_url, path);
-        let mut req = self.client.request(method, &url);
-        if let Some(key) = &self.api_key {
-            req = req.header("X-API-Key", key);
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Config context selected by `--profile` or `config use-context`.
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    /// How requests are authenticated: `hmac`, `login`, `api-key` or `none`.
+    pub fn auth_method(&self) -> &'static str {
+        self.auth_method
+    }
+
+    /// True under `--dry-run`, for commands with local side effects to skip.
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Builds a request for `path`, relative to the server unless it is an
+    /// absolute URL. Credentials are added by the middleware when it is sent.
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = if path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else {
+            format!("{}{}", self.base_url, path)
+        };
+        self.client.request(method, &url)
+    }
+
+    /// Sends a request through the middleware stack; see [`crate::middleware`]
+    /// for the layers and their order.
+    async fn send(&self, req: RequestBuilder) -> Result<Response> {
+        self.dispatch(req, self.timeout).await
+    }
+
+    /// Like [`Self::send`] but without the request timeout, for event streams
+    /// and uploads whose bodies take as long as they take.
+    async fn send_untimed(&self, req: RequestBuilder) -> Result<Response> {
+        self.dispatch(req, None).await
+    }
+
+    async fn dispatch(&self, req: RequestBuilder, timeout: Option<Duration>) -> Result<Response> {
+        let mut request = req.build()?;
+        *request.timeout_mut() = timeout;
+        Next::new(&self.client, &self.layers).run(request).await
+    }
+
+    /// POSTs a form to an OAuth endpoint without attaching any credentials.
+    /// OAuth endpoints take `application/x-www-form-urlencoded` bodies.
+    /// These change nothing on the server, so they skip the `--dry-run`
+    /// layer even when the issuer shares the server's origin.
+    pub async fn oauth_post(&self, url: &str, form: &[(&str, &str)]) -> Result<Value> {
+        let mut request = self.client.post(url).form(form).build()?;
+        *request.timeout_mut() = self.timeout;
+        let below_dry_run = &self.layers[usize::from(self.dry_run)..];
+        let resp = Next::new(&self.client, below_dry_run).run(request).await?;
+        self.handle_response(resp).await
+    }
+
+    /// POSTs JSON to an absolute URL outside the API, such as a webhook.
+    /// The response body is ignored since receivers often reply with plain
+    /// text.
+    pub async fn post_webhook(&self, url: &str, body: &Value) -> Result<()> {
+        let resp = self.send(self.client.post(url).json(body)).await?;
+        self.response_text(resp).await.map(drop)
+    }
+
+    /// Sends a bodiless request to an absolute URL outside the API, such as
+    /// an artifact download, with the configured proxy, CA and timeout.
+    /// Credentials are only attached for the server's own origin.
+    pub async fn send_external(&self, method: Method, url: &str) -> Result<Response> {
+        self.send(self.client.request(method, url)).await
+    }
+
+    /// Opens a WebSocket on `path`, switching the scheme to `ws`/`wss`.
+    pub async fn websocket(&self, path: &str) -> Result<WsStream> {
+        if self.unix_socket {
+            return Err(OrbitError::Config(
+                "WebSocket connections are not supported over a Unix socket".into(),
+            ));
         }
-        if let Some(t) = &self.tenant {
-            req = req.header("X-Tenant-ID", t);
+        let url = format!("{}{}", self.base_url, path);
+        let url = if let Some(rest) = url.strip_prefix("https://") {
+            format!("wss://{rest}")
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            format!("ws://{rest}")
+        } else {
+            url
+        };
+        let ws_err =
+            |e: tokio_tungstenite::tungstenite::Error| OrbitError::WebSocket(e.to_string());
+
+        self.tracer.websocket(&url);
+        let mut request = url.into_client_request().map_err(ws_err)?;
+        let signature = self
+            .signer
+            .as_ref()
+            .map(|s| s.headers("GET", path, Some(&[])))
+            .unwrap_or_default();
+        let headers = self
+            .credentials
+            .headers()
+            .chain(signature.iter().map(|(n, v)| (*n, v.as_str())));
+        for (name, value) in headers {
+            let header = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| OrbitError::Config(format!("Invalid header name {name}: {e}")))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| OrbitError::Config(format!("Invalid {name} header: {e}")))?;
+            request.headers_mut().append(header, value);
         }
-        if let Some(ns) = &self.namespace {
-            req = req.header("X-Namespace", ns);
+        let below = Next::new(&self.client, &self.layers[self.below_credentials..]);
+        if let Some(token) = self.credentials.bearer(below).await? {
+            let value = HeaderValue::from_str(&format!("Bearer {token}"))
+                .map_err(|e| OrbitError::Config(format!("Invalid bearer token: {e}")))?;
+            request.headers_mut().insert("Authorization", value);
         }
-        req
+        let connector = self
+            .ws_tls
+            .clone()
+            .map(tokio_tungstenite::Connector::NativeTls);
+        let (stream, _) =
+            tokio_tungstenite::connect_async_tls_with_config(request, None, false, connector)
+                .await
+                .map_err(ws_err)?;
+        Ok(stream)
     }
 
-    async fn handle_response(resp: Response) -> Result<Value> {
+    async fn api_error(&self, resp: Response) -> OrbitError {
         let status = resp.status().as_u16();
-        if status >= 400 {
-            let body = resp.text().await.unwrap_or_default();
-            let message = serde_json::from_str::<Value>(&body)
-                .ok()
-                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
-                .unwrap_or(body);
-            return Err(OrbitError::api(status, message));
+        let wait = middleware::retry_after(resp.headers());
+        let body = resp.text().await.unwrap_or_default();
+        self.tracer.response_body(&body);
+        let parsed = serde_json::from_str::<Value>(&body).ok();
+        let field = |key: &str| {
+            parsed
+                .as_ref()
+                .and_then(|v| v.get(key))
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        };
+        let message = field("error").unwrap_or_else(|| body.clone());
+        let message = match wait {
+            Some(wait) if status == 429 => {
+                format!("{message} (retry after {}s)", wait.as_secs_f32().ceil())
+            }
+            _ => message,
+        };
+        // Hints come as `hint` or a `hints` array.
+        let hints = match parsed.as_ref().and_then(|v| v.get("hints")) {
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|h| h.as_str().map(String::from))
+                .collect(),
+            _ => field("hint").into_iter().collect(),
+        };
+        OrbitError::Api {
+            status,
+            message,
+            code: field("code"),
+            hints,
+        }
+    }
+
+    async fn response_text(&self, resp: Response) -> Result<String> {
+        if resp.status().as_u16() >= 400 {
+            return Err(self.api_error(resp).await);
         }
         let text = resp.text().await?;
+        self.tracer.response_body(&text);
+        Ok(text)
+    }
+
+    fn parse_body(text: &str) -> Result<Value> {
         if text.is_empty() {
             Ok(Value::Null)
         } else {
-            serde_json::from_str(&text).map_err(OrbitError::Json)
+            serde_json::from_str(text).map_err(OrbitError::Json)
+        }
+    }
+
+    /// Parses a JSON response. Under `--wait`, a 202 Accepted carrying a
+    /// `Location` is followed until the operation finishes.
+    async fn handle_response(&self, resp: Response) -> Result<Value> {
+        let location = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+            .filter(|_| resp.status() == reqwest::StatusCode::ACCEPTED);
+        let text = self.response_text(resp).await?;
+        let body = Self::parse_body(&text)?;
+        match location {
+            Some(location) if self.wait => self.wait_operation(&location, None).await,
+            _ => Ok(body),
         }
     }
 
+    /// Polls the operation at `location` (a path or URL, resolved against the
+    /// server) until its `status` is terminal, returning the final state. A
+    /// failed or paused operation is an error carrying its
+    /// `last_error`/`error`; a body without a status is taken as finished.
+    pub async fn wait_operation(&self, location: &str, timeout: Option<Duration>) -> Result<Value> {
+        // Paths stay under the server URL's own path, as for every request.
+        let base = reqwest::Url::parse(&format!("{}/", self.base_url))
+            .map_err(|e| OrbitError::Config(format!("Invalid server URL: {e}")))?;
+        let relative = match location.strip_prefix('/') {
+            Some(rest) if !rest.starts_with('/') => rest,
+            _ => location,
+        };
+        let url = base.join(relative).map_err(|e| {
+            OrbitError::Input(format!("Invalid operation location '{location}': {e}"))
+        })?;
+        // Credentials are attached to every request, so only follow the
+        // server's own origin.
+        if url.origin() != base.origin() {
+            return Err(OrbitError::Operation(format!(
+                "{location} is not on {}",
+                self.base_url
+            )));
+        }
+        let path = url.as_str();
+        let deadline = timeout.map(|t| std::time::Instant::now() + t);
+        let status = |op: &Value| {
+            op["status"]
+                .as_str()
+                .or(op["state"].as_str())
+                .map(str::to_ascii_lowercase)
+        };
+
+        let spinner = crate::output::progress_bar("wait-operation", None);
+        spinner.set_style(
+            indicatif::ProgressStyle::default_spinner()
+                .template("{spinner:.cyan} Waiting for {msg} ({elapsed})...")
+                .unwrap(),
+        );
+        spinner.enable_steady_tick(Duration::from_millis(80));
+        let result = loop {
+            let op = match self.get(path).await {
+                Ok(op) => op,
+                Err(e) => break Err(e),
+            };
+            let Some(state) = status(&op) else {
+                break Ok(op);
+            };
+            if OPERATION_DONE.contains(&state.as_str()) {
+                break Ok(op);
+            }
+            if OPERATION_FAILED.contains(&state.as_str()) {
+                let error = op["last_error"]
+                    .as_str()
+                    .or(op["error"].as_str())
+                    .unwrap_or("no error reported");
+                break Err(OrbitError::Operation(format!("{path} {state}: {error}")));
+            }
+            if OPERATION_PAUSED.contains(&state.as_str()) {
+                break Err(OrbitError::Operation(format!(
+                    "{path} is {state}; resume it and wait again"
+                )));
+            }
+            if deadline.is_some_and(|d| std::time::Instant::now() >= d) {
+                break Err(OrbitError::Timeout(format!(
+                    "operation {path} still {state}"
+                )));
+            }
+            spinner.set_message(format!("{path} ({state})"));
+            tokio::time::sleep(OPERATION_POLL_INTERVAL).await;
+        };
+        spinner.finish_and_clear();
+        result
+    }
+
+    /// GETs `path`, returning the body and any `Link: rel="next"` target.
+    async fn get_with_link(&self, path: &str) -> Result<(Value, Option<String>)> {
+        let resp = self.send(self.request(Method::GET, path)).await?;
+        let link = resp
+            .headers()
+            .get(reqwest::header::LINK)
+            .and_then(|v| v.to_str().ok())
+            .and_then(next_link);
+        let body = self.response_text(resp).await?;
+        Ok((Self::parse_body(&body)?, link))
+    }
+
     pub async fn get(&self, path: &str) -> Result<Value> {
-        let resp = self.build_request(Method::GET, path).send().await?;
-        Self::handle_response(resp).await
+        Ok(self.get_with_link(path).await?.0)
+    }
+
+    /// Fetches a list endpoint, unwrapping `{ "items": [...] }` envelopes.
+    /// Follows every page only when the client was built with `fetch_all`.
+    pub async fn get_paged(&self, path: &str) -> Result<Value> {
+        self.fetch_pages(path, self.fetch_all).await
+    }
+
+    /// Fetches every page of a list endpoint regardless of `fetch_all`.
+    pub async fn get_all(&self, path: &str) -> Result<Value> {
+        self.fetch_pages(path, true).await
+    }
+
+    /// Subsequent pages are located by the `Link: rel="next"` header, a
+    /// `next_cursor` field, or `pagination.next_offset`, in that order.
+    async fn fetch_pages(&self, path: &str, follow: bool) -> Result<Value> {
+        let mut items = Vec::new();
+        let mut next = Some(path.to_string());
+        let mut pages = 0;
+
+        while let Some(current) = next.take() {
+            let (page, link) = self.get_with_link(&current).await?;
+
+            let (page_items, cursor, offset) = match page {
+                Value::Object(mut map) if map.contains_key("items") => {
+                    let cursor = map
+                        .get("next_cursor")
+                        .and_then(|v| v.as_str())
+                        .filter(|c| !c.is_empty())
+                        .map(String::from);
+                    let offset = map
+                        .get("pagination")
+                        .and_then(|p| p.get("next_offset"))
+                        .and_then(|v| v.as_u64());
+                    (map.remove("items").unwrap_or(Value::Null), cursor, offset)
+                }
+                other => (other, None, None),
+            };
+            match page_items {
+                Value::Array(arr) => items.extend(arr),
+                Value::Null => {}
+                other if pages == 0 && !follow => return Ok(other),
+                other => items.push(other),
+            }
+
+            pages += 1;
+            if !follow || pages >= MAX_PAGES {
+                break;
+            }
+            next = if let Some(link) = link {
+                Some(
+                    link.strip_prefix(&self.base_url)
+                        .unwrap_or(&link)
+                        .to_string(),
+                )
+            } else if let Some(cursor) = cursor {
+                Some(with_query_param(path, "cursor", &cursor))
+            } else {
+                offset.map(|o| with_query_param(path, "offset", &o.to_string()))
+            };
+        }
+
+        Ok(Value::Array(items))
+    }
+
+    /// Opens a Server-Sent Events stream on `path`.
+    pub async fn stream(&self, path: &str) -> Result<EventStream> {
+        let req = self
+            .request(Method::GET, path)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+        let resp = self.send_untimed(req).await?;
+        if resp.status().as_u16() >= 400 {
+            return Err(self.api_error(resp).await);
+        }
+        Ok(EventStream {
+            resp,
+            buffer: Vec::new(),
+        })
     }
 
     pub async fn post(&self, path: &str, body: &Value) -> Result<Value> {
-        let resp = self
-            .build_request(Method::POST, path)
-            .json(body)
-            .send()
-            .await?;
-        Self::handle_response(resp).await
+        let req = self.request(Method::POST, path).json(body);
+        let resp = self.send(req).await?;
+        self.handle_response(resp).await
+    }
+
+    /// GETs `path` and returns the server's `Date` header, for clock skew
+    /// checks.
+    pub async fn server_date(&self, path: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        let resp = self.send(self.request(Method::GET, path)).await?;
+        if resp.status().as_u16() >= 400 {
+            return Err(self.api_error(resp).await);
+        }
+        Ok(resp
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| chrono::DateTime::parse_from_rfc2822(s).ok())
+            .map(|t| t.with_timezone(&chrono::Utc)))
+    }
+
+    /// GETs `path` as raw bytes, for binary downloads.
+    pub async fn get_bytes(&self, path: &str) -> Result<Vec<u8>> {
+        let resp = self.send(self.request(Method::GET, path)).await?;
+        if resp.status().as_u16() >= 400 {
+            return Err(self.api_error(resp).await);
+        }
+        Ok(resp.bytes().await?.to_vec())
+    }
+
+    /// POSTs a multipart form. File parts may stream their contents, in which
+    /// case the request is not retried on 429.
+    pub async fn post_multipart(
+        &self,
+        path: &str,
+        form: reqwest::multipart::Form,
+    ) -> Result<Value> {
+        let req = self.request(Method::POST, path).multipart(form);
+        let resp = self.send_untimed(req).await?;
+        self.handle_response(resp).await
+    }
+
+    /// PUTs a multipart form.
+    pub async fn put_multipart(&self, path: &str, form: reqwest::multipart::Form) -> Result<Value> {
+        let req = self.request(Method::PUT, path).multipart(form);
+        let resp = self.send_untimed(req).await?;
+        self.handle_response(resp).await
+    }
+
+    /// Sends `body` as JSON, gzip-compressed with `Content-Encoding: gzip`
+    /// under `--compress` once it is large enough for compression to pay
+    /// off. A server answering 415 to the compressed body gets it again
+    /// uncompressed.
+    async fn send_compressed(&self, method: Method, path: &str, body: &Value) -> Result<Value> {
+        let json = serde_json::to_vec(body)?;
+        if self.compress && json.len() >= COMPRESS_MIN_BYTES {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&json)?;
+            let req = self
+                .request(method.clone(), path)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                .body(encoder.finish()?);
+            let resp = self.send(req).await?;
+            if resp.status() != reqwest::StatusCode::UNSUPPORTED_MEDIA_TYPE {
+                return self.handle_response(resp).await;
+            }
+        }
+        let req = self
+            .request(method, path)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(json);
+        let resp = self.send(req).await?;
+        self.handle_response(resp).await
+    }
+
+    pub async fn post_compressed(&self, path: &str, body: &Value) -> Result<Value> {
+        self.send_compressed(Method::POST, path, body).await
+    }
+
+    pub async fn patch_compressed(&self, path: &str, body: &Value) -> Result<Value> {
+        self.send_compressed(Method::PATCH, path, body).await
+    }
+
+    pub async fn put_compressed(&self, path: &str, body: &Value) -> Result<Value> {
+        self.send_compressed(Method::PUT, path, body).await
     }
 
     pub async fn patch(&self, path: &str, body: &Value) -> Result<Value> {
-        let resp = self
-            .build_request(Method::PATCH, path)
-            .json(body)
-            .send()
-            .await?;
-        Self::handle_response(resp).await
+        let req = self.request(Method::PATCH, path).json(body);
+        let resp = self.send(req).await?;
+        self.handle_response(resp).await
     }
 
     pub async fn put(&self, path: &str, body: &Value) -> Result<Value> {
-        let resp = self
-            .build_request(Method::PUT, path)
-            .json(body)
-            .send()
-            .await?;
-        Self::handle_response(resp).await
+        let req = self.request(Method::PUT, path).json(body);
+        let resp = self.send(req).await?;
+        self.handle_response(resp).await
+    }
+
+    /// Runs `f` for every item with at most `concurrency` requests in
+    /// flight. Each item is returned with its own result, in input order, so
+    /// one failure does not abort the rest.
+    pub async fn batch<'a, T, F, Fut>(
+        &'a self,
+        items: impl IntoIterator<Item = T>,
+        concurrency: usize,
+        f: F,
+    ) -> Vec<(T, Result<Value>)>
+    where
+        T: Clone,
+        F: Fn(&'a NovaClient, T) -> Fut,
+        Fut: Future<Output = Result<Value>>,
+    {
+        futures_util::stream::iter(items)
+            .map(|item| {
+                let request = f(self, item.clone());
+                async move { (item, request.await) }
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
     }
 
     pub async fn delete(&self, path: &str) -> Result<Value> {
-        let resp = self.build_request(Method::DELETE, path).send().await?;
-        Self::handle_response(resp).await
+        let resp = self.send(self.request(Method::DELETE, path)).await?;
+        self.handle_response(resp).await
     }
 }
@@ -42,12 +42,12 @@ pub async fn run(cmd: AiCmd, client: &NovaClient, output_format: &str) -> Result
         } => {
             let body = json!({ "function_name": function_name, "prompt": prompt });
             let result = client.post("/ai/generate", &body).await?;
-            output::render(&result, &[], "json");
+            output::render(&result, &[], "json")?;
         }
         AiCmd::Review { function_name } => {
             let body = json!({ "function_name": function_name });
             let result = client.post("/ai/review", &body).await?;
-            output::render(&result, &[], "json");
+            output::render(&result, &[], "json")?;
         }
         AiCmd::Rewrite {
             function_name,
@@ -55,17 +55,17 @@ pub async fn run(cmd: AiCmd, client: &NovaClient, output_format: &str) -> Result
         } => {
             let body = json!({ "function_name": function_name, "instructions": instructions });
             let result = client.post("/ai/rewrite", &body).await?;
-            output::render(&result, &[], "json");
+            output::render(&result, &[], "json")?;
         }
         AiCmd::GenerateDocs { function_name } => {
             let body = json!({ "function_name": function_name });
             let result = client.post("/ai/generate-docs", &body).await?;
-            output::render(&result, &[], "json");
+            output::render(&result, &[], "json")?;
         }
         AiCmd::GenerateWorkflowDocs { workflow_name } => {
             let body = json!({ "workflow_name": workflow_name });
             let result = client.post("/ai/generate-workflow-docs", &body).await?;
-            output::render(&result, &[], "json");
+            output::render(&result, &[], "json")?;
         }
         AiCmd::Status => {
             let result = client.get("/ai/status").await?;
@@ -77,11 +77,11 @@ pub async fn run(cmd: AiCmd, client: &NovaClient, output_format: &str) -> Result
                     Column::new("Model", "model"),
                 ],
                 output_format,
-            );
+            )?;
         }
         AiCmd::Models => {
             let result = client.get("/ai/models").await?;
-            output::render(&result, MODEL_COLUMNS, output_format);
+            output::render(&result, MODEL_COLUMNS, output_format)?;
         }
     }
     Ok(())
@@ -0,0 +1,166 @@
+//! `orbit functions aliases`: named pointers such as `prod` to a version,
+//! optionally splitting traffic between two versions by weight. Invoke an
+//! alias with `functions invoke --alias` or route to one from a gateway
+//! route with `--alias`.
+
+use crate::client::NovaClient;
+use crate::commands::functions::AliasesSubCmd;
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use serde_json::{Value, json};
+
+const ALIAS_COLUMNS: &[Column] = &[
+    Column::new("Alias", "name"),
+    Column::new("Routes To", "routing"),
+    Column::new("Updated", "updated_at"),
+    Column::wide("Created", "created_at"),
+];
+
+/// Parses `--split V=PCT`: the share of traffic, in percent, sent to
+/// version `V` instead of the alias's main version.
+fn parse_split(input: &str) -> Result<(u32, u32)> {
+    let invalid = || {
+        OrbitError::Input(format!(
+            "Invalid --split '{input}'. Use VERSION=PERCENT, e.g. 4=10"
+        ))
+    };
+    let (version, percent) = input.split_once('=').ok_or_else(invalid)?;
+    let version: u32 = version.trim().parse().map_err(|_| invalid())?;
+    let percent: u32 = percent
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| invalid())?;
+    if !(1..=99).contains(&percent) {
+        return Err(OrbitError::Input(
+            "--split percent must be between 1 and 99".into(),
+        ));
+    }
+    Ok((version, percent))
+}
+
+/// The request body pointing an alias at `version`, with `split` of the
+/// traffic going to another version.
+pub fn target(version: u32, split: Option<(u32, u32)>) -> Result<Value> {
+    match split {
+        None => Ok(json!({ "version": version })),
+        Some((other, _)) if other == version => Err(OrbitError::Input(format!(
+            "--split must name a version other than v{version}"
+        ))),
+        Some((other, percent)) => {
+            let mut split = serde_json::Map::new();
+            split.insert(version.to_string(), json!(100 - percent));
+            split.insert(other.to_string(), json!(percent));
+            Ok(json!({ "traffic_split": split }))
+        }
+    }
+}
+
+/// The version an alias mainly routes to: its single version, or the one
+/// with the larger share of a split.
+pub fn main_version(alias: &Value) -> Option<u32> {
+    if let Some(v) = alias["version"].as_u64().filter(|v| *v > 0) {
+        return Some(v as u32);
+    }
+    alias["traffic_split"]
+        .as_object()?
+        .iter()
+        .max_by_key(|(_, share)| share.as_u64())
+        .and_then(|(v, _)| v.parse().ok())
+}
+
+/// Adds `routing`, e.g. `v3` or `v3 90%, v4 10%`.
+fn with_routing(mut alias: Value) -> Value {
+    let routing = match alias["traffic_split"].as_object().filter(|s| !s.is_empty()) {
+        Some(split) => {
+            let mut shares: Vec<(u64, u64)> = split
+                .iter()
+                .filter_map(|(v, share)| Some((v.parse().ok()?, share.as_u64()?)))
+                .collect();
+            shares.sort_unstable();
+            shares
+                .iter()
+                .map(|(v, share)| format!("v{v} {share}%"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+        None => match alias["version"].as_u64() {
+            Some(v) => format!("v{v}"),
+            None => "-".to_string(),
+        },
+    };
+    alias["routing"] = json!(routing);
+    alias
+}
+
+pub async fn run(cmd: AliasesSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    match cmd {
+        AliasesSubCmd::Create {
+            name,
+            alias,
+            version,
+            split,
+        } => {
+            let mut body = target(version, split.as_deref().map(parse_split).transpose()?)?;
+            body["name"] = json!(alias);
+            let result = client
+                .post(&format!("/functions/{name}/aliases"), &body)
+                .await?;
+            output::render_single(&with_routing(result), ALIAS_COLUMNS, output_format)?;
+        }
+        AliasesSubCmd::Update {
+            name,
+            alias,
+            version,
+            split,
+            no_split,
+        } => {
+            if version.is_none() && split.is_none() && !no_split {
+                return Err(OrbitError::Input(
+                    "Nothing to update. Set --version, --split or --no-split".into(),
+                ));
+            }
+            let path = format!("/functions/{name}/aliases/{alias}");
+            let current = client.get(&path).await?;
+            let main = main_version(&current);
+            let version = version.or(main).ok_or_else(|| {
+                OrbitError::Input(format!("Alias '{alias}' has no version; set --version"))
+            })?;
+            // Keep an existing split unless it is replaced or dropped, or its
+            // version is the one being promoted to main.
+            let split = match split {
+                Some(s) => Some(parse_split(&s)?),
+                None if no_split => None,
+                None => current["traffic_split"]
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|(v, share)| Some((v.parse().ok()?, share.as_u64()? as u32)))
+                    .find(|(v, _)| Some(*v) != main)
+                    .filter(|(v, _)| *v != version),
+            };
+            let mut body = target(version, split)?;
+            body["name"] = json!(alias);
+            let result = client.put(&path, &body).await?;
+            output::render_single(&with_routing(result), ALIAS_COLUMNS, output_format)?;
+        }
+        AliasesSubCmd::List { name } => {
+            let mut result = client
+                .get_paged(&format!("/functions/{name}/aliases"))
+                .await?;
+            if let Some(aliases) = result.as_array_mut() {
+                for alias in aliases {
+                    *alias = with_routing(alias.take());
+                }
+            }
+            output::render(&result, ALIAS_COLUMNS, output_format)?;
+        }
+        AliasesSubCmd::Delete { name, alias } => {
+            client
+                .delete(&format!("/functions/{name}/aliases/{alias}"))
+                .await?;
+            output::print_success(&format!("Alias '{alias}' of '{name}' deleted."));
+        }
+    }
+    Ok(())
+}
@@ -2,7 +2,7 @@ use crate::client::NovaClient;
 use crate::error::Result;
 use crate::output::{self, Column};
 use clap::Subcommand;
-use serde_json::json;
+use serde_json::{Value, json};
 
 #[derive(Subcommand)]
 pub enum ApiKeysCmd {
@@ -12,9 +12,41 @@ pub enum ApiKeysCmd {
         name: String,
         #[arg(long)]
         scopes: Vec<String>,
+        /// Only accept requests from this CIDR range (repeatable)
+        #[arg(long = "allowed-cidr")]
+        allowed_cidrs: Vec<String>,
+        /// Only accept requests with this Referer (repeatable)
+        #[arg(long = "allowed-referer")]
+        allowed_referers: Vec<String>,
     },
     /// List API keys
-    List,
+    List {
+        /// List keys of another tenant (admin only)
+        #[arg(long = "tenant-id")]
+        tenant_id: Option<String>,
+        /// Show when each key was last used
+        #[arg(long)]
+        show_last_used: bool,
+        /// Only show keys not used for this long (e.g. 90d)
+        #[arg(long)]
+        unused_for: Option<String>,
+    },
+    /// Report keys that violate common hygiene rules
+    Audit {
+        /// Audit keys of another tenant (admin only)
+        #[arg(long = "tenant-id")]
+        tenant_id: Option<String>,
+        /// Inactivity threshold (e.g. 90d)
+        #[arg(long, default_value = "90d")]
+        inactive_for: String,
+    },
+    /// Show request counts by endpoint, error rates, and client IPs for a key
+    Usage {
+        id: String,
+        /// Time range (e.g. 24h, 7d)
+        #[arg(long, default_value = "7d")]
+        range: String,
+    },
     /// Delete an API key
     Delete { id: String },
     /// Update an API key
@@ -24,6 +56,12 @@ pub enum ApiKeysCmd {
         name: Option<String>,
         #[arg(long)]
         scopes: Vec<String>,
+        /// Replace the allowed CIDR ranges (repeatable)
+        #[arg(long = "allowed-cidr")]
+        allowed_cidrs: Vec<String>,
+        /// Replace the allowed Referers (repeatable)
+        #[arg(long = "allowed-referer")]
+        allowed_referers: Vec<String>,
     },
 }
 
@@ -32,28 +70,229 @@ const APIKEY_COLUMNS: &[Column] = &[
     Column::new("Name", "name"),
     Column::new("Key", "key"),
     Column::new("Scopes", "scopes"),
+    Column::wide("Allowed CIDRs", "allowed_cidrs"),
+    Column::wide("Allowed Referers", "allowed_referers"),
     Column::new("Created", "created_at"),
 ];
 
+const APIKEY_LAST_USED_COLUMNS: &[Column] = &[
+    Column::new("ID", "id"),
+    Column::new("Name", "name"),
+    Column::new("Scopes", "scopes"),
+    Column::new("Last Used", "last_used_at"),
+    Column::new("Expires", "expires_at"),
+    Column::new("Created", "created_at"),
+];
+
+const AUDIT_COLUMNS: &[Column] = &[
+    Column::new("ID", "id"),
+    Column::new("Name", "name"),
+    Column::new("Findings", "findings"),
+    Column::wide("Last Used", "last_used_at"),
+    Column::wide("Expires", "expires_at"),
+];
+
+const USAGE_SUMMARY_COLUMNS: &[Column] = &[
+    Column::new("Key", "key_id"),
+    Column::new("Range", "range"),
+    Column::new("Requests", "total_requests"),
+    Column::new("Errors", "total_errors"),
+    Column::new("Error Rate", "error_rate"),
+    Column::new("Last Used", "last_used_at"),
+];
+
+const USAGE_ENDPOINT_COLUMNS: &[Column] = &[
+    Column::new("Method", "method"),
+    Column::new("Endpoint", "endpoint"),
+    Column::new("Requests", "requests"),
+    Column::new("Errors", "errors"),
+    Column::new("Error Rate", "error_rate"),
+];
+
+const USAGE_IP_COLUMNS: &[Column] = &[
+    Column::new("IP", "ip"),
+    Column::new("Requests", "requests"),
+    Column::new("First Seen", "first_seen_at"),
+    Column::new("Last Seen", "last_seen_at"),
+];
+
+fn validate_cidr(cidr: &str) -> Result<()> {
+    let (addr, prefix) = cidr.split_once('/').unwrap_or((cidr, ""));
+    let invalid = || crate::error::OrbitError::Input(format!("Invalid CIDR '{cidr}'"));
+    let ip: std::net::IpAddr = addr.parse().map_err(|_| invalid())?;
+    if !prefix.is_empty() {
+        let bits: u8 = prefix.parse().map_err(|_| invalid())?;
+        let max = if ip.is_ipv4() { 32 } else { 128 };
+        if bits > max {
+            return Err(invalid());
+        }
+    }
+    Ok(())
+}
+
+fn apply_restrictions(
+    body: &mut Value,
+    allowed_cidrs: Vec<String>,
+    allowed_referers: Vec<String>,
+) -> Result<()> {
+    if !allowed_cidrs.is_empty() {
+        for cidr in &allowed_cidrs {
+            validate_cidr(cidr)?;
+        }
+        body["allowed_cidrs"] = json!(allowed_cidrs);
+    }
+    if !allowed_referers.is_empty() {
+        body["allowed_referers"] = json!(allowed_referers);
+    }
+    Ok(())
+}
+
+fn keys_path(tenant_id: Option<String>) -> String {
+    match tenant_id {
+        Some(t) => format!("/api-keys?tenant_id={t}"),
+        None => "/api-keys".to_string(),
+    }
+}
+
+fn unused_since(key: &Value, cutoff: chrono::DateTime<chrono::Utc>) -> bool {
+    crate::duration::timestamp(key, "last_used_at").is_none_or(|t| t < cutoff)
+}
+
+fn audit_findings(key: &Value, cutoff: chrono::DateTime<chrono::Utc>) -> Vec<&'static str> {
+    let mut findings = Vec::new();
+    if key.get("expires_at").is_none_or(|v| v.is_null()) {
+        findings.push("no expiry");
+    }
+    let wildcard = key
+        .get("scopes")
+        .and_then(|s| s.as_array())
+        .is_some_and(|scopes| {
+            scopes
+                .iter()
+                .filter_map(|s| s.as_str())
+                .any(|s| s == "*" || s.ends_with(":*"))
+        });
+    if wildcard {
+        findings.push("wildcard scope");
+    }
+    if unused_since(key, cutoff) {
+        findings.push("inactive");
+    }
+    findings
+}
+
 pub async fn run(cmd: ApiKeysCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
-        ApiKeysCmd::Create { name, scopes } => {
+        ApiKeysCmd::Create {
+            name,
+            scopes,
+            allowed_cidrs,
+            allowed_referers,
+        } => {
             let mut body = json!({ "name": name });
             if !scopes.is_empty() {
                 body["scopes"] = json!(scopes);
             }
+            let unrestricted = allowed_cidrs.is_empty() && allowed_referers.is_empty();
+            apply_restrictions(&mut body, allowed_cidrs, allowed_referers)?;
             let result = client.post("/api-keys", &body).await?;
-            output::render_single(&result, APIKEY_COLUMNS, output_format);
+            output::render_single(&result, APIKEY_COLUMNS, output_format)?;
+            if unrestricted {
+                output::print_warning(
+                    "This key has no IP or Referer restrictions; consider --allowed-cidr or --allowed-referer.",
+                );
+            }
+        }
+        ApiKeysCmd::List {
+            tenant_id,
+            show_last_used,
+            unused_for,
+        } => {
+            let mut result = client.get_paged(&keys_path(tenant_id)).await?;
+            if let Some(age) = unused_for {
+                let cutoff = crate::duration::ago(crate::duration::parse(&age)?)?;
+                if let Some(keys) = result.as_array_mut() {
+                    keys.retain(|k| unused_since(k, cutoff));
+                }
+            }
+            let columns = if show_last_used {
+                APIKEY_LAST_USED_COLUMNS
+            } else {
+                APIKEY_COLUMNS
+            };
+            output::render(&result, columns, output_format)?;
         }
-        ApiKeysCmd::List => {
-            let result = client.get("/api-keys").await?;
-            output::render(&result, APIKEY_COLUMNS, output_format);
+        ApiKeysCmd::Audit {
+            tenant_id,
+            inactive_for,
+        } => {
+            let cutoff = crate::duration::ago(crate::duration::parse(&inactive_for)?)?;
+            let keys = client.get_all(&keys_path(tenant_id)).await?;
+            let keys = keys.as_array().map(Vec::as_slice).unwrap_or_default();
+            let flagged: Vec<Value> = keys
+                .iter()
+                .filter_map(|k| {
+                    let findings = audit_findings(k, cutoff);
+                    (!findings.is_empty()).then(|| {
+                        let mut row = k.clone();
+                        row["findings"] = json!(findings);
+                        row
+                    })
+                })
+                .collect();
+            let count = |finding: &str| {
+                flagged
+                    .iter()
+                    .filter(|k| {
+                        k["findings"]
+                            .as_array()
+                            .is_some_and(|f| f.iter().any(|v| v == finding))
+                    })
+                    .count()
+            };
+            let summary = format!(
+                "{} of {} keys flagged: {} without expiry, {} with wildcard scopes, {} inactive for {inactive_for}",
+                flagged.len(),
+                keys.len(),
+                count("no expiry"),
+                count("wildcard scope"),
+                count("inactive"),
+            );
+            output::render(&Value::Array(flagged), AUDIT_COLUMNS, output_format)?;
+            if !output::is_machine_readable(output_format) {
+                output::print_line(&summary);
+            }
+        }
+        ApiKeysCmd::Usage { id, range } => {
+            crate::duration::parse(&range)?;
+            let result = client
+                .get(&format!("/api-keys/{id}/usage?range={range}"))
+                .await?;
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, &[], output_format)?;
+            } else {
+                output::render_single(&result, USAGE_SUMMARY_COLUMNS, output_format)?;
+                let section = |field: &str| match &result[field] {
+                    Value::Null => json!([]),
+                    v => v.clone(),
+                };
+                output::print_line("\nRequests by endpoint:");
+                output::render(&section("endpoints"), USAGE_ENDPOINT_COLUMNS, output_format)?;
+                output::print_line("\nClient IPs:");
+                output::render(&section("ips"), USAGE_IP_COLUMNS, output_format)?;
+            }
         }
         ApiKeysCmd::Delete { id } => {
             client.delete(&format!("/api-keys/{id}")).await?;
             output::print_success(&format!("API key '{id}' revoked."));
         }
-        ApiKeysCmd::Update { id, name, scopes } => {
+        ApiKeysCmd::Update {
+            id,
+            name,
+            scopes,
+            allowed_cidrs,
+            allowed_referers,
+        } => {
             let mut body = json!({});
             if let Some(n) = name {
                 body["name"] = json!(n);
@@ -61,8 +300,9 @@ pub async fn run(cmd: ApiKeysCmd, client: &NovaClient, output_format: &str) -> R
             if !scopes.is_empty() {
                 body["scopes"] = json!(scopes);
             }
+            apply_restrictions(&mut body, allowed_cidrs, allowed_referers)?;
             let result = client.patch(&format!("/api-keys/{id}"), &body).await?;
-            output::render_single(&result, APIKEY_COLUMNS, output_format);
+            output::render_single(&result, APIKEY_COLUMNS, output_format)?;
         }
     }
     Ok(())
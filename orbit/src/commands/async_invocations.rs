@@ -1,19 +1,44 @@
 use crate::client::NovaClient;
 use crate::commands::functions::AsyncInvocationsSubCmd;
-use crate::error::Result;
+use crate::error::{OrbitError, Result};
 use crate::output::{self, Column};
+use chrono::{DateTime, Utc};
 use clap::Subcommand;
+use indicatif::ProgressStyle;
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
 
 const ASYNC_COLUMNS: &[Column] = &[
     Column::new("ID", "id"),
     Column::new("Function", "function_name"),
     Column::new("Status", "status"),
     Column::new("Attempts", "max_attempts"),
+    Column::new("Priority", "priority"),
+    Column::new("Run At", "next_run_at"),
     Column::wide("Idempotency Key", "idempotency_key"),
     Column::new("Created", "created_at"),
     Column::wide("Updated", "updated_at"),
+    Column::wide("Callback URL", "callback_url"),
 ];
 
+const BACKLOG_COLUMNS: &[Column] = &[
+    Column::new("Timestamp", "timestamp"),
+    Column::new("Queued", "queued"),
+    Column::new("Processing", "processing"),
+    Column::new("Failed", "failed"),
+];
+
+const TREND_COLUMNS: &[Column] = &[
+    Column::new("Series", "series"),
+    Column::new("Trend", "trend"),
+    Column::new("Min", "min"),
+    Column::new("Max", "max"),
+    Column::new("Now", "now"),
+];
+
+/// Samples kept for the trend line in `backlog --watch`.
+const WATCH_HISTORY: usize = 40;
+
 #[derive(Subcommand)]
 pub enum GlobalAsyncCmd {
     /// List all async invocations
@@ -27,6 +52,267 @@ pub enum GlobalAsyncCmd {
     Get { id: String },
     /// Retry a failed async invocation
     Retry { id: String },
+    /// Print the output of a completed async invocation
+    Result {
+        id: String,
+        /// Write the output to a file instead of stdout
+        #[arg(long)]
+        out: Option<String>,
+        /// Wait for the invocation to finish first
+        #[arg(long)]
+        follow: bool,
+        /// Give up waiting after this long (e.g. 30s, 10m)
+        #[arg(long, requires = "follow")]
+        max_wait: Option<String>,
+    },
+    /// Show queued, processing and failed counts over time with a trend line
+    Backlog {
+        /// Only count invocations of this function
+        #[arg(long)]
+        function: Option<String>,
+        /// Time range to reconstruct (e.g. 1h, 6h, 1d)
+        #[arg(long, default_value = "1h")]
+        range: String,
+        /// Points on the timeline
+        #[arg(long, default_value_t = 30)]
+        points: usize,
+        /// Keep sampling the current counts, printing a line per sample
+        #[arg(long)]
+        watch: bool,
+        /// Sampling interval for --watch (e.g. 5s, 1m)
+        #[arg(long, default_value = "10s")]
+        interval: String,
+    },
+}
+
+/// Fetches invocation `id`, polling until it leaves the queue when `follow`
+/// is set.
+async fn wait_for_result(
+    client: &NovaClient,
+    id: &str,
+    follow: bool,
+    max_wait: Option<&str>,
+) -> Result<Value> {
+    let path = format!("/async-invocations/{id}");
+    let deadline = match max_wait {
+        // Waits too long to represent never time out.
+        Some(w) => {
+            Instant::now().checked_add(crate::duration::parse(w)?.to_std().unwrap_or_default())
+        }
+        None => None,
+    };
+    let mut invocation = client.get(&path).await?;
+    let status = |inv: &Value| inv["status"].as_str().unwrap_or("unknown").to_string();
+    if matches!(status(&invocation).as_str(), "succeeded" | "dlq") {
+        return Ok(invocation);
+    }
+    if !follow {
+        return Err(OrbitError::Input(format!(
+            "Invocation {id} is {}; use --follow to wait for it",
+            status(&invocation)
+        )));
+    }
+
+    let spinner = output::progress_bar("wait-invocation", None);
+    spinner.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.cyan} Waiting for {msg} ({elapsed})...")
+            .unwrap(),
+    );
+    spinner.set_message(format!("{id} ({})", status(&invocation)));
+    spinner.enable_steady_tick(Duration::from_millis(80));
+    while !matches!(status(&invocation).as_str(), "succeeded" | "dlq") {
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            spinner.finish_and_clear();
+            return Err(OrbitError::Timeout(format!(
+                "invocation {id} still {} after {}",
+                status(&invocation),
+                max_wait.unwrap_or_default()
+            )));
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        invocation = client.get(&path).await?;
+        spinner.set_message(format!("{id} ({})", status(&invocation)));
+    }
+    spinner.finish_and_clear();
+    Ok(invocation)
+}
+
+/// Queue depths at one point in time.
+#[derive(Clone, Copy, Default)]
+struct Depth {
+    queued: u64,
+    processing: u64,
+    failed: u64,
+}
+
+/// Reconstructs the depths at `at` from invocation timestamps: queued from
+/// creation until started, processing until completed, and failed (in the
+/// DLQ) from the last update. Retries make this approximate.
+fn depth_at(invocations: &[Value], at: DateTime<Utc>) -> Depth {
+    let ts = |inv: &Value, field: &str| crate::duration::timestamp(inv, field);
+    let mut depth = Depth::default();
+    for inv in invocations {
+        let Some(created) = ts(inv, "created_at") else {
+            continue;
+        };
+        if created > at {
+            continue;
+        }
+        let status = inv["status"].as_str().unwrap_or_default();
+        let started = ts(inv, "started_at");
+        let completed = ts(inv, "completed_at");
+        if status == "dlq" && ts(inv, "updated_at").is_some_and(|t| t <= at) {
+            depth.failed += 1;
+        } else if started.is_none_or(|s| s > at) {
+            if started.is_some() || matches!(status, "queued" | "paused") {
+                depth.queued += 1;
+            }
+        } else if completed.is_none_or(|c| c > at) && (completed.is_some() || status == "running") {
+            depth.processing += 1;
+        }
+    }
+    depth
+}
+
+/// Current depths: the server summary, or a count over the function's
+/// invocations.
+async fn current_depth(client: &NovaClient, function: Option<&str>) -> Result<Depth> {
+    let count = |v: &Value| v.as_u64().unwrap_or(0);
+    match function {
+        None => {
+            let summary = client.get("/async-invocations/summary").await?;
+            Ok(Depth {
+                queued: count(&summary["queued"]),
+                processing: count(&summary["running"]),
+                failed: count(&summary["dlq"]),
+            })
+        }
+        Some(name) => {
+            let invocations = client
+                .get_all(&format!("/functions/{name}/async-invocations"))
+                .await?;
+            Ok(depth_at(
+                invocations
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                Utc::now(),
+            ))
+        }
+    }
+}
+
+fn trend_rows(history: &[Depth]) -> Value {
+    ["queued", "processing", "failed"]
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let values: Vec<u64> = history
+                .iter()
+                .map(|d| [d.queued, d.processing, d.failed][i])
+                .collect();
+            let scaled: Vec<f64> = values.iter().map(|v| *v as f64).collect();
+            json!({
+                "series": name,
+                "trend": output::sparkline(&scaled),
+                "min": values.iter().min(),
+                "max": values.iter().max(),
+                "now": values.last(),
+            })
+        })
+        .collect()
+}
+
+async fn run_backlog(
+    client: &NovaClient,
+    function: Option<String>,
+    range: &str,
+    points: usize,
+    watch: bool,
+    interval: &str,
+    output_format: &str,
+) -> Result<()> {
+    if watch {
+        let every = crate::duration::parse(interval)?
+            .to_std()
+            .ok()
+            .filter(|d| !d.is_zero())
+            .ok_or_else(|| OrbitError::Input("Interval must be positive".into()))?;
+        let mut history: Vec<Depth> = Vec::new();
+        output::print_status(&format!("Sampling every {interval}. Press Ctrl-C to stop."));
+        loop {
+            match current_depth(client, function.as_deref()).await {
+                Ok(depth) => {
+                    history.push(depth);
+                    if history.len() > WATCH_HISTORY {
+                        history.remove(0);
+                    }
+                    let queued: Vec<f64> = history.iter().map(|d| d.queued as f64).collect();
+                    println!(
+                        "{}  queued {:>6}  processing {:>5}  failed {:>5}  {}",
+                        chrono::Local::now().format("%H:%M:%S"),
+                        depth.queued,
+                        depth.processing,
+                        depth.failed,
+                        output::sparkline(&queued)
+                    );
+                }
+                Err(e) => output::print_warning(&format!("Sample failed: {e}")),
+            }
+            tokio::time::sleep(every).await;
+        }
+    }
+
+    let window = crate::duration::parse(range)?;
+    if window.num_seconds() <= 0 {
+        return Err(OrbitError::Input("Range must be positive".into()));
+    }
+    let points = points.max(2);
+    let path = match &function {
+        Some(name) => format!("/functions/{name}/async-invocations"),
+        None => "/async-invocations".to_string(),
+    };
+    let invocations = client.get_all(&path).await?;
+    let invocations = invocations
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let start = crate::duration::ago(window)?;
+    let step = window / (points as i32 - 1);
+    let timeline: Vec<(DateTime<Utc>, Depth)> = (0..points)
+        .map(|i| {
+            let at = start + step * i as i32;
+            (at, depth_at(invocations, at))
+        })
+        .collect();
+
+    if output::is_machine_readable(output_format) {
+        let rows: Vec<Value> = timeline
+            .iter()
+            .map(|(at, d)| {
+                json!({
+                    "timestamp": at.to_rfc3339(),
+                    "queued": d.queued,
+                    "processing": d.processing,
+                    "failed": d.failed,
+                })
+            })
+            .collect();
+        output::render(&Value::Array(rows), BACKLOG_COLUMNS, output_format)?;
+        return Ok(());
+    }
+    let history: Vec<Depth> = timeline.iter().map(|(_, d)| *d).collect();
+    output::render(&trend_rows(&history), TREND_COLUMNS, output_format)?;
+    if !output::is_machine_readable(output_format) {
+        output::print_line(&format!(
+            "Last {range} in {points} points, reconstructed from {} invocation(s){}.",
+            invocations.len(),
+            function.map(|f| format!(" of '{f}'")).unwrap_or_default()
+        ));
+    }
+    Ok(())
 }
 
 pub async fn run_fn(
@@ -51,8 +337,8 @@ pub async fn run_fn(
             if !params.is_empty() {
                 path = format!("{}?{}", path, params.join("&"));
             }
-            let result = client.get(&path).await?;
-            output::render(&result, ASYNC_COLUMNS, output_format);
+            let result = client.get_paged(&path).await?;
+            output::render(&result, ASYNC_COLUMNS, output_format)?;
         }
     }
     Ok(())
@@ -76,12 +362,63 @@ pub async fn run_global(
             if !params.is_empty() {
                 path = format!("{}?{}", path, params.join("&"));
             }
-            let result = client.get(&path).await?;
-            output::render(&result, ASYNC_COLUMNS, output_format);
+            let result = client.get_paged(&path).await?;
+            output::render(&result, ASYNC_COLUMNS, output_format)?;
         }
         GlobalAsyncCmd::Get { id } => {
             let result = client.get(&format!("/async-invocations/{id}")).await?;
-            output::render_single(&result, ASYNC_COLUMNS, output_format);
+            output::render_single(&result, ASYNC_COLUMNS, output_format)?;
+        }
+        GlobalAsyncCmd::Backlog {
+            function,
+            range,
+            points,
+            watch,
+            interval,
+        } => {
+            run_backlog(
+                client,
+                function,
+                &range,
+                points,
+                watch,
+                &interval,
+                output_format,
+            )
+            .await?;
+        }
+        GlobalAsyncCmd::Result {
+            id,
+            out,
+            follow,
+            max_wait,
+        } => {
+            let invocation = wait_for_result(client, &id, follow, max_wait.as_deref()).await?;
+            if invocation["status"] == "dlq" {
+                return Err(OrbitError::Operation(format!(
+                    "invocation {id} failed after {} attempt(s): {}",
+                    invocation["attempt"].as_i64().unwrap_or(0),
+                    invocation["last_error"].as_str().unwrap_or("unknown error")
+                )));
+            }
+            let result = match &invocation["output"] {
+                Value::String(s) => s.clone(),
+                Value::Null => String::new(),
+                other => serde_json::to_string_pretty(other)?,
+            };
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, &result)?;
+                    output::print_success(&format!(
+                        "Wrote output of {id} to {path} ({} bytes).",
+                        result.len()
+                    ));
+                }
+                None if output::is_machine_readable(output_format) => {
+                    output::render(&invocation["output"], &[], output_format)?
+                }
+                None => output::print_text(&result),
+            }
         }
         GlobalAsyncCmd::Retry { id } => {
             let result = client
@@ -90,7 +427,7 @@ pub async fn run_global(
                     &serde_json::json!({}),
                 )
                 .await?;
-            output::render_single(&result, ASYNC_COLUMNS, output_format);
+            output::render_single(&result, ASYNC_COLUMNS, output_format)?;
         }
     }
     Ok(())
@@ -0,0 +1,160 @@
+//! `orbit audit`: offline checks over recent control-plane data.
+//!
+//! `loops` reads the call chains the server records on async invocations
+//! made by other functions, and reports invocations of a function already
+//! on their chain, such as a function that asynchronously invokes itself
+//! through another.
+
+use crate::client::NovaClient;
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use clap::Subcommand;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+const LOOP_COLUMNS: &[Column] = &[
+    Column::new("Function", "function"),
+    Column::new("Loop", "path"),
+    Column::new("Occurrences", "occurrences"),
+    Column::new("Max Depth", "max_depth"),
+    Column::new("Last Seen", "last_seen"),
+];
+
+#[derive(Subcommand)]
+pub enum AuditCmd {
+    /// Report probable recursive invocation loops (function → other
+    /// function → same function) in recent async invocations
+    Loops {
+        /// How far back to look (e.g. 1h, 24h)
+        #[arg(long, default_value = "1h")]
+        since: String,
+        /// Maximum invocations to analyze
+        #[arg(long, default_value_t = 5000)]
+        limit: u32,
+        /// Only report loops passing through this function
+        #[arg(long)]
+        function: Option<String>,
+    },
+}
+
+fn function_name(inv: &Value) -> &str {
+    inv.get("function_name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("?")
+}
+
+/// The functions that led to an invocation, outermost first, from the
+/// `call_chain` the server records on function-to-function async calls.
+fn call_chain(inv: &Value) -> Vec<&str> {
+    inv.get("call_chain")
+        .and_then(|v| v.as_str())
+        .map(|c| c.split(',').filter(|f| !f.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+struct Loop {
+    function: String,
+    occurrences: u64,
+    max_depth: u64,
+    last_seen: String,
+}
+
+/// Groups invocations of a function already on their call chain by the
+/// loop's path, keyed like `a → b → a`.
+fn find_loops(invocations: &[Value]) -> HashMap<String, Loop> {
+    let mut loops: HashMap<String, Loop> = HashMap::new();
+    for inv in invocations {
+        let chain = call_chain(inv);
+        let name = function_name(inv);
+        let Some(back) = chain.iter().position(|f| *f == name) else {
+            continue;
+        };
+        let path = format!("{} → {name}", chain[back..].join(" → "));
+        let depth = inv
+            .get("call_depth")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(chain.len() as u64);
+        let created = inv
+            .get("created_at")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let entry = loops.entry(path).or_insert_with(|| Loop {
+            function: name.to_string(),
+            occurrences: 0,
+            max_depth: 0,
+            last_seen: String::new(),
+        });
+        entry.occurrences += 1;
+        entry.max_depth = entry.max_depth.max(depth);
+        if created > entry.last_seen.as_str() {
+            entry.last_seen = created.to_string();
+        }
+    }
+    loops
+}
+
+pub async fn run(cmd: AuditCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    match cmd {
+        AuditCmd::Loops {
+            since,
+            limit,
+            function,
+        } => {
+            let window = crate::duration::parse(&since)?;
+            if window.num_seconds() <= 0 {
+                return Err(OrbitError::Input("--since must be positive".into()));
+            }
+            let cutoff = crate::duration::ago(window)?;
+            let result = client
+                .get_all(&format!("/async-invocations?limit={limit}"))
+                .await?;
+            let invocations: Vec<Value> = result
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|inv| {
+                    crate::duration::timestamp(inv, "created_at").is_none_or(|t| t >= cutoff)
+                })
+                .collect();
+            if !invocations.is_empty() && invocations.iter().all(|i| call_chain(i).is_empty()) {
+                output::print_warning(
+                    "No async invocation was made by another function; loops through synchronous calls or topics are not recorded.",
+                );
+            }
+
+            let mut rows: Vec<Value> = find_loops(&invocations)
+                .into_iter()
+                .filter(|(path, _)| {
+                    function
+                        .as_deref()
+                        .is_none_or(|f| path.split(" → ").any(|name| name == f))
+                })
+                .map(|(path, l)| {
+                    json!({
+                        "function": l.function,
+                        "path": path,
+                        "occurrences": l.occurrences,
+                        "max_depth": l.max_depth,
+                        "last_seen": l.last_seen,
+                    })
+                })
+                .collect();
+            rows.sort_by(|a, b| {
+                b["occurrences"]
+                    .as_u64()
+                    .cmp(&a["occurrences"].as_u64())
+                    .then_with(|| a["path"].as_str().cmp(&b["path"].as_str()))
+            });
+            if rows.is_empty() && !output::is_machine_readable(output_format) {
+                println!(
+                    "No probable loops in {} invocations over the last {since}.",
+                    invocations.len()
+                );
+                return Ok(());
+            }
+            output::render(&Value::Array(rows), LOOP_COLUMNS, output_format)?;
+        }
+    }
+    Ok(())
+}
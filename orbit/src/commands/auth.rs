@@ -0,0 +1,173 @@
+use crate::client::NovaClient;
+use crate::config::{OrbitConfig, StoredToken};
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+fn issuer_url(issuer: Option<String>, client: &NovaClient) -> String {
+    issuer
+        .unwrap_or_else(|| format!("{}/auth", client.base_url()))
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn oauth_error(resp: &Value) -> Option<&str> {
+    resp.get("error").and_then(|v| v.as_str())
+}
+
+const WHOAMI_COLUMNS: &[Column] = &[
+    Column::new("Subject", "subject"),
+    Column::new("Auth", "auth_method"),
+    Column::new("API Key", "key_name"),
+    Column::new("Scopes", "scopes"),
+    Column::new("Tenant", "tenant"),
+    Column::new("Namespace", "namespace"),
+    Column::new("Tier", "tier"),
+    Column::new("Context", "context"),
+    Column::new("Server", "server"),
+    Column::new("Server Version", "server_version"),
+    Column::wide("Policies", "policies"),
+];
+
+/// Shows the identity the server resolves for the current credentials,
+/// alongside the client-side settings requests are sent with.
+pub async fn run_whoami(client: &NovaClient, output_format: &str) -> Result<()> {
+    let mut identity = client.get("/auth/whoami").await?;
+    if identity["server_version"].is_null()
+        && let Ok(health) = client.get("/health").await
+    {
+        identity["server_version"] = health["version"].clone();
+    }
+    // The server's view wins; fall back to what the client sends.
+    if identity["tenant"].is_null() {
+        identity["tenant"] = json!(client.tenant().unwrap_or("default"));
+    }
+    if identity["namespace"].is_null() {
+        identity["namespace"] = json!(client.namespace().unwrap_or("default"));
+    }
+    identity["auth_method"] = json!(client.auth_method());
+    identity["context"] = json!(client.context());
+    identity["server"] = json!(client.base_url());
+    output::render_single(&identity, WHOAMI_COLUMNS, output_format)?;
+    Ok(())
+}
+
+/// Runs the OAuth device authorization flow and stores the resulting token.
+pub async fn run_login(
+    issuer: Option<String>,
+    client_id: String,
+    scope: Option<String>,
+    client: &NovaClient,
+) -> Result<()> {
+    let issuer = issuer_url(issuer, client);
+    let token_endpoint = format!("{issuer}/token");
+
+    let mut form = vec![("client_id", client_id.as_str())];
+    if let Some(s) = &scope {
+        form.push(("scope", s));
+    }
+    let device = client
+        .oauth_post(&format!("{issuer}/device/code"), &form)
+        .await?;
+    let field = |name: &str| {
+        device.get(name).and_then(|v| v.as_str()).ok_or_else(|| {
+            OrbitError::Config(format!("Device authorization response missing '{name}'"))
+        })
+    };
+    let device_code = field("device_code")?;
+    let user_code = field("user_code")?;
+    let verification_uri = field("verification_uri")?;
+
+    match device
+        .get("verification_uri_complete")
+        .and_then(|v| v.as_str())
+    {
+        Some(uri) => eprintln!("Open {uri} to sign in."),
+        None => eprintln!("Open {verification_uri} and enter code {user_code}."),
+    }
+
+    let mut interval = device.get("interval").and_then(|v| v.as_u64()).unwrap_or(5);
+    let expires_in = device
+        .get("expires_in")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(600);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(expires_in);
+
+    let poll = [
+        ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+        ("device_code", device_code),
+        ("client_id", client_id.as_str()),
+    ];
+    let token = loop {
+        if tokio::time::Instant::now() >= deadline {
+            return Err(OrbitError::Config(
+                "Device code expired before sign-in completed".into(),
+            ));
+        }
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+
+        // The token endpoint reports pending authorization as a 400 with an
+        // OAuth error code, so inspect API errors rather than bailing out.
+        let resp = match client.oauth_post(&token_endpoint, &poll).await {
+            Ok(resp) => resp,
+            Err(OrbitError::Api { message, .. }) => json!({ "error": message }),
+            Err(e) => return Err(e),
+        };
+        match oauth_error(&resp) {
+            None => {
+                break StoredToken::from_response(&resp, &token_endpoint, &client_id, None)
+                    .ok_or_else(|| {
+                        OrbitError::Config("Token response missing 'access_token'".into())
+                    })?;
+            }
+            Some("authorization_pending") => {}
+            Some("slow_down") => interval += 5,
+            Some("access_denied") => {
+                return Err(OrbitError::Config("Sign-in was denied".into()));
+            }
+            Some("expired_token") => {
+                return Err(OrbitError::Config(
+                    "Device code expired before sign-in completed".into(),
+                ));
+            }
+            Some(other) => {
+                return Err(OrbitError::Config(format!("Sign-in failed: {other}")));
+            }
+        }
+    };
+
+    let mut cfg = OrbitConfig::load();
+    cfg.section_mut(client.context()).token = Some(token);
+    cfg.save()?;
+    output::print_success("Logged in.");
+    Ok(())
+}
+
+/// Removes the stored token, revoking it on the server when possible.
+pub async fn run_logout(issuer: Option<String>, client: &NovaClient) -> Result<()> {
+    let mut cfg = OrbitConfig::load();
+    let Some(token) = cfg.section_mut(client.context()).token.take() else {
+        output::print_success("Not logged in.");
+        return Ok(());
+    };
+
+    let issuer = issuer_url(issuer, client);
+    let form = [
+        (
+            "token",
+            token
+                .refresh_token
+                .as_deref()
+                .unwrap_or(&token.access_token),
+        ),
+        ("client_id", token.client_id.as_str()),
+    ];
+    if let Err(e) = client.oauth_post(&format!("{issuer}/revoke"), &form).await {
+        output::print_warning(&format!("Could not revoke token on server: {e}"));
+    }
+
+    cfg.save()?;
+    output::print_success("Logged out.");
+    Ok(())
+}
@@ -10,6 +10,6 @@ const BACKEND_COLUMNS: &[Column] = &[
 
 pub async fn run(client: &NovaClient, output_format: &str) -> Result<()> {
     let result = client.get("/backends").await?;
-    output::render(&result, BACKEND_COLUMNS, output_format);
+    output::render(&result, BACKEND_COLUMNS, output_format)?;
     Ok(())
 }
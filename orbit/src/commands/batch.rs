@@ -0,0 +1,224 @@
+//! `orbit batch`: runs many operations from one process, sharing the client
+//! (auth, connection pool, cache) between them.
+//!
+//! Input is one subcommand per line, as typed after `orbit` (blank lines and
+//! `#` comments are skipped), or a JSON array whose items are command strings
+//! or argument arrays. Global flags come from the `batch` invocation itself.
+//! Output rendered by each operation is collected and printed in input order,
+//! followed by a report of every operation. An operation that fails, on a
+//! `--query` or template error too, is reported as failed and the rest still
+//! run.
+
+use crate::client::NovaClient;
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use clap::Parser;
+use serde_json::{Value, json};
+use std::time::Instant;
+
+const BATCH_COLUMNS: &[Column] = &[
+    Column::new("#", "index"),
+    Column::new("Command", "command"),
+    Column::new("Status", "status"),
+    Column::new("Duration", "duration_ms"),
+    Column::new("Error", "error").max_width(60),
+];
+
+#[derive(Clone)]
+struct Op {
+    /// Line number, or position in a JSON array
+    index: usize,
+    command: String,
+    args: Vec<String>,
+}
+
+/// Splits a command line into words: whitespace separates, single quotes
+/// are literal, and double quotes and backslashes work as in `sh`.
+fn split_words(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => w.push(c),
+                        None => return Err(unterminated(line)),
+                    }
+                }
+            }
+            '"' => {
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => w.push(c),
+                            Some(c) => {
+                                w.push('\\');
+                                w.push(c);
+                            }
+                            None => return Err(unterminated(line)),
+                        },
+                        Some(c) => w.push(c),
+                        None => return Err(unterminated(line)),
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    word.get_or_insert_with(String::new).push(c);
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+fn unterminated(line: &str) -> OrbitError {
+    OrbitError::Input(format!("Unterminated quote in '{line}'"))
+}
+
+/// A leading `orbit` is accepted, so lines can be pasted from a shell.
+fn op(index: usize, command: String, mut args: Vec<String>) -> Op {
+    if args.first().is_some_and(|a| a == "orbit") {
+        args.remove(0);
+    }
+    Op {
+        index,
+        command,
+        args,
+    }
+}
+
+fn read_ops(input: &str) -> Result<Vec<Op>> {
+    if input.trim_start().starts_with('[') {
+        let items: Vec<Value> = serde_json::from_str(input)
+            .map_err(|e| OrbitError::Input(format!("Invalid operations array: {e}")))?;
+        return items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| match item {
+                Value::String(command) => Ok(op(i + 1, command.clone(), split_words(&command)?)),
+                Value::Array(args) => {
+                    let args = args
+                        .iter()
+                        .map(|a| a.as_str().map(String::from))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| {
+                            OrbitError::Input(format!(
+                                "Operation {}: arguments must be strings",
+                                i + 1
+                            ))
+                        })?;
+                    Ok(op(i + 1, args.join(" "), args))
+                }
+                _ => Err(OrbitError::Input(format!(
+                    "Operation {}: expected a command string or an array of arguments",
+                    i + 1
+                ))),
+            })
+            .collect();
+    }
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| Ok(op(i + 1, line.trim().to_string(), split_words(line)?)))
+        .collect()
+}
+
+async fn run_op(client: &NovaClient, op: Op, output_format: &str) -> Value {
+    let started = Instant::now();
+    let (result, out) = match crate::BatchLine::try_parse_from(&op.args) {
+        Ok(line) if matches!(line.command, crate::Commands::Batch { .. }) => (
+            Err(OrbitError::Input("batch cannot be nested".into())),
+            String::new(),
+        ),
+        Ok(line) => {
+            let run = crate::dispatch(line.command, client, output_format, None);
+            output::capture(Box::pin(run)).await
+        }
+        Err(e) => {
+            let message = e.to_string();
+            let first = message.lines().next().unwrap_or_default();
+            let first = first.strip_prefix("error: ").unwrap_or(first);
+            (Err(OrbitError::Input(first.to_string())), String::new())
+        }
+    };
+    json!({
+        "index": op.index,
+        "command": op.command,
+        "status": if result.is_ok() { "ok" } else { "failed" },
+        "duration_ms": started.elapsed().as_millis() as u64,
+        "error": result.err().map(|e| e.to_string()),
+        "output": out,
+    })
+}
+
+pub async fn run(
+    input: String,
+    concurrency: usize,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let text = if input == "-" {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(&input)
+            .map_err(|e| OrbitError::Input(format!("Cannot read {input}: {e}")))?
+    };
+    let ops = read_ops(&text)?;
+    if ops.is_empty() {
+        return Err(OrbitError::Input("No operations to run".into()));
+    }
+
+    let results = client
+        .batch(ops, concurrency, |client, op| async move {
+            Ok(run_op(client, op, output_format).await)
+        })
+        .await;
+    let mut rows: Vec<Value> = results.into_iter().filter_map(|(_, r)| r.ok()).collect();
+    let failed = rows.iter().filter(|r| r["status"] == "failed").count();
+
+    if output::is_machine_readable(output_format) {
+        // Operations rendered JSON or YAML too (YAML parses both); nest
+        // what parses.
+        for row in &mut rows {
+            let out = row["output"].as_str().unwrap_or_default();
+            if let Ok(parsed) = serde_yaml::from_str::<Value>(out) {
+                row["output"] = parsed;
+            }
+        }
+    } else {
+        for row in &rows {
+            let out = row["output"].as_str().unwrap_or_default();
+            if !out.is_empty() {
+                output::print_line(&format!(
+                    "==> [{}] {}",
+                    row["index"],
+                    row["command"].as_str().unwrap_or_default()
+                ));
+                output::print_text(out.trim_end());
+            }
+        }
+    }
+    let total = rows.len();
+    output::render(&Value::Array(rows), BATCH_COLUMNS, output_format)?;
+
+    if failed > 0 {
+        return Err(OrbitError::Input(format!(
+            "{failed} of {total} operation(s) failed"
+        )));
+    }
+    if !output::is_machine_readable(output_format) {
+        output::print_success(&format!("Ran {total} operation(s)."));
+    }
+    Ok(())
+}
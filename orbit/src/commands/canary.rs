@@ -0,0 +1,408 @@
+//! `orbit functions canary`: shift a share of an alias's traffic to a new
+//! version, bake it while watching the function's error rate, then promote
+//! or roll back. Orchestrated client-side: the canary lives in the alias's
+//! traffic split, so `status`, `promote` and `abort` work from any machine.
+//!
+//! The metrics endpoint reports the whole function, so the error rate
+//! watched covers stable and canary traffic together; a regression in the
+//! canary shows up diluted by its share.
+
+use crate::client::NovaClient;
+use crate::commands::aliases;
+use crate::commands::functions::CanarySubCmd;
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Reads of the metrics attempted per check before the bake gives up.
+const METRICS_ATTEMPTS: u32 = 3;
+
+const CANARY_COLUMNS: &[Column] = &[
+    Column::new("Alias", "alias"),
+    Column::new("Stable", "stable_version"),
+    Column::new("Canary", "canary_version"),
+    Column::new("Canary %", "canary_percent"),
+    Column::new("Invocations", "invocations"),
+    Column::new("Errors", "errors"),
+    Column::new("Error Rate", "error_rate"),
+    Column::new("Started", "started_at"),
+];
+
+/// An alias's canary: the version keeping most traffic, the one on trial
+/// and the trial's share.
+struct Canary {
+    stable: u32,
+    candidate: u32,
+    percent: u32,
+    started: Option<DateTime<Utc>>,
+}
+
+impl Canary {
+    fn from_alias(alias: &Value) -> Option<Self> {
+        let stable = aliases::main_version(alias)?;
+        let (candidate, percent) = alias["traffic_split"]
+            .as_object()?
+            .iter()
+            .filter_map(|(v, share)| Some((v.parse::<u32>().ok()?, share.as_u64()? as u32)))
+            .find(|(v, _)| *v != stable)?;
+        Some(Self {
+            stable,
+            candidate,
+            percent,
+            started: crate::duration::timestamp(alias, "updated_at"),
+        })
+    }
+}
+
+/// The alias, or `None` if it doesn't exist yet.
+async fn get_alias(client: &NovaClient, name: &str, alias: &str) -> Result<Option<Value>> {
+    match client
+        .get(&format!("/functions/{name}/aliases/{alias}"))
+        .await
+    {
+        Ok(a) => Ok(Some(a)),
+        Err(OrbitError::Api { status: 404, .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Points `alias` at `version`, with `split` of its traffic elsewhere.
+async fn set_alias(
+    client: &NovaClient,
+    name: &str,
+    alias: &str,
+    exists: bool,
+    version: u32,
+    split: Option<(u32, u32)>,
+) -> Result<Value> {
+    let mut body = aliases::target(version, split)?;
+    body["name"] = json!(alias);
+    if exists {
+        client
+            .put(&format!("/functions/{name}/aliases/{alias}"), &body)
+            .await
+    } else {
+        client
+            .post(&format!("/functions/{name}/aliases"), &body)
+            .await
+    }
+}
+
+/// Makes `version` the one unqualified invocations run.
+async fn activate(client: &NovaClient, name: &str, version: u32) -> Result<()> {
+    client
+        .post(
+            &format!("/functions/{name}/versions/{version}/activate"),
+            &json!({}),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Invocations and errors of `name` since `since`, from the per-minute
+/// metrics timeseries.
+async fn errors_since(client: &NovaClient, name: &str, since: DateTime<Utc>) -> Result<(u64, u64)> {
+    let minutes = (Utc::now() - since).num_minutes() + 1;
+    let metrics = client
+        .get(&format!("/functions/{name}/metrics?range={minutes}m"))
+        .await?;
+    let buckets = metrics["timeseries"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let recent = buckets
+        .iter()
+        .filter(|b| crate::duration::timestamp(b, "timestamp").is_some_and(|t| t >= since));
+    let (mut invocations, mut errors) = (0, 0);
+    for bucket in recent {
+        invocations += bucket["invocations"].as_u64().unwrap_or(0);
+        errors += bucket["errors"].as_u64().unwrap_or(0);
+    }
+    Ok((invocations, errors))
+}
+
+/// [`errors_since`], retried with backoff so one failed read doesn't end
+/// the bake.
+async fn errors_since_retrying(
+    client: &NovaClient,
+    name: &str,
+    since: DateTime<Utc>,
+) -> Result<(u64, u64)> {
+    let mut delay = Duration::from_secs(1);
+    for _ in 1..METRICS_ATTEMPTS {
+        match errors_since(client, name, since).await {
+            Ok(counts) => return Ok(counts),
+            Err(e) => {
+                output::print_warning(&format!(
+                    "Cannot read the metrics of '{name}' ({e}); retrying in {}s.",
+                    delay.as_secs()
+                ));
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+        }
+    }
+    errors_since(client, name, since).await
+}
+
+fn error_rate(invocations: u64, errors: u64) -> f64 {
+    if invocations == 0 {
+        0.0
+    } else {
+        errors as f64 * 100.0 / invocations as f64
+    }
+}
+
+async fn status_row(client: &NovaClient, name: &str, alias: &str, canary: &Canary) -> Value {
+    let mut row = json!({
+        "alias": alias,
+        "stable_version": canary.stable,
+        "canary_version": canary.candidate,
+        "canary_percent": format!("{}%", canary.percent),
+        "started_at": canary.started.map(|t| t.to_rfc3339()),
+    });
+    if let Some(since) = canary.started
+        && let Ok((invocations, errors)) = errors_since(client, name, since).await
+    {
+        row["invocations"] = json!(invocations);
+        row["errors"] = json!(errors);
+        row["error_rate"] = json!(format!("{:.2}%", error_rate(invocations, errors)));
+    }
+    row
+}
+
+/// Rolls the alias and the function back to the stable version.
+async fn roll_back(client: &NovaClient, name: &str, alias: &str, canary: &Canary) -> Result<()> {
+    set_alias(client, name, alias, true, canary.stable, None).await?;
+    activate(client, name, canary.stable).await
+}
+
+/// Routes the alias and the function entirely to the candidate.
+async fn promote(client: &NovaClient, name: &str, alias: &str, canary: &Canary) -> Result<()> {
+    set_alias(client, name, alias, true, canary.candidate, None).await?;
+    activate(client, name, canary.candidate).await
+}
+
+pub struct StartOptions {
+    pub alias: String,
+    pub version: Option<u32>,
+    pub code_dir: Option<String>,
+    pub percent: u32,
+    pub bake: String,
+    pub max_error_rate: f64,
+    pub interval: String,
+    pub min_invocations: u64,
+    pub no_wait: bool,
+}
+
+async fn start(
+    name: &str,
+    options: StartOptions,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    if !(1..=99).contains(&options.percent) {
+        return Err(OrbitError::Input(
+            "--percent must be between 1 and 99".into(),
+        ));
+    }
+    let bake = crate::duration::parse(&options.bake)?;
+    let interval = crate::duration::parse(&options.interval)?
+        .to_std()
+        .ok()
+        .filter(|d| !d.is_zero())
+        .ok_or_else(|| OrbitError::Input("Interval must be positive".into()))?;
+    let alias = options.alias.as_str();
+
+    let function = client.get(&format!("/functions/{name}")).await?;
+    let existing = get_alias(client, name, alias).await?;
+    if let Some(canary) = existing.as_ref().and_then(Canary::from_alias) {
+        return Err(OrbitError::Input(format!(
+            "A canary of v{} is already running on '{alias}'; promote or abort it first",
+            canary.candidate
+        )));
+    }
+    let stable = existing
+        .as_ref()
+        .and_then(aliases::main_version)
+        .or(function["version"].as_u64().map(|v| v as u32))
+        .ok_or_else(|| OrbitError::Input(format!("'{name}' has no published version")))?;
+
+    let mut alias_exists = existing.is_some();
+    let candidate = match (options.version, &options.code_dir) {
+        (Some(v), _) => v,
+        (None, Some(dir)) => {
+            // Uploading replaces the active code, so keep the alias on the
+            // stable version until the split is in place.
+            set_alias(client, name, alias, alias_exists, stable, None).await?;
+            alias_exists = true;
+            crate::commands::code::upload_dir(client, name, dir, None).await?;
+            let published = client
+                .patch(&format!("/functions/{name}"), &json!({}))
+                .await;
+            // Only the canary share should see the new code.
+            activate(client, name, stable).await?;
+            published?["version"]
+                .as_u64()
+                .map(|v| v as u32)
+                .ok_or_else(|| {
+                    OrbitError::Input("The server did not report the new version".into())
+                })?
+        }
+        (None, None) => function["version"]
+            .as_u64()
+            .map(|v| v as u32)
+            .unwrap_or(stable),
+    };
+    if candidate == stable {
+        return Err(OrbitError::Input(format!(
+            "v{candidate} is already the stable version of '{alias}'; pass --code-dir or --version"
+        )));
+    }
+
+    let alias_value = set_alias(
+        client,
+        name,
+        alias,
+        alias_exists,
+        stable,
+        Some((candidate, options.percent)),
+    )
+    .await?;
+    let mut canary = Canary::from_alias(&alias_value).unwrap_or(Canary {
+        stable,
+        candidate,
+        percent: options.percent,
+        started: None,
+    });
+    canary.started = Some(canary.started.unwrap_or_else(Utc::now));
+    output::print_status(&format!(
+        "Shifted {}% of '{alias}' traffic to v{candidate}; v{stable} keeps the rest.",
+        options.percent
+    ));
+    if options.no_wait {
+        let row = status_row(client, name, alias, &canary).await;
+        output::render_single(&row, CANARY_COLUMNS, output_format)?;
+        return Ok(());
+    }
+
+    output::print_status(&format!(
+        "Baking for {} (error rate limit {}%). Press Ctrl-C to leave it running.",
+        options.bake, options.max_error_rate
+    ));
+    let started = canary.started.unwrap_or_else(Utc::now);
+    let sampled = loop {
+        tokio::time::sleep(interval).await;
+        let (invocations, errors) = match errors_since_retrying(client, name, started).await {
+            Ok(counts) => counts,
+            Err(e) => {
+                roll_back(client, name, alias, &canary).await?;
+                return Err(OrbitError::Input(format!(
+                    "Rolled '{name}' back to v{stable}: cannot read its metrics: {e}"
+                )));
+            }
+        };
+        let rate = error_rate(invocations, errors);
+        output::print_status(&format!(
+            "{}  invocations {invocations:>6}  errors {errors:>5}  error rate {rate:>6.2}%",
+            chrono::Local::now().format("%H:%M:%S"),
+        ));
+        if rate > options.max_error_rate {
+            roll_back(client, name, alias, &canary).await?;
+            return Err(OrbitError::Input(format!(
+                "Rolled '{name}' back to v{stable}: error rate {rate:.2}% exceeded {}%",
+                options.max_error_rate
+            )));
+        }
+        if Utc::now() - started >= bake {
+            break invocations;
+        }
+    };
+    if sampled < options.min_invocations {
+        roll_back(client, name, alias, &canary).await?;
+        return Err(OrbitError::Input(format!(
+            "Rolled '{name}' back to v{stable}: only {sampled} invocation(s) during the bake, fewer than --min-invocations {}",
+            options.min_invocations
+        )));
+    }
+    let row = status_row(client, name, alias, &canary).await;
+    promote(client, name, alias, &canary).await?;
+    output::render_single(&row, CANARY_COLUMNS, output_format)?;
+    output::print_success(&format!(
+        "Promoted v{candidate} of '{name}' after baking {}.",
+        options.bake
+    ));
+    Ok(())
+}
+
+/// The canary running on `alias`, or an error saying where it routes.
+async fn running(client: &NovaClient, name: &str, alias: &str) -> Result<Canary> {
+    let current = get_alias(client, name, alias).await?;
+    current
+        .as_ref()
+        .and_then(Canary::from_alias)
+        .ok_or_else(|| {
+            let routing = current
+                .as_ref()
+                .and_then(aliases::main_version)
+                .map(|v| format!("; it routes to v{v}"))
+                .unwrap_or_default();
+            OrbitError::Input(format!(
+                "No canary running on '{alias}' of '{name}'{routing}"
+            ))
+        })
+}
+
+pub async fn run(cmd: CanarySubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    match cmd {
+        CanarySubCmd::Start {
+            name,
+            alias,
+            version,
+            code_dir,
+            percent,
+            bake,
+            max_error_rate,
+            interval,
+            min_invocations,
+            no_wait,
+        } => {
+            let options = StartOptions {
+                alias,
+                version,
+                code_dir,
+                percent,
+                bake,
+                max_error_rate,
+                interval,
+                min_invocations,
+                no_wait,
+            };
+            start(&name, options, client, output_format).await?;
+        }
+        CanarySubCmd::Status { name, alias } => {
+            let canary = running(client, &name, &alias).await?;
+            let row = status_row(client, &name, &alias, &canary).await;
+            output::render_single(&row, CANARY_COLUMNS, output_format)?;
+        }
+        CanarySubCmd::Promote { name, alias } => {
+            let canary = running(client, &name, &alias).await?;
+            promote(client, &name, &alias, &canary).await?;
+            output::print_success(&format!(
+                "Promoted v{} of '{name}'; '{alias}' routes all traffic to it.",
+                canary.candidate
+            ));
+        }
+        CanarySubCmd::Abort { name, alias } => {
+            let canary = running(client, &name, &alias).await?;
+            roll_back(client, &name, &alias, &canary).await?;
+            output::print_success(&format!(
+                "Aborted the canary of v{}; '{alias}' of '{name}' is back on v{}.",
+                canary.candidate, canary.stable
+            ));
+        }
+    }
+    Ok(())
+}
@@ -2,7 +2,33 @@ use crate::client::NovaClient;
 use crate::commands::functions::CapacitySubCmd;
 use crate::error::Result;
 use crate::output::{self, Column};
-use serde_json::json;
+use clap::Subcommand;
+use serde_json::{Value, json};
+
+#[derive(Subcommand)]
+pub enum CapacityCmd {
+    /// Report pool utilization, memory headroom and VM growth across nodes
+    Report {
+        /// History used for the peak and growth trend (e.g. 7d, 30d)
+        #[arg(long, default_value = "7d")]
+        range: String,
+        /// Also write the node table to this CSV file
+        #[arg(long)]
+        csv: Option<String>,
+    },
+}
+
+const REPORT_COLUMNS: &[Column] = &[
+    Column::new("Node", "name"),
+    Column::new("Active VMs", "active_vms"),
+    Column::new("Max VMs", "max_vms"),
+    Column::new("Utilization %", "utilization_pct"),
+    Column::new("Memory MB", "memory_mb"),
+    Column::new("Memory Used %", "memory_usage"),
+    Column::new("Headroom MB", "headroom_mb"),
+    Column::wide("State", "state"),
+    Column::wide("CPU %", "cpu_usage"),
+];
 
 const CAPACITY_COLUMNS: &[Column] = &[
     Column::new("Enabled", "enabled"),
@@ -17,7 +43,7 @@ pub async fn run(cmd: CapacitySubCmd, client: &NovaClient, output_format: &str)
     match cmd {
         CapacitySubCmd::Get { name } => {
             let result = client.get(&format!("/functions/{name}/capacity")).await?;
-            output::render_single(&result, CAPACITY_COLUMNS, output_format);
+            output::render_single(&result, CAPACITY_COLUMNS, output_format)?;
         }
         CapacitySubCmd::Set {
             name,
@@ -42,7 +68,7 @@ pub async fn run(cmd: CapacitySubCmd, client: &NovaClient, output_format: &str)
             let result = client
                 .put(&format!("/functions/{name}/capacity"), &body)
                 .await?;
-            output::render_single(&result, CAPACITY_COLUMNS, output_format);
+            output::render_single(&result, CAPACITY_COLUMNS, output_format)?;
         }
         CapacitySubCmd::Delete { name } => {
             client
@@ -53,3 +79,107 @@ pub async fn run(cmd: CapacitySubCmd, client: &NovaClient, output_format: &str)
     }
     Ok(())
 }
+
+fn pct(part: f64, whole: f64) -> Value {
+    if whole > 0.0 {
+        json!((part * 1000.0 / whole).round() / 10.0)
+    } else {
+        Value::Null
+    }
+}
+
+/// Least-squares slope of `(days, value)` points, in value per day.
+fn slope_per_day(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let var_x: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if var_x == 0.0 {
+        return None;
+    }
+    let cov: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    Some(cov / var_x)
+}
+
+pub async fn run_report(cmd: CapacityCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    let CapacityCmd::Report { range, csv } = cmd;
+    let nodes = client.get_all("/cluster/nodes").await?;
+    let mut rows: Vec<Value> = nodes.as_array().cloned().unwrap_or_default();
+    let (mut active, mut max, mut memory, mut used_memory) = (0.0, 0.0, 0.0, 0.0);
+    for row in &mut rows {
+        let n = |key: &str| row[key].as_f64().unwrap_or(0.0);
+        let (node_active, node_max, node_memory) = (n("active_vms"), n("max_vms"), n("memory_mb"));
+        let node_used = node_memory * n("memory_usage") / 100.0;
+        active += node_active;
+        max += node_max;
+        memory += node_memory;
+        used_memory += node_used;
+        if row["name"].as_str().is_none_or(str::is_empty) {
+            row["name"] = row["id"].clone();
+        }
+        row["utilization_pct"] = pct(node_active, node_max);
+        row["headroom_mb"] = json!((node_memory - node_used).round() as i64);
+    }
+    if rows.len() > 1 {
+        rows.push(json!({
+            "name": "TOTAL",
+            "active_vms": active as i64,
+            "max_vms": max as i64,
+            "utilization_pct": pct(active, max),
+            "memory_mb": memory as i64,
+            "memory_usage": pct(used_memory, memory),
+            "headroom_mb": (memory - used_memory).round() as i64,
+        }));
+    }
+    output::render(&Value::Array(rows.clone()), REPORT_COLUMNS, output_format)?;
+    if let Some(path) = &csv {
+        output::write_csv(path, &rows, REPORT_COLUMNS)?;
+        output::print_success(&format!("Wrote {} row(s) to {path}.", rows.len()));
+    }
+    if output::is_machine_readable(output_format) {
+        return Ok(());
+    }
+
+    let series = client
+        .get(&format!("/metrics/timeseries?range={range}"))
+        .await?;
+    let buckets = series.as_array().cloned().unwrap_or_default();
+    let start = buckets
+        .iter()
+        .find_map(|b| crate::duration::timestamp(b, "timestamp"));
+    let points: Vec<(f64, f64)> = buckets
+        .iter()
+        .filter_map(|b| {
+            let at = crate::duration::timestamp(b, "timestamp")?;
+            let days = (at - start?).num_seconds() as f64 / 86400.0;
+            Some((days, b["active_vms"].as_f64()?))
+        })
+        .collect();
+    let peak = points.iter().map(|p| p.1).fold(0.0, f64::max);
+
+    output::print_line("");
+    output::print_line(&format!(
+        "Peak concurrent VMs ({range}): {peak:.0} of {max:.0}"
+    ));
+    match slope_per_day(&points) {
+        Some(slope) if slope > 0.0 => {
+            output::print_line(&format!("Growth trend:             +{slope:.2} VMs/day"));
+            if max > peak {
+                output::print_line(&format!(
+                    "Projected full in:        {:.0} days at the current trend",
+                    (max - peak) / slope
+                ));
+            } else {
+                output::print_warning("Peak demand has already reached VM capacity.");
+            }
+        }
+        Some(slope) => output::print_line(&format!(
+            "Growth trend:             {slope:.2} VMs/day (flat or shrinking)"
+        )),
+        None => output::print_line("Growth trend:             (not enough history)"),
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+//! `orbit functions caps`: daily invocation and spend ceilings. Unlike a
+//! throttle they don't expire; they reset at midnight UTC and, once
+//! breached, the server throttles, disables or only alerts depending on
+//! `--on-breach`.
+
+use crate::client::NovaClient;
+use crate::commands::functions::CapsSubCmd;
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use serde_json::{Value, json};
+
+const CAPS_COLUMNS: &[Column] = &[
+    Column::new("Max Invocations/Day", "max_invocations_per_day"),
+    Column::new("Invocations Today", "invocations_today"),
+    Column::new("Max Cost/Day", "max_cost_per_day"),
+    Column::new("Cost Today", "cost_today"),
+    Column::new("On Breach", "on_breach"),
+    Column::new("Breached", "breached_at"),
+    Column::wide("Updated", "updated_at"),
+];
+
+/// Parses a count with an optional k/M/B suffix, e.g. `1M` or `2.5k`.
+fn parse_count(input: &str) -> Result<u64> {
+    let invalid = || OrbitError::Input(format!("Invalid count '{input}' (e.g. 500, 10k, 1M)"));
+    let input = input.trim();
+    let (number, scale) = match input.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&input[..i], 1e3),
+        Some((i, 'm' | 'M')) => (&input[..i], 1e6),
+        Some((i, 'b' | 'B')) => (&input[..i], 1e9),
+        _ => (input, 1.0),
+    };
+    let value = number.parse::<f64>().map_err(|_| invalid())? * scale;
+    if !value.is_finite() || value < 1.0 || value.fract() != 0.0 {
+        return Err(invalid());
+    }
+    Ok(value as u64)
+}
+
+pub async fn run(cmd: CapsSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    match cmd {
+        CapsSubCmd::Set {
+            name,
+            max_invocations_per_day,
+            max_cost_per_day,
+            on_breach,
+        } => {
+            if max_invocations_per_day.is_none() && max_cost_per_day.is_none() {
+                return Err(OrbitError::Input(
+                    "Set --max-invocations-per-day, --max-cost-per-day or both".into(),
+                ));
+            }
+            let mut body = json!({ "on_breach": on_breach });
+            if let Some(count) = max_invocations_per_day {
+                body["max_invocations_per_day"] = json!(parse_count(&count)?);
+            }
+            if let Some(cost) = max_cost_per_day {
+                // NaN would be sent as null and clear the ceiling.
+                if !cost.is_finite() || cost <= 0.0 {
+                    return Err(OrbitError::Input(
+                        "--max-cost-per-day must be a positive number".into(),
+                    ));
+                }
+                body["max_cost_per_day"] = json!(cost);
+            }
+            let result = client
+                .put(&format!("/functions/{name}/caps"), &body)
+                .await?;
+            output::render_single(&result, CAPS_COLUMNS, output_format)?;
+        }
+        CapsSubCmd::Get { name } => {
+            let result = match client.get(&format!("/functions/{name}/caps")).await {
+                Err(OrbitError::Api { status: 404, .. }) => Value::Null,
+                other => other?,
+            };
+            if result.is_null() && !output::is_machine_readable(output_format) {
+                println!("No caps set on '{name}'.");
+                return Ok(());
+            }
+            output::render_single(&result, CAPS_COLUMNS, output_format)?;
+        }
+        CapsSubCmd::Clear { name } => {
+            client.delete(&format!("/functions/{name}/caps")).await?;
+            output::print_success(&format!("Caps removed from '{name}'."));
+        }
+    }
+    Ok(())
+}
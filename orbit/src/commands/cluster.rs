@@ -26,15 +26,15 @@ pub async fn run(cmd: ClusterCmd, client: &NovaClient, output_format: &str) -> R
     match cmd {
         ClusterCmd::List => {
             let result = client.get("/cluster/nodes").await?;
-            output::render(&result, NODE_COLUMNS, output_format);
+            output::render(&result, NODE_COLUMNS, output_format)?;
         }
         ClusterCmd::Healthy => {
             let result = client.get("/cluster/nodes/healthy").await?;
-            output::render(&result, NODE_COLUMNS, output_format);
+            output::render(&result, NODE_COLUMNS, output_format)?;
         }
         ClusterCmd::Get { id } => {
             let result = client.get(&format!("/cluster/nodes/{id}")).await?;
-            output::render_single(&result, NODE_COLUMNS, output_format);
+            output::render_single(&result, NODE_COLUMNS, output_format)?;
         }
         ClusterCmd::Delete { id } => {
             client.delete(&format!("/cluster/nodes/{id}")).await?;
@@ -1,8 +1,57 @@
 use crate::client::NovaClient;
 use crate::commands::functions::CodeSubCmd;
-use crate::error::Result;
+use crate::diff;
+use crate::error::{OrbitError, Result};
+use crate::manifest::FUNCTION_MANIFESTS;
 use crate::output;
-use serde_json::json;
+use crate::package;
+use reqwest::multipart::{Form, Part};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Uploads `dir` zipped (see [`crate::package`]) as the code of `name`. The
+/// list of included files is sent along as `manifest` and returned as
+/// `files`.
+pub async fn upload_dir(
+    client: &NovaClient,
+    name: &str,
+    dir: &str,
+    entry_point: Option<&str>,
+) -> Result<Value> {
+    let package = package::zip_dir(Path::new(dir))?;
+    let (count, size) = (package.manifest.len(), package.archive.len());
+    let result = upload(client, name, package, entry_point).await?;
+    output::print_status(&format!(
+        "Uploaded {count} file(s) from {dir} ({} zipped).",
+        output::format_bytes(size)
+    ));
+    Ok(result)
+}
+
+/// Uploads an already zipped package as the code of `name`.
+pub async fn upload(
+    client: &NovaClient,
+    name: &str,
+    package: package::Package,
+    entry_point: Option<&str>,
+) -> Result<Value> {
+    let archive = Part::bytes(package.archive)
+        .file_name("code.zip")
+        .mime_str("application/zip")?;
+    let mut form = Form::new()
+        .part("archive", archive)
+        .text("archive_type", "zip")
+        .text("manifest", serde_json::to_string(&package.manifest)?);
+    if let Some(e) = entry_point {
+        form = form.text("entry_point", e.to_string());
+    }
+    let mut result = client
+        .put_multipart(&format!("/functions/{name}/code"), form)
+        .await?;
+    result["files"] = json!(package.manifest);
+    Ok(result)
+}
 
 pub async fn run(cmd: CodeSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
@@ -10,35 +59,167 @@ pub async fn run(cmd: CodeSubCmd, client: &NovaClient, output_format: &str) -> R
             let result = client.get(&format!("/functions/{name}/code")).await?;
             if output_format == "table" || output_format == "wide" {
                 if let Some(code) = result.get("code").and_then(|v| v.as_str()) {
-                    println!("{code}");
+                    output::print_text(code);
                 } else {
-                    println!("{}", serde_json::to_string_pretty(&result)?);
+                    output::print_text(&serde_json::to_string_pretty(&result)?);
                 }
             } else {
-                output::render_single(&result, &[], output_format);
+                output::render_single(&result, &[], output_format)?;
+            }
+        }
+        CodeSubCmd::Update {
+            name,
+            code_dir: Some(dir),
+            entry_point,
+            ..
+        } => {
+            let result = upload_dir(client, &name, &dir, entry_point.as_deref()).await?;
+            output::print_success(&format!("Code updated for '{name}'."));
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, &[], output_format)?;
             }
         }
-        CodeSubCmd::Update { name, code, file } => {
+        CodeSubCmd::Update {
+            name,
+            code,
+            file,
+            compress,
+            ..
+        } => {
             let code_value = match (code, file) {
                 (Some(c), _) => c,
-                (_, Some(path)) => std::fs::read_to_string(&path).map_err(|e| {
-                    crate::error::OrbitError::Input(format!("Cannot read file {path}: {e}"))
-                })?,
+                (_, Some(path)) => std::fs::read_to_string(&path)
+                    .map_err(|e| OrbitError::Input(format!("Cannot read file {path}: {e}")))?,
                 _ => {
-                    return Err(crate::error::OrbitError::Input(
-                        "Provide --code or --file".into(),
-                    ));
+                    return Err(OrbitError::Input("Provide --code or --file".into()));
                 }
             };
             let body = json!({ "code": code_value });
-            let result = client
-                .put(&format!("/functions/{name}/code"), &body)
-                .await?;
+            let path = format!("/functions/{name}/code");
+            let result = if compress.no_compress {
+                client.put(&path, &body).await?
+            } else {
+                client.put_compressed(&path, &body).await?
+            };
             output::print_success(&format!("Code updated for '{name}'."));
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&result, &[], output_format);
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, &[], output_format)?;
             }
         }
     }
     Ok(())
 }
+
+/// `orbit functions diff`: the local code of `name` (a `file` compared with
+/// the deployed entry file, or every packaged file of `dir`) against what is
+/// deployed, as unified diffs. Any difference is an error, so CI can use it
+/// as a drift check.
+pub async fn diff(
+    client: &NovaClient,
+    name: &str,
+    dir: Option<String>,
+    file: Option<String>,
+    context: usize,
+    output_format: &str,
+) -> Result<()> {
+    let code = client.get(&format!("/functions/{name}/code")).await?;
+    let source = code
+        .get("source_code")
+        .or_else(|| code.get("code"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let read = |path: &Path| {
+        std::fs::read(path)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .map_err(|e| OrbitError::Input(format!("Cannot read file {}: {e}", path.display())))
+    };
+
+    // Deployed and local content per path; `None` where a side lacks it.
+    let mut files: BTreeMap<String, (Option<String>, Option<String>)> = BTreeMap::new();
+    let local_root = match file {
+        Some(file) => {
+            files.insert(file.clone(), (Some(source), Some(read(Path::new(&file))?)));
+            None
+        }
+        None => {
+            let dir = dir.unwrap_or_else(|| ".".to_string());
+            let paths = package::list_files(Path::new(&dir), FUNCTION_MANIFESTS)?;
+            for path in &paths {
+                files.entry(path.clone()).or_default().1 = Some(read(&Path::new(&dir).join(path))?);
+            }
+            let deployed = client
+                .get(&format!("/functions/{name}/files/content"))
+                .await?;
+            let deployed = deployed
+                .get("files")
+                .and_then(|f| f.as_object())
+                .filter(|f| !f.is_empty());
+            match deployed {
+                Some(deployed) => {
+                    for (path, content) in deployed {
+                        files.entry(path.clone()).or_default().0 =
+                            Some(output::format_value(content));
+                    }
+                }
+                // Single-file code: compare with the local entry file.
+                None => {
+                    let entry = package::entry_file(&paths, None).unwrap_or("handler");
+                    files.entry(entry.to_string()).or_default().0 = Some(source);
+                }
+            }
+            Some(dir)
+        }
+    };
+
+    let mut rows = Vec::new();
+    let mut diffs = String::new();
+    for (path, (deployed, local)) in &files {
+        let old_label = match deployed {
+            Some(_) => format!("{name}/{path}"),
+            None => "/dev/null".to_string(),
+        };
+        let new_label = match (local, &local_root) {
+            (None, _) => "/dev/null".to_string(),
+            (Some(_), Some(root)) => Path::new(root).join(path).display().to_string(),
+            (Some(_), None) => path.clone(),
+        };
+        let Some(text) = diff::unified(
+            deployed.as_deref().unwrap_or_default(),
+            local.as_deref().unwrap_or_default(),
+            &old_label,
+            &new_label,
+            context,
+        ) else {
+            continue;
+        };
+        let status = match (deployed, local) {
+            (None, _) => "local only",
+            (_, None) => "deployed only",
+            _ => "changed",
+        };
+        rows.push(json!({ "path": path, "status": status, "diff": text }));
+        diffs.push_str(&text);
+    }
+
+    if output::is_machine_readable(output_format) {
+        output::render(
+            &json!({ "function": name, "differs": !rows.is_empty(), "files": rows }),
+            &[],
+            output_format,
+        )?;
+    } else {
+        output::print_diff(&diffs);
+    }
+    if !rows.is_empty() {
+        // `--out-file` is otherwise only written on success, but the diff
+        // is the result here even though differences fail the command.
+        output::write_out_file()?;
+        return Err(OrbitError::Input(format!(
+            "Local code differs from deployed '{name}' in {} file(s)",
+            rows.len()
+        )));
+    }
+    output::print_success(&format!("Local code matches deployed '{name}'."));
+    Ok(())
+}
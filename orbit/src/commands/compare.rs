@@ -0,0 +1,117 @@
+//! `orbit functions compare`: two functions' configurations side by side,
+//! for "works in staging, not in prod" investigations.
+
+use crate::client::NovaClient;
+use crate::error::Result;
+use crate::output::{self, Column};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeSet;
+
+/// Fields that identify or date a function rather than configure it.
+const IGNORED_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "function_id",
+    "tenant_id",
+    "namespace",
+    "version",
+    "code",
+    "created_at",
+    "updated_at",
+];
+
+/// Flattens `value` into `out` as dotted paths; `env_vars` contributes only
+/// its keys, since values may be secrets.
+fn flatten(prefix: &str, value: &Value, out: &mut Map<String, Value>) {
+    match value {
+        Value::Object(map) if prefix == "env_vars" => {
+            for key in map.keys() {
+                out.insert(format!("env_vars.{key}"), json!("set"));
+            }
+        }
+        Value::Object(map) => {
+            for (key, v) in map {
+                if prefix.is_empty() && IGNORED_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&path, v, out);
+            }
+        }
+        Value::Null => {}
+        v => {
+            out.insert(prefix.to_string(), v.clone());
+        }
+    }
+}
+
+/// The function's configuration, flattened, with its layers when the server
+/// reports them.
+async fn config(client: &NovaClient, name: &str) -> Result<Map<String, Value>> {
+    let function = client.get(&format!("/functions/{name}")).await?;
+    let mut config = Map::new();
+    flatten("", &function, &mut config);
+    if let Ok(Value::Array(layers)) = client.get(&format!("/functions/{name}/layers")).await {
+        let names: Vec<&str> = layers.iter().filter_map(|l| l["name"].as_str()).collect();
+        if !names.is_empty() {
+            config.insert("layers".into(), json!(names.join(", ")));
+        }
+    }
+    Ok(config)
+}
+
+pub async fn run(
+    a: &str,
+    b: &str,
+    diff_only: bool,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let (config_a, config_b) = tokio::try_join!(config(client, a), config(client, b))?;
+    let fields: BTreeSet<&String> = config_a.keys().chain(config_b.keys()).collect();
+    let mut rows: Vec<Value> = fields
+        .into_iter()
+        .filter_map(|field| {
+            let (va, vb) = (config_a.get(field), config_b.get(field));
+            let differs = va != vb;
+            (differs || !diff_only).then(|| {
+                json!({
+                    "field": field,
+                    "a": va,
+                    "b": vb,
+                    "differs": differs,
+                    "marker": if differs { "≠" } else { "" },
+                })
+            })
+        })
+        .collect();
+    let differing = rows.iter().filter(|r| r["differs"] == true).count();
+    if output::is_machine_readable(output_format) {
+        for row in &mut rows {
+            if let Some(row) = row.as_object_mut() {
+                row.remove("marker");
+            }
+        }
+    }
+
+    // Headers name the functions, so they are built at runtime; the leak is
+    // bounded to one compare per process.
+    let columns = [
+        Column::new("", "marker"),
+        Column::new("Field", "field"),
+        Column::new(Box::leak(a.to_string().into_boxed_str()), "a"),
+        Column::new(Box::leak(b.to_string().into_boxed_str()), "b"),
+    ];
+    output::render(&Value::Array(rows), &columns, output_format)?;
+    if !output::is_machine_readable(output_format) {
+        match differing {
+            0 => output::print_success(&format!("'{a}' and '{b}' are configured the same.")),
+            n => output::print_warning(&format!("{n} field(s) differ between '{a}' and '{b}'.")),
+        }
+    }
+    Ok(())
+}
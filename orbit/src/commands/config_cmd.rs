@@ -1,7 +1,18 @@
 use crate::client::NovaClient;
-use crate::error::Result;
-use crate::output;
+use crate::config::{ConfigEncryption, OrbitConfig, ProjectConfig};
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
 use clap::Subcommand;
+use serde_json::{Value, json};
+
+const CONTEXT_COLUMNS: &[Column] = &[
+    Column::new("Current", "current"),
+    Column::new("Name", "name"),
+    Column::new("Server", "server"),
+    Column::new("Tenant", "tenant"),
+    Column::new("Namespace", "namespace"),
+    Column::new("Auth", "auth"),
+];
 
 #[derive(Subcommand)]
 pub enum ConfigCmd {
@@ -9,31 +20,218 @@ pub enum ConfigCmd {
     Get,
     /// Set a configuration value
     Set {
-        /// Key to set (server, api_key, tenant, namespace, output)
+        /// Key to set (server, api_key, tenant, namespace, output, timeout, proxy,
+        /// compress, client_cert, client_key, ca_cert, auth_mode, hmac_secret, header)
         key: String,
-        /// Value
+        /// Value; for `header`, KEY=VALUE (an empty VALUE removes the header)
         value: String,
+        /// Store the value in the OS keyring instead of the config file
+        /// (api_key only)
+        #[arg(long)]
+        keyring: bool,
+    },
+    /// Remove a configuration value
+    Unset {
+        /// Key to remove (same keys as `set`); `header` removes every header
+        key: String,
+    },
+    /// Open the config file in $VISUAL or $EDITOR
+    Edit,
+    /// Print the configuration, for sharing with `config import`
+    Export {
+        /// Leave out API keys, secrets, tokens and credential headers
+        #[arg(long)]
+        redact: bool,
+        /// Output format (toml, json)
+        #[arg(long, default_value = "toml")]
+        format: String,
     },
+    /// Merge a configuration file exported with `config export`
+    Import {
+        /// TOML or JSON file (by extension; TOML otherwise)
+        file: String,
+    },
+    /// Move the legacy ~/.orbit/config.toml to the XDG config directory
+    Migrate,
+    /// Encrypt stored API keys, secrets and tokens with a passphrase (or
+    /// change the passphrase). Read from ORBIT_CONFIG_PASSPHRASE if set.
+    Encrypt,
+    /// Store credentials as plain text again
+    Decrypt,
+    /// Make a context the default for later commands
+    UseContext {
+        /// Context name
+        name: String,
+    },
+    /// List configured contexts
+    GetContexts,
+    /// Let the nearest .orbit.toml send the stored credentials to the server
+    /// it sets
+    TrustProject,
+    /// Rename a context
+    RenameContext {
+        /// Current name
+        old: String,
+        /// New name
+        new: String,
+    },
+}
+
+fn redact_proxy(proxy: &str) -> String {
+    match reqwest::Url::parse(proxy) {
+        Ok(mut url) if url.password().is_some() => {
+            let _ = url.set_password(Some("***"));
+            url.to_string()
+        }
+        _ => proxy.to_string(),
+    }
+}
+
+fn auth_kind(config: &OrbitConfig) -> &'static str {
+    if config.auth_mode.as_deref() == Some("hmac") {
+        "hmac"
+    } else if config.token.is_some() {
+        "login"
+    } else if config.api_key.is_some() {
+        "api-key"
+    } else {
+        "none"
+    }
+}
+
+/// Moves the legacy config file to the XDG location, which `config_path`
+/// prefers from then on.
+fn migrate_config() -> Result<()> {
+    let legacy = crate::config::legacy_config_path();
+    let target = crate::config::xdg_config_path();
+    if !legacy.exists() {
+        output::print_success(&format!(
+            "Nothing to migrate: {} not found",
+            legacy.display()
+        ));
+        return Ok(());
+    }
+    if target.exists() {
+        return Err(OrbitError::Input(format!(
+            "{} already exists; merge {} into it with 'orbit config import' and remove the old file",
+            target.display(),
+            legacy.display()
+        )));
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // A rename fails across filesystems, e.g. a home directory on a mount.
+    if std::fs::rename(&legacy, &target).is_err() {
+        std::fs::copy(&legacy, &target)?;
+        std::fs::remove_file(&legacy)?;
+    }
+    output::print_success(&format!(
+        "Moved {} to {}",
+        legacy.display(),
+        target.display()
+    ));
+    Ok(())
+}
+
+/// Edits a copy of the config file and only replaces the original once the
+/// copy parses, offering to reopen the editor on errors.
+fn edit_config() -> Result<()> {
+    let path = OrbitConfig::config_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let draft = path.with_extension("toml.edit");
+    let original = std::fs::read_to_string(&path).unwrap_or_default();
+    std::fs::write(&draft, &original)?;
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".into());
+
+    loop {
+        // Run through the shell so editors with arguments ("code -w") work.
+        let status = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("{editor} \"$1\""))
+            .arg("sh")
+            .arg(&draft)
+            .status()
+            .map_err(|e| OrbitError::Input(format!("Cannot run editor '{editor}': {e}")))?;
+        if !status.success() {
+            let _ = std::fs::remove_file(&draft);
+            return Err(OrbitError::Input(format!(
+                "Editor '{editor}' exited with {status}; config left unchanged"
+            )));
+        }
+        let edited = std::fs::read_to_string(&draft)?;
+        if edited == original {
+            let _ = std::fs::remove_file(&draft);
+            output::print_status("No changes made.");
+            return Ok(());
+        }
+        match OrbitConfig::parse(&edited) {
+            Ok(_) => {
+                std::fs::rename(&draft, &path)?;
+                output::print_success(&format!("Saved {}", path.display()));
+                return Ok(());
+            }
+            Err(e) => {
+                output::print_error(&e.to_string());
+                if !output::confirm("Reopen the editor?") {
+                    let _ = std::fs::remove_file(&draft);
+                    return Err(OrbitError::Config(
+                        "Edit discarded; config left unchanged".into(),
+                    ));
+                }
+            }
+        }
+    }
 }
 
-pub async fn run(cmd: ConfigCmd, _client: &NovaClient, output_format: &str) -> Result<()> {
+pub async fn run(cmd: ConfigCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         ConfigCmd::Get => {
-            let config = crate::config::OrbitConfig::load();
-            let value = serde_json::to_value(&config)?;
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&value, &[], output_format);
+            let (project_path, project) = ProjectConfig::discover()?.unzip();
+            let config = OrbitConfig::load()
+                .resolve(client.context())?
+                .apply_project(project.unwrap_or_default(), project_path.as_deref());
+            let mut value = serde_json::to_value(&config)?;
+            if let Some(map) = value.as_object_mut() {
+                map.remove("profiles");
+                map.remove("current_context");
+                map.insert("context".into(), json!(client.context()));
+                map.insert(
+                    "project_config".into(),
+                    json!(project_path.as_ref().map(|p| p.display().to_string())),
+                );
+            }
+            if let Some(token) = &config.token {
+                value["token"] = serde_json::json!({ "expires_at": token.expires_at });
+            }
+            if config.hmac_secret.is_some() {
+                value["hmac_secret"] = serde_json::json!("***");
+            }
+            if config.api_key_keyring {
+                value["api_key"] = serde_json::json!("***");
+            }
+            if output::is_machine_readable(output_format) {
+                output::render_single(&value, &[], output_format)?;
             } else {
+                println!("context:   {}", client.context().unwrap_or("(none)"));
+                if let Some(path) = &project_path {
+                    println!("project:   {}", path.display());
+                }
                 println!(
                     "server:    {}",
                     config.server.as_deref().unwrap_or("(not set)")
                 );
                 println!(
                     "api_key:   {}",
-                    if config.api_key.is_some() {
-                        "***"
-                    } else {
-                        "(not set)"
+                    match (&config.api_key, config.api_key_keyring) {
+                        (Some(_), true) => "*** (keyring)",
+                        (Some(_), false) => "***",
+                        (None, true) => "(keyring, unavailable)",
+                        (None, false) => "(not set)",
                     }
                 );
                 println!(
@@ -45,24 +243,374 @@ pub async fn run(cmd: ConfigCmd, _client: &NovaClient, output_format: &str) -> R
                     config.namespace.as_deref().unwrap_or("(not set)")
                 );
                 println!("output:    {}", config.output.as_deref().unwrap_or("table"));
+                println!(
+                    "timeout:   {}",
+                    config
+                        .timeout
+                        .map(|t| format!("{t}s"))
+                        .unwrap_or_else(|| "(not set)".into())
+                );
+                println!(
+                    "proxy:     {}",
+                    config
+                        .proxy
+                        .as_deref()
+                        .map(redact_proxy)
+                        .unwrap_or_else(|| "(not set)".into())
+                );
+                println!(
+                    "compress:  {}",
+                    if config.compress.unwrap_or(false) {
+                        "on"
+                    } else {
+                        "off"
+                    }
+                );
+                println!(
+                    "client_cert: {}",
+                    config.client_cert.as_deref().unwrap_or("(not set)")
+                );
+                println!(
+                    "client_key:  {}",
+                    config.client_key.as_deref().unwrap_or("(not set)")
+                );
+                println!(
+                    "ca_cert:     {}",
+                    config.ca_cert.as_deref().unwrap_or("(not set)")
+                );
+                println!(
+                    "auth_mode:   {}",
+                    config.auth_mode.as_deref().unwrap_or("api-key")
+                );
+                println!(
+                    "hmac_secret: {}",
+                    if config.hmac_secret.is_some() {
+                        "***"
+                    } else {
+                        "(not set)"
+                    }
+                );
+                if config.headers.is_empty() {
+                    println!("headers:     (not set)");
+                } else {
+                    let names: Vec<&str> = config
+                        .headers
+                        .iter()
+                        .map(|h| h.split_once('=').map_or(h.as_str(), |(n, _)| n))
+                        .collect();
+                    println!("headers:     {}", names.join(", "));
+                }
+                println!(
+                    "login:       {}",
+                    match &config.token {
+                        Some(t) => match t.expires_at {
+                            Some(exp) => format!("logged in (expires {})", exp.to_rfc3339()),
+                            None => "logged in".into(),
+                        },
+                        None => "(not logged in)".into(),
+                    }
+                );
+            }
+        }
+        ConfigCmd::Set {
+            key,
+            value,
+            keyring,
+        } => {
+            let is_api_key = key == "api_key" || key == "api-key";
+            if keyring && !is_api_key {
+                return Err(OrbitError::Input(
+                    "--keyring is only supported for api_key".into(),
+                ));
+            }
+            let mut config = OrbitConfig::load();
+            let section = config.section_mut(client.context());
+            match key.as_str() {
+                "server" => section.server = Some(value),
+                "api_key" | "api-key" => {
+                    if keyring {
+                        crate::config::store_keyring_api_key(client.context(), &value)?;
+                    } else if section.api_key_keyring {
+                        crate::config::delete_keyring_api_key(client.context())?;
+                    }
+                    section.api_key = Some(value);
+                    section.api_key_keyring = keyring;
+                }
+                "tenant" => section.tenant = Some(value),
+                "namespace" => section.namespace = Some(value),
+                "output" => {
+                    output::validate_format(&value)?;
+                    section.output = Some(value);
+                }
+                "timeout" => {
+                    let secs = value.parse::<u64>().map_err(|_| {
+                        OrbitError::Input(format!(
+                            "Invalid timeout '{value}': expected whole seconds"
+                        ))
+                    })?;
+                    section.timeout = Some(secs);
+                }
+                "proxy" => section.proxy = Some(value),
+                "compress" => {
+                    let on = value.parse::<bool>().map_err(|_| {
+                        OrbitError::Input(format!(
+                            "Invalid compress '{value}': expected true or false"
+                        ))
+                    })?;
+                    section.compress = Some(on);
+                }
+                "client_cert" | "client-cert" => section.client_cert = Some(value),
+                "client_key" | "client-key" => section.client_key = Some(value),
+                "ca_cert" | "ca-cert" => section.ca_cert = Some(value),
+                "auth_mode" | "auth-mode" => {
+                    if value != "api-key" && value != "hmac" {
+                        return Err(OrbitError::Input(format!(
+                            "Invalid auth_mode '{value}': expected api-key or hmac"
+                        )));
+                    }
+                    section.auth_mode = Some(value);
+                }
+                "hmac_secret" | "hmac-secret" => section.hmac_secret = Some(value),
+                "header" | "headers" => {
+                    let name = match value.split_once('=') {
+                        Some((name, "")) => name.trim().to_string(),
+                        _ => crate::client::parse_header(&value)?.0,
+                    };
+                    section.headers.retain(|h| {
+                        h.split_once('=')
+                            .is_none_or(|(n, _)| !n.trim().eq_ignore_ascii_case(&name))
+                    });
+                    if !value.ends_with('=') {
+                        section.headers.push(value);
+                    }
+                }
+                _ => {
+                    return Err(OrbitError::Input(format!(
+                        "Unknown key '{key}'. Valid keys: server, api_key, tenant, namespace, output, timeout, proxy, compress, client_cert, client_key, ca_cert, auth_mode, hmac_secret, header"
+                    )));
+                }
+            }
+            config.save()?;
+            match (client.context(), keyring) {
+                (Some(ctx), true) => output::print_success(&format!(
+                    "Stored '{key}' for context '{ctx}' in the OS keyring"
+                )),
+                (None, true) => output::print_success(&format!("Stored '{key}' in the OS keyring")),
+                (Some(ctx), false) => {
+                    output::print_success(&format!("Set '{key}' for context '{ctx}'"))
+                }
+                (None, false) => output::print_success(&format!(
+                    "Set '{key}' in {}",
+                    OrbitConfig::config_path().display()
+                )),
             }
         }
-        ConfigCmd::Set { key, value } => {
-            let mut config = crate::config::OrbitConfig::load();
+        ConfigCmd::Unset { key } => {
+            let mut config = OrbitConfig::load();
+            let section = config.section_mut(client.context());
             match key.as_str() {
-                "server" => config.server = Some(value),
-                "api_key" | "api-key" => config.api_key = Some(value),
-                "tenant" => config.tenant = Some(value),
-                "namespace" => config.namespace = Some(value),
-                "output" => config.output = Some(value),
+                "server" => section.server = None,
+                "api_key" | "api-key" => {
+                    if section.api_key_keyring {
+                        crate::config::delete_keyring_api_key(client.context())?;
+                    }
+                    section.api_key = None;
+                    section.api_key_keyring = false;
+                }
+                "tenant" => section.tenant = None,
+                "namespace" => section.namespace = None,
+                "output" => section.output = None,
+                "timeout" => section.timeout = None,
+                "proxy" => section.proxy = None,
+                "compress" => section.compress = None,
+                "client_cert" | "client-cert" => section.client_cert = None,
+                "client_key" | "client-key" => section.client_key = None,
+                "ca_cert" | "ca-cert" => section.ca_cert = None,
+                "auth_mode" | "auth-mode" => section.auth_mode = None,
+                "hmac_secret" | "hmac-secret" => section.hmac_secret = None,
+                "header" | "headers" => section.headers.clear(),
                 _ => {
-                    return Err(crate::error::OrbitError::Input(format!(
-                        "Unknown key '{key}'. Valid keys: server, api_key, tenant, namespace, output"
+                    return Err(OrbitError::Input(format!(
+                        "Unknown key '{key}'. Valid keys: server, api_key, tenant, namespace, output, timeout, proxy, compress, client_cert, client_key, ca_cert, auth_mode, hmac_secret, header"
                     )));
                 }
             }
             config.save()?;
-            output::print_success(&format!("Set '{key}' in ~/.orbit/config.toml"));
+            match client.context() {
+                Some(ctx) => output::print_success(&format!("Unset '{key}' for context '{ctx}'")),
+                None => output::print_success(&format!(
+                    "Unset '{key}' in {}",
+                    OrbitConfig::config_path().display()
+                )),
+            }
+        }
+        ConfigCmd::Edit => edit_config()?,
+        ConfigCmd::Migrate => migrate_config()?,
+        ConfigCmd::Encrypt => {
+            let mut config = OrbitConfig::load();
+            let rekey = config.encryption.is_some();
+            let passphrase = crate::passphrase::read_passphrase(true)?;
+            config.encryption = Some(ConfigEncryption::new(&passphrase)?);
+            config.save()?;
+            let path = OrbitConfig::config_path();
+            if rekey {
+                output::print_success(&format!(
+                    "Re-encrypted credentials in {} with the new passphrase",
+                    path.display()
+                ));
+            } else {
+                output::print_success(&format!(
+                    "Encrypted credentials in {}. Commands will ask for the passphrase, or read {}.",
+                    path.display(),
+                    crate::passphrase::PASSPHRASE_ENV
+                ));
+            }
+        }
+        ConfigCmd::Decrypt => {
+            let mut config = OrbitConfig::load();
+            if config.encryption.take().is_none() {
+                output::print_success("Config is not encrypted");
+                return Ok(());
+            }
+            config.save()?;
+            output::print_success(&format!(
+                "Credentials in {} are stored as plain text again",
+                OrbitConfig::config_path().display()
+            ));
+        }
+        ConfigCmd::Export { redact, format } => {
+            let mut config = OrbitConfig::load();
+            // Exports carry decrypted values; the importing side encrypts
+            // them under its own passphrase, if any.
+            config.encryption = None;
+            if redact {
+                config.redact();
+            }
+            let text = match format.as_str() {
+                "toml" => toml::to_string_pretty(&config)
+                    .map_err(|e| OrbitError::Config(e.to_string()))?,
+                "json" => serde_json::to_string_pretty(&config)?,
+                other => {
+                    return Err(OrbitError::Input(format!(
+                        "Unknown format '{other}': expected toml or json"
+                    )));
+                }
+            };
+            print!("{text}");
+            if format == "json" {
+                println!();
+            }
+        }
+        ConfigCmd::Import { file } => {
+            let content = std::fs::read_to_string(&file)
+                .map_err(|e| OrbitError::Input(format!("Cannot read file {file}: {e}")))?;
+            let mut imported = if file.ends_with(".json") {
+                let config: OrbitConfig = serde_json::from_str(&content)
+                    .map_err(|e| OrbitError::Input(format!("Invalid config {file}: {e}")))?;
+                OrbitConfig::parse(
+                    &toml::to_string(&config).map_err(|e| OrbitError::Config(e.to_string()))?,
+                )?
+            } else {
+                OrbitConfig::parse(&content)?
+            };
+            if imported
+                .credential_fields()
+                .iter()
+                .any(|v| crate::passphrase::is_encrypted(v))
+            {
+                return Err(OrbitError::Input(format!(
+                    "{file} holds encrypted credentials; import a 'config export' of it instead"
+                )));
+            }
+            let mut config = OrbitConfig::load();
+            let added = imported
+                .profiles
+                .keys()
+                .filter(|name| !config.profiles.contains_key(*name))
+                .count();
+            let total = imported.profiles.len();
+            config.merge(imported);
+            config.save()?;
+            output::print_success(&format!(
+                "Imported {file}: {total} context(s), {added} new."
+            ));
+        }
+        ConfigCmd::UseContext { name } => {
+            let mut config = OrbitConfig::load();
+            if !config.profiles.contains_key(&name) {
+                return Err(OrbitError::Input(format!("Unknown context '{name}'")));
+            }
+            config.current_context = Some(name.clone());
+            config.save()?;
+            output::print_success(&format!("Switched to context '{name}'."));
+        }
+        ConfigCmd::GetContexts => {
+            let config = OrbitConfig::load();
+            let current = config.current_context.as_deref();
+            let rows: Vec<Value> = config
+                .profiles
+                .iter()
+                .map(|(name, ctx)| {
+                    json!({
+                        "current": if current == Some(name.as_str()) { "*" } else { "" },
+                        "name": name,
+                        "server": ctx.server.as_deref().or(config.server.as_deref()),
+                        "tenant": ctx.tenant.as_deref().or(config.tenant.as_deref()),
+                        "namespace": ctx.namespace.as_deref().or(config.namespace.as_deref()),
+                        "auth": auth_kind(ctx),
+                    })
+                })
+                .collect();
+            if rows.is_empty() && !output::is_machine_readable(output_format) {
+                println!(
+                    "No contexts configured. Create one with 'orbit config set --profile <name> server <url>'."
+                );
+                return Ok(());
+            }
+            output::render(&Value::Array(rows), CONTEXT_COLUMNS, output_format)?;
+        }
+        ConfigCmd::TrustProject => {
+            let Some((path, project)) = ProjectConfig::discover()? else {
+                return Err(OrbitError::Input(format!(
+                    "No {} found in this directory or its parents",
+                    crate::config::PROJECT_CONFIG_FILE
+                )));
+            };
+            let Some(server) = project.server else {
+                output::print_success(&format!(
+                    "{} does not set a server; nothing to trust",
+                    path.display()
+                ));
+                return Ok(());
+            };
+            let mut config = OrbitConfig::load();
+            config
+                .trusted_projects
+                .insert(crate::config::project_key(&path), server.clone());
+            config.save()?;
+            output::print_success(&format!(
+                "Trusted {} to use the stored credentials with {server}",
+                path.display()
+            ));
+        }
+        ConfigCmd::RenameContext { old, new } => {
+            let mut config = OrbitConfig::load();
+            if config.profiles.contains_key(&new) {
+                return Err(OrbitError::Input(format!("Context '{new}' already exists")));
+            }
+            let ctx = config
+                .profiles
+                .remove(&old)
+                .ok_or_else(|| OrbitError::Input(format!("Unknown context '{old}'")))?;
+            if ctx.api_key_keyring {
+                crate::config::rename_keyring_api_key(&old, &new)?;
+            }
+            config.profiles.insert(new.clone(), ctx);
+            if config.current_context.as_deref() == Some(old.as_str()) {
+                config.current_context = Some(new.clone());
+            }
+            config.save()?;
+            output::print_success(&format!("Renamed context '{old}' to '{new}'."));
         }
     }
     Ok(())
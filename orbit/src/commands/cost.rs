@@ -49,17 +49,19 @@ pub async fn run(cmd: CostCmd, client: &NovaClient, output_format: &str) -> Resu
                 .get(&format!("/cost/summary?window={window}"))
                 .await?;
             if let Some(functions) = result.get("functions") {
-                output::render(functions, COST_SUMMARY_COLUMNS, output_format);
+                output::render(functions, COST_SUMMARY_COLUMNS, output_format)?;
             }
-            if let Some(total) = result.get("total_cost") {
-                println!("\nTotal Cost: {total}");
+            if let Some(total) = result.get("total_cost")
+                && !output::is_machine_readable(output_format)
+            {
+                output::print_line(&format!("\nTotal Cost: {total}"));
             }
         }
         CostCmd::Function { name, window } => {
             let result = client
                 .get(&format!("/functions/{name}/cost?window={window}"))
                 .await?;
-            output::render_single(&result, FUNCTION_COST_COLUMNS, output_format);
+            output::render_single(&result, FUNCTION_COST_COLUMNS, output_format)?;
         }
     }
     Ok(())
@@ -0,0 +1,1175 @@
+use crate::client::NovaClient;
+use crate::error::{OrbitError, Result};
+use crate::manifest::{self, FunctionSpec, ProjectManifest};
+use crate::output::{self, Column};
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use indicatif::ProgressStyle;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Environment describing the deployment, exported to every hook.
+struct HookEnv {
+    vars: Vec<(&'static str, String)>,
+}
+
+impl HookEnv {
+    fn new(client: &NovaClient, manifest_path: &Path, functions: &[&str]) -> Self {
+        let id = format!(
+            "{}-{}",
+            chrono::Utc::now().format("%Y%m%d%H%M%S"),
+            std::process::id()
+        );
+        let functions = functions.join(",");
+        let mut vars = vec![
+            ("ORBIT_DEPLOY_ID", id),
+            ("ORBIT_DEPLOY_FUNCTIONS", functions),
+            (
+                "ORBIT_DEPLOY_MANIFEST",
+                manifest_path.to_string_lossy().into_owned(),
+            ),
+            ("ORBIT_SERVER", client.base_url().to_string()),
+            ("ORBIT_TENANT", client.tenant().unwrap_or("default").into()),
+            (
+                "ORBIT_NAMESPACE",
+                client.namespace().unwrap_or("default").into(),
+            ),
+        ];
+        if let Ok(out) = Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(manifest::base_dir(manifest_path))
+            .output()
+            && out.status.success()
+        {
+            let sha = String::from_utf8_lossy(&out.stdout).trim().to_string();
+            vars.push(("ORBIT_GIT_COMMIT", sha));
+        }
+        Self { vars }
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.vars
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Runs each hook with `sh -c` from the manifest directory, stopping at the
+/// first failure.
+fn run_hooks(
+    phase: &str,
+    hooks: &[String],
+    dir: &Path,
+    env: &HookEnv,
+    dry_run: bool,
+) -> Result<()> {
+    for hook in hooks {
+        if dry_run {
+            output::print_status(&format!("Would run {phase}-deploy hook: {hook}"));
+            continue;
+        }
+        output::print_status(&format!("Running {phase}-deploy hook: {hook}"));
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(hook)
+            .current_dir(dir)
+            .envs(env.vars.iter().map(|(k, v)| (*k, v.as_str())))
+            .env("ORBIT_DEPLOY_PHASE", phase)
+            .status()
+            .map_err(|e| OrbitError::Input(format!("Cannot run hook '{hook}': {e}")))?;
+        if !status.success() {
+            return Err(OrbitError::Input(format!(
+                "{phase}-deploy hook '{hook}' failed ({status})"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Creates the function described by `spec` or updates it if it already
+/// exists, then attaches its layers.
+pub async fn upsert(client: &NovaClient, spec: &FunctionSpec, body: &Value) -> Result<Value> {
+    upsert_from(client, spec, body)
+        .await
+        .map(|(_, result)| result)
+}
+
+/// [`upsert`], also returning the function as it was before if it existed.
+async fn upsert_from(
+    client: &NovaClient,
+    spec: &FunctionSpec,
+    body: &Value,
+) -> Result<(Option<Value>, Value)> {
+    let name = &spec.name;
+    let (before, result) = match client.get(&format!("/functions/{name}")).await {
+        Ok(before) => {
+            let result = client
+                .patch_compressed(&format!("/functions/{name}"), body)
+                .await?;
+            (Some(before), result)
+        }
+        Err(OrbitError::Api { status: 404, .. }) => {
+            (None, client.post_compressed("/functions", body).await?)
+        }
+        Err(e) => return Err(e),
+    };
+    if !spec.layers.is_empty() {
+        client
+            .put(
+                &format!("/functions/{name}/layers"),
+                &json!({ "layers": spec.layers }),
+            )
+            .await?;
+    }
+    Ok((before, result))
+}
+
+/// `orbit functions deploy <dir>`: packages the function directory and
+/// creates or updates the function its manifest describes.
+pub async fn run_dir(dir: &str, client: &NovaClient) -> Result<Value> {
+    let (path, spec) = FunctionSpec::load_dir(Path::new(dir))?;
+    let body = spec.to_package_body(Path::new(dir))?;
+    let files = 1 + body["dependency_files"].as_object().map_or(0, |f| f.len());
+    let result = upsert(client, &spec, &body).await?;
+    let version = result["version"]
+        .as_i64()
+        .map(|v| format!(" (v{v})"))
+        .unwrap_or_default();
+    output::print_status(&format!(
+        "Deployed '{}'{version}: {files} file(s) from {}.",
+        spec.name,
+        path.display()
+    ));
+    Ok(result)
+}
+
+/// What `orbit deploy` deploys out of the project.
+pub struct DeployOptions {
+    pub no_hooks: bool,
+    /// Only these functions
+    pub only: Vec<String>,
+    /// Only functions with files changed since this git ref
+    pub changed_since: Option<String>,
+    /// Also functions unchanged since their last deploy
+    pub force: bool,
+}
+
+/// Resources deployed at once within a level of the dependency graph.
+const PARALLELISM: usize = 8;
+
+/// A resource of the project and what identifies it on the server.
+#[derive(Clone)]
+enum Kind {
+    Layer(String),
+    Function(FunctionSpec),
+    Route { domain: String, path: String },
+    Subscription { topic: String, name: String },
+}
+
+impl Kind {
+    fn kind_name(&self) -> &'static str {
+        match self {
+            Kind::Layer(_) => "layer",
+            Kind::Function(_) => "function",
+            Kind::Route { .. } => "route",
+            Kind::Subscription { .. } => "subscription",
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Kind::Layer(name) => name.clone(),
+            Kind::Function(spec) => spec.name.clone(),
+            Kind::Route { domain, path } => format!("{domain}{path}"),
+            Kind::Subscription { topic, name } => format!("{topic}/{name}"),
+        }
+    }
+}
+
+/// Key of a resource in the deploy state; functions keep their bare name.
+fn state_key(kind: &str, name: &str) -> String {
+    match kind {
+        "function" => name.to_string(),
+        _ => format!("{kind}:{name}"),
+    }
+}
+
+/// A resource of the project, read and ready to deploy.
+struct Resource {
+    kind: Kind,
+    body: Value,
+    /// Files and directories, relative to the manifest directory, whose
+    /// changes affect the resource
+    sources: Vec<PathBuf>,
+    /// Resources of the project deployed before this one
+    needs: Vec<usize>,
+}
+
+impl Resource {
+    fn kind_name(&self) -> &'static str {
+        self.kind.kind_name()
+    }
+
+    fn name(&self) -> String {
+        self.kind.name()
+    }
+
+    fn label(&self) -> String {
+        format!("{} {}", self.kind_name(), self.name())
+    }
+
+    fn state_key(&self) -> String {
+        state_key(self.kind_name(), &self.name())
+    }
+
+    /// Hash of everything deployed, to tell whether the resource changed
+    /// since its last deploy.
+    fn content_hash(&self) -> String {
+        let content = match &self.kind {
+            Kind::Function(spec) => json!({ "body": self.body, "layers": spec.layers }),
+            _ => json!({ "body": self.body }),
+        };
+        hex::encode(Sha256::digest(content.to_string()))
+    }
+
+    fn function(&self) -> Option<&str> {
+        match &self.kind {
+            Kind::Function(spec) => Some(&spec.name),
+            _ => None,
+        }
+    }
+}
+
+/// `path` without `.` components, for comparing with paths from git.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+/// Reads every resource of the project, each after the ones it can depend
+/// on: layers, functions (those inline in the manifest, then the workspace
+/// members, packaged from their directories), then routes and
+/// subscriptions.
+fn resources(
+    manifest: &ProjectManifest,
+    manifest_path: &Path,
+    dir: &Path,
+) -> Result<Vec<Resource>> {
+    let manifest_file: Vec<PathBuf> = manifest_path
+        .file_name()
+        .map(PathBuf::from)
+        .into_iter()
+        .collect();
+    let resource = |kind, body, sources| Resource {
+        kind,
+        body,
+        sources,
+        needs: Vec::new(),
+    };
+    let mut resources = Vec::new();
+    for layer in &manifest.layers {
+        resources.push(resource(
+            Kind::Layer(layer.name.clone()),
+            layer.to_body(),
+            manifest_file.clone(),
+        ));
+    }
+    for spec in &manifest.functions {
+        let mut sources = manifest_file.clone();
+        sources.extend(spec.code.as_deref().map(|c| normalize(Path::new(c))));
+        resources.push(resource(
+            Kind::Function(spec.clone()),
+            spec.to_body(dir)?,
+            sources,
+        ));
+    }
+    for member in manifest.members(dir) {
+        let (_, spec) = FunctionSpec::load_dir(&member)?;
+        let relative = member.strip_prefix(dir).unwrap_or(&member);
+        resources.push(resource(
+            Kind::Function(spec.clone()),
+            spec.to_package_body(&member)?,
+            vec![normalize(relative)],
+        ));
+    }
+    for route in &manifest.routes {
+        let kind = Kind::Route {
+            domain: route.domain.clone(),
+            path: route.path.clone(),
+        };
+        resources.push(resource(kind, route.to_body(), manifest_file.clone()));
+    }
+    for sub in &manifest.subscriptions {
+        let kind = Kind::Subscription {
+            topic: sub.topic.clone(),
+            name: sub.name.clone(),
+        };
+        resources.push(resource(kind, sub.to_body(), manifest_file.clone()));
+    }
+
+    let mut labels = std::collections::HashSet::new();
+    for r in &resources {
+        if !labels.insert(r.label()) {
+            return Err(OrbitError::Input(format!(
+                "{} is defined more than once in the project",
+                capitalize(&r.label())
+            )));
+        }
+    }
+
+    // Functions need the layers they attach, routes and subscriptions the
+    // function they point at, when the project deploys those too.
+    let find = |kind: &str, name: &str| {
+        resources
+            .iter()
+            .position(|r| r.kind_name() == kind && r.name() == name)
+    };
+    let needs: Vec<Vec<usize>> = resources
+        .iter()
+        .map(|r| match &r.kind {
+            Kind::Layer(_) => Vec::new(),
+            Kind::Function(spec) => spec
+                .layers
+                .iter()
+                .filter_map(|l| find("layer", l))
+                .collect(),
+            Kind::Route { .. } | Kind::Subscription { .. } => r.body["function_name"]
+                .as_str()
+                .and_then(|f| find("function", f))
+                .into_iter()
+                .collect(),
+        })
+        .collect();
+    for (r, needs) in resources.iter_mut().zip(needs) {
+        r.needs = needs;
+    }
+    Ok(resources)
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Level of each resource in the dependency graph: 0 for those needing
+/// nothing, otherwise one more than the deepest resource needed. Resources
+/// only need ones listed before them, so one pass settles every level.
+fn levels(resources: &[Resource]) -> Vec<usize> {
+    let mut levels: Vec<usize> = Vec::with_capacity(resources.len());
+    for r in resources {
+        let level = r.needs.iter().map(|n| levels[*n] + 1).max().unwrap_or(0);
+        levels.push(level);
+    }
+    levels
+}
+
+/// What deploying a resource changed, kept in the release record to revert
+/// it.
+#[derive(Clone, Serialize, Deserialize)]
+struct Change {
+    kind: String,
+    name: String,
+    /// `created`, `updated`, or `exists` for a layer left as it was
+    action: String,
+    /// Version deployed, for functions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<i64>,
+    /// What the resource was before: the active version of a function, the
+    /// settings of a route or subscription
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    previous: Value,
+    /// Server ID of a route or subscription
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+}
+
+/// `field`s of `item` that it has.
+fn pick(item: &Value, fields: &[&str]) -> Value {
+    let mut picked = json!({});
+    for field in fields {
+        if let Some(v) = item.get(*field).filter(|v| !v.is_null()) {
+            picked[*field] = v.clone();
+        }
+    }
+    picked
+}
+
+/// Creates the resource or brings it in line with the manifest, returning
+/// what changed.
+async fn apply(client: NovaClient, kind: Kind, body: Value) -> Result<Change> {
+    let change = Change {
+        kind: kind.kind_name().to_string(),
+        name: kind.name(),
+        action: "created".to_string(),
+        version: None,
+        previous: Value::Null,
+        id: None,
+    };
+    let id_of = |item: &Value| match &item["id"] {
+        Value::Null => None,
+        id => Some(output::format_value(id)),
+    };
+    match kind {
+        Kind::Layer(name) => match client.get(&format!("/layers/{name}")).await {
+            Ok(_) => Ok(Change {
+                action: "exists".to_string(),
+                ..change
+            }),
+            Err(OrbitError::Api { status: 404, .. }) => {
+                client.post("/layers", &body).await?;
+                Ok(change)
+            }
+            Err(e) => Err(e),
+        },
+        Kind::Function(spec) => {
+            let (before, result) = upsert_from(&client, &spec, &body).await?;
+            let version = result.get("version").and_then(|v| v.as_i64());
+            Ok(match before {
+                Some(before) => Change {
+                    action: "updated".to_string(),
+                    version,
+                    previous: before["version"].clone(),
+                    ..change
+                },
+                None => Change { version, ..change },
+            })
+        }
+        Kind::Route { domain, path } => {
+            let routes = client.get_all("/gateway/routes").await?;
+            let existing = routes.as_array().and_then(|routes| {
+                routes
+                    .iter()
+                    .find(|r| r["domain"] == domain.as_str() && r["path"] == path.as_str())
+            });
+            match existing.and_then(|r| Some((id_of(r)?, r))) {
+                Some((id, route)) => {
+                    client
+                        .patch(&format!("/gateway/routes/{id}"), &body)
+                        .await?;
+                    let fields = [
+                        "function_name",
+                        "function_alias",
+                        "methods",
+                        "auth_strategy",
+                    ];
+                    let mut previous = pick(route, &fields);
+                    if previous.get("function_alias").is_none() {
+                        // An empty alias routes to the latest version again.
+                        previous["function_alias"] = json!("");
+                    }
+                    Ok(Change {
+                        action: "updated".to_string(),
+                        previous,
+                        id: Some(id),
+                        ..change
+                    })
+                }
+                None => {
+                    let result = client.post("/gateway/routes", &body).await?;
+                    Ok(Change {
+                        id: id_of(&result),
+                        ..change
+                    })
+                }
+            }
+        }
+        Kind::Subscription { topic, name } => {
+            let subs = client
+                .get_all(&format!("/topics/{topic}/subscriptions"))
+                .await?;
+            let existing = subs
+                .as_array()
+                .and_then(|subs| subs.iter().find(|s| s["name"] == name.as_str()));
+            let Some(existing) = existing else {
+                let result = client
+                    .post(&format!("/topics/{topic}/subscriptions"), &body)
+                    .await?;
+                return Ok(Change {
+                    id: id_of(&result),
+                    ..change
+                });
+            };
+            // Only the delivery settings of a subscription can change.
+            if existing["function_name"] != body["function_name"] {
+                return Err(OrbitError::Input(format!(
+                    "it delivers to '{}'; delete it to point it at {}",
+                    output::format_value(&existing["function_name"]),
+                    body["function_name"]
+                )));
+            }
+            let id = output::format_value(&existing["id"]);
+            let fields = ["max_attempts", "max_inflight"];
+            client
+                .patch(&format!("/subscriptions/{id}"), &pick(&body, &fields))
+                .await?;
+            Ok(Change {
+                action: "updated".to_string(),
+                previous: pick(existing, &fields),
+                id: Some(id),
+                ..change
+            })
+        }
+    }
+}
+
+/// How deploying a resource went.
+enum Outcome {
+    Unchanged,
+    Done(Change),
+    Failed(String),
+    /// Not attempted because a resource it needs failed
+    Blocked(String),
+}
+
+impl Outcome {
+    fn is_failure(&self) -> bool {
+        matches!(self, Self::Failed(_) | Self::Blocked(_))
+    }
+
+    fn status(&self) -> String {
+        match self {
+            Self::Unchanged => "unchanged".to_string(),
+            Self::Done(change) => match change.version {
+                Some(v) => format!("{} (v{v})", change.action),
+                None => change.action.clone(),
+            },
+            Self::Failed(e) => format!("failed: {e}"),
+            Self::Blocked(need) => format!("skipped: {need} failed"),
+        }
+    }
+}
+
+/// Prints the deployed resources level by level, as a tree.
+fn print_tree(resources: &[Resource], levels: &[usize], outcomes: &[Option<Outcome>]) {
+    let width = resources.iter().map(|r| r.label().len()).max().unwrap_or(0);
+    let deepest = levels.iter().copied().max().unwrap_or(0);
+    for level in 0..=deepest {
+        let rows: Vec<(&Resource, &Outcome)> = resources
+            .iter()
+            .zip(outcomes)
+            .zip(levels)
+            .filter(|(_, l)| **l == level)
+            .filter_map(|((r, o), _)| Some((r, o.as_ref()?)))
+            .collect();
+        if rows.is_empty() {
+            continue;
+        }
+        output::print_line(&format!("Level {}", level + 1));
+        for (i, (resource, outcome)) in rows.iter().enumerate() {
+            let branch = if i + 1 == rows.len() {
+                "└─"
+            } else {
+                "├─"
+            };
+            output::print_line(&format!(
+                "{branch} {:<width$}  {}",
+                resource.label(),
+                outcome.status()
+            ));
+        }
+    }
+}
+
+/// Paths under `dir`, relative to it, that differ from `git_ref`, including
+/// uncommitted and untracked files.
+fn changed_paths(dir: &Path, git_ref: &str) -> Result<Vec<PathBuf>> {
+    let git = |args: &[&str]| -> Result<String> {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .map_err(|e| OrbitError::Input(format!("Cannot run git: {e}")))?;
+        if !out.status.success() {
+            return Err(OrbitError::Input(format!(
+                "Cannot diff against '{git_ref}': {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    };
+    let diff = git(&["diff", "--name-only", "--relative", git_ref, "--"])?;
+    let untracked = git(&["ls-files", "--others", "--exclude-standard"])?;
+    Ok(diff
+        .lines()
+        .chain(untracked.lines())
+        .filter(|l| !l.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Content hashes of what was last deployed from this machine, per server,
+/// tenant and namespace. Best-effort: a missing or unreadable file only
+/// means everything counts as changed.
+struct DeployState {
+    path: PathBuf,
+    scope: String,
+    hashes: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl DeployState {
+    fn load(client: &NovaClient) -> Self {
+        let path = crate::config::orbit_dir().join("deploy-state.json");
+        let hashes = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            scope: scope(client),
+            hashes,
+        }
+    }
+
+    fn is_current(&self, resource: &Resource) -> bool {
+        self.hashes
+            .get(&self.scope)
+            .and_then(|h| h.get(&resource.state_key()))
+            .is_some_and(|h| *h == resource.content_hash())
+    }
+
+    fn record(&mut self, resource: &Resource) {
+        self.hashes
+            .entry(self.scope.clone())
+            .or_default()
+            .insert(resource.state_key(), resource.content_hash());
+        self.save();
+    }
+
+    /// Drops the hashes of `keys`, so their next deploy isn't skipped.
+    fn forget(&mut self, keys: &[String]) {
+        if let Some(hashes) = self.hashes.get_mut(&self.scope) {
+            hashes.retain(|k, _| !keys.contains(k));
+        }
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Some(parent) = self.path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string_pretty(&self.hashes) {
+            let _ = std::fs::write(&self.path, content);
+        }
+    }
+}
+
+/// Server, tenant and namespace deployed to, keying local deploy records.
+fn scope(client: &NovaClient) -> String {
+    format!(
+        "{} {}/{}",
+        client.base_url(),
+        client.tenant().unwrap_or("default"),
+        client.namespace().unwrap_or("default")
+    )
+}
+
+/// Releases kept per server, tenant and namespace.
+const HISTORY_LIMIT: usize = 50;
+
+/// An `orbit deploy` as recorded for `orbit deploy rollback`.
+#[derive(Serialize, Deserialize)]
+struct Release {
+    id: String,
+    created_at: DateTime<Utc>,
+    manifest: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    git_commit: Option<String>,
+    /// In deploy order
+    changes: Vec<Change>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rolled_back_at: Option<DateTime<Utc>>,
+}
+
+/// Releases deployed from this machine, oldest first, per server, tenant
+/// and namespace.
+struct History {
+    path: PathBuf,
+    scope: String,
+    releases: BTreeMap<String, Vec<Release>>,
+}
+
+impl History {
+    fn load(client: &NovaClient) -> Result<Self> {
+        let path = crate::config::orbit_dir().join("deploy-history.json");
+        let releases = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| {
+                OrbitError::Input(format!("Invalid deploy history {}: {e}", path.display()))
+            })?,
+            Err(_) => BTreeMap::new(),
+        };
+        Ok(Self {
+            path,
+            scope: scope(client),
+            releases,
+        })
+    }
+
+    fn releases(&mut self) -> &mut Vec<Release> {
+        self.releases.entry(self.scope.clone()).or_default()
+    }
+
+    fn push(&mut self, release: Release) {
+        let releases = self.releases();
+        releases.push(release);
+        let excess = releases.len().saturating_sub(HISTORY_LIMIT);
+        releases.drain(..excess);
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.releases)?)?;
+        Ok(())
+    }
+}
+
+/// `orbit deploy`: deploys the project's resources level by level through
+/// their dependency graph, in parallel within a level, then reports how each
+/// went.
+pub async fn run(
+    file: String,
+    options: DeployOptions,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let path = Path::new(&file);
+    let manifest = ProjectManifest::load_or_workspace(path)?;
+    let dir = manifest::base_dir(path);
+
+    // Read every code file up front so a typo fails before hooks run.
+    let resources = resources(&manifest, path, &dir)?;
+    let levels = levels(&resources);
+
+    // Which resources to deploy: the selected functions with the layers
+    // they attach and the routes and subscriptions pointing at them.
+    let mut selected = vec![true; resources.len()];
+    if !options.only.is_empty() {
+        if let Some(unknown) = options.only.iter().find(|name| {
+            !resources
+                .iter()
+                .any(|r| r.function() == Some(name.as_str()))
+        }) {
+            return Err(OrbitError::Input(format!(
+                "No function '{unknown}' in {file}"
+            )));
+        }
+        let chosen = |r: &Resource| {
+            r.function()
+                .is_some_and(|f| options.only.iter().any(|o| o == f))
+        };
+        for (i, r) in resources.iter().enumerate() {
+            selected[i] = match &r.kind {
+                Kind::Layer(_) => resources.iter().any(|f| chosen(f) && f.needs.contains(&i)),
+                Kind::Function(_) => chosen(r),
+                _ => {
+                    r.needs.iter().any(|n| chosen(&resources[*n]))
+                        || r.body["function_name"]
+                            .as_str()
+                            .is_some_and(|f| options.only.iter().any(|o| o == f))
+                }
+            };
+        }
+    }
+    if let Some(git_ref) = &options.changed_since {
+        let changed = changed_paths(&dir, git_ref)?;
+        for (i, r) in resources.iter().enumerate() {
+            selected[i] &= changed
+                .iter()
+                .any(|c| r.sources.iter().any(|s| c.starts_with(s)));
+        }
+    }
+    let mut state = DeployState::load(client);
+    let mut outcomes: Vec<Option<Outcome>> = resources
+        .iter()
+        .zip(&selected)
+        .map(|(r, selected)| {
+            (*selected && !options.force && state.is_current(r)).then_some(Outcome::Unchanged)
+        })
+        .collect();
+    let pending: Vec<usize> = (0..resources.len())
+        .filter(|i| selected[*i] && outcomes[*i].is_none())
+        .collect();
+    let unchanged = outcomes.iter().flatten().count();
+    if pending.is_empty() {
+        output::print_success("Nothing to deploy (use --force to redeploy unchanged resources).");
+        return Ok(());
+    }
+
+    let functions: Vec<&str> = pending
+        .iter()
+        .filter_map(|i| resources[*i].function())
+        .collect();
+    let mut env = HookEnv::new(client, path, &functions);
+    if !options.no_hooks {
+        run_hooks(
+            "pre",
+            &manifest.hooks.pre_deploy,
+            &dir,
+            &env,
+            client.dry_run(),
+        )?;
+    }
+
+    let progress = output::progress_bar("deploy", Some(pending.len() as u64));
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.cyan} Deploying {msg} [{bar:30.cyan/blue}] {pos}/{len}")
+            .unwrap(),
+    );
+    let semaphore = Arc::new(Semaphore::new(PARALLELISM));
+    let deepest = levels.iter().copied().max().unwrap_or(0);
+    for level in 0..=deepest {
+        progress.set_message(format!("level {}", level + 1));
+        let mut tasks = JoinSet::new();
+        for &i in pending.iter().filter(|i| levels[**i] == level) {
+            let failed_need = resources[i]
+                .needs
+                .iter()
+                .find(|n| outcomes[**n].as_ref().is_some_and(Outcome::is_failure));
+            if let Some(n) = failed_need {
+                outcomes[i] = Some(Outcome::Blocked(resources[*n].label()));
+                progress.inc(1);
+                continue;
+            }
+            let client = client.clone();
+            let semaphore = semaphore.clone();
+            let kind = resources[i].kind.clone();
+            let body = resources[i].body.clone();
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                (i, apply(client, kind, body).await)
+            });
+        }
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((i, result)) = joined {
+                outcomes[i] = Some(match result {
+                    Ok(change) => {
+                        if !client.dry_run() {
+                            state.record(&resources[i]);
+                        }
+                        Outcome::Done(change)
+                    }
+                    Err(e) => Outcome::Failed(e.to_string()),
+                });
+            }
+            progress.inc(1);
+        }
+    }
+    progress.finish_and_clear();
+
+    // Record what changed, even in a partial deploy, for `deploy rollback`.
+    let changes: Vec<Change> = outcomes
+        .iter()
+        .filter_map(|o| match o {
+            Some(Outcome::Done(change)) => Some(change.clone()),
+            _ => None,
+        })
+        .collect();
+    let release_id = env.get("ORBIT_DEPLOY_ID").unwrap_or_default().to_string();
+    if !changes.is_empty() && !client.dry_run() {
+        let recorded = History::load(client).and_then(|mut history| {
+            history.push(Release {
+                id: release_id.clone(),
+                created_at: Utc::now(),
+                manifest: file.clone(),
+                git_commit: env.get("ORBIT_GIT_COMMIT").map(String::from),
+                changes,
+                rolled_back_at: None,
+            });
+            history.save()
+        });
+        if let Err(e) = recorded {
+            output::print_warning(&format!("Cannot record release {release_id}: {e}"));
+        }
+    }
+
+    if output::is_machine_readable(output_format) {
+        let rows: Vec<Value> = resources
+            .iter()
+            .zip(&outcomes)
+            .zip(&levels)
+            .filter_map(|((r, o), level)| {
+                let o = o.as_ref()?;
+                Some(json!({
+                    "level": level + 1,
+                    "kind": r.kind_name(),
+                    "name": r.name(),
+                    "needs": r.needs.iter().map(|n| resources[*n].label()).collect::<Vec<_>>(),
+                    "status": o.status(),
+                }))
+            })
+            .collect();
+        output::render(&Value::Array(rows), &[], output_format)?;
+    } else {
+        print_tree(&resources, &levels, &outcomes);
+    }
+
+    let failed = outcomes.iter().flatten().filter(|o| o.is_failure()).count();
+    if failed > 0 {
+        return Err(OrbitError::Input(format!(
+            "{failed} of {} resources were not deployed",
+            pending.len()
+        )));
+    }
+
+    if !options.no_hooks {
+        let versions: Vec<String> = resources
+            .iter()
+            .zip(&outcomes)
+            .filter_map(|(r, o)| match o {
+                Some(Outcome::Done(Change {
+                    version: Some(v), ..
+                })) => Some(format!("{}={v}", r.function()?)),
+                _ => None,
+            })
+            .collect();
+        env.vars.push(("ORBIT_DEPLOY_VERSIONS", versions.join(",")));
+        run_hooks(
+            "post",
+            &manifest.hooks.post_deploy,
+            &dir,
+            &env,
+            client.dry_run(),
+        )?;
+    }
+
+    let skipped = match unchanged {
+        0 => String::new(),
+        n => format!("; {n} unchanged"),
+    };
+    output::print_success(&format!(
+        "Deployed {} resource(s) from {file} as release {release_id}{skipped}.",
+        pending.len()
+    ));
+    Ok(())
+}
+
+#[derive(Subcommand)]
+pub enum DeployCmd {
+    /// List the deploys recorded on this machine, newest first
+    History,
+    /// Revert every resource a deploy changed to what it was before
+    Rollback {
+        /// Release to return to, reverting every later deploy [default: the
+        /// one before the latest]
+        #[arg(long, value_name = "RELEASE_ID")]
+        to: Option<String>,
+        /// Skip confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+const HISTORY_COLUMNS: &[Column] = &[
+    Column::new("Release", "id"),
+    Column::new("Deployed At", "created_at"),
+    Column::new("Manifest", "manifest"),
+    Column::new("Changes", "changes"),
+    Column::new("Rolled Back", "rolled_back_at"),
+    Column::wide("Commit", "git_commit"),
+];
+
+const REVERT_COLUMNS: &[Column] = &[
+    Column::new("Release", "release"),
+    Column::new("Kind", "kind"),
+    Column::new("Name", "name"),
+    Column::new("Revert", "revert"),
+];
+
+pub async fn run_cmd(cmd: DeployCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    match cmd {
+        DeployCmd::History => {
+            let mut history = History::load(client)?;
+            let rows: Vec<Value> = history
+                .releases()
+                .iter()
+                .rev()
+                .map(|r| {
+                    json!({
+                        "id": r.id,
+                        "created_at": r.created_at,
+                        "manifest": r.manifest,
+                        "git_commit": r.git_commit,
+                        "changes": r.changes.iter().filter(|c| c.action != "exists").count(),
+                        "rolled_back_at": r.rolled_back_at,
+                    })
+                })
+                .collect();
+            output::render(&Value::Array(rows), HISTORY_COLUMNS, output_format)?;
+        }
+        DeployCmd::Rollback { to, yes } => rollback(to, yes, client, output_format).await?,
+    }
+    Ok(())
+}
+
+enum Request {
+    Post(String, Value),
+    Patch(String, Value),
+    Delete(String),
+}
+
+/// The request putting the resource of `change` back as it was, described;
+/// `None` when the deploy left it as it was.
+fn revert(change: &Change) -> Option<(String, Request)> {
+    let name = &change.name;
+    let id = change.id.as_deref();
+    let restore = |path: String| {
+        Some((
+            "restore".to_string(),
+            Request::Patch(path, change.previous.clone()),
+        ))
+    };
+    let delete = |path: String| Some(("delete".to_string(), Request::Delete(path)));
+    match (change.kind.as_str(), change.action.as_str()) {
+        ("function", "created") => delete(format!("/functions/{name}")),
+        ("function", "updated") => {
+            let v = change.previous.as_i64()?;
+            Some((
+                format!("activate v{v}"),
+                Request::Post(
+                    format!("/functions/{name}/versions/{v}/activate"),
+                    json!({}),
+                ),
+            ))
+        }
+        ("layer", "created") => delete(format!("/layers/{name}")),
+        ("route", "created") => delete(format!("/gateway/routes/{}", id?)),
+        ("route", "updated") => restore(format!("/gateway/routes/{}", id?)),
+        ("subscription", "created") => delete(format!("/subscriptions/{}", id?)),
+        ("subscription", "updated") => restore(format!("/subscriptions/{}", id?)),
+        _ => None,
+    }
+}
+
+/// `orbit deploy rollback`: reverts the latest deploy, or every deploy after
+/// `to`, newest first and each in reverse deploy order.
+async fn rollback(
+    to: Option<String>,
+    yes: bool,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let mut history = History::load(client)?;
+    let releases = history.releases();
+    // Deploys still in effect, newest first.
+    let live: Vec<usize> = (0..releases.len())
+        .rev()
+        .filter(|i| releases[*i].rolled_back_at.is_none())
+        .collect();
+    let undo: Vec<usize> = match &to {
+        Some(id) => {
+            let at = live
+                .iter()
+                .position(|i| releases[*i].id == *id)
+                .ok_or_else(|| {
+                    OrbitError::Input(format!(
+                        "No release '{id}' in effect; see `orbit deploy history`"
+                    ))
+                })?;
+            live[..at].to_vec()
+        }
+        None => live.first().copied().into_iter().collect(),
+    };
+    if undo.is_empty() {
+        match &to {
+            Some(id) => output::print_success(&format!("Release {id} is the latest deploy.")),
+            None => output::print_success("No deploy to roll back."),
+        }
+        return Ok(());
+    }
+
+    let mut plan = Vec::new();
+    for &r in &undo {
+        for change in releases[r].changes.iter().rev() {
+            if let Some((action, request)) = revert(change) {
+                plan.push((r, change, action, request));
+            }
+        }
+    }
+    let rows: Vec<Value> = plan
+        .iter()
+        .map(|(r, change, action, _)| {
+            json!({
+                "release": releases[*r].id,
+                "kind": change.kind,
+                "name": change.name,
+                "revert": action,
+            })
+        })
+        .collect();
+    if !output::is_machine_readable(output_format) {
+        output::render(&json!(rows), REVERT_COLUMNS, output_format)?;
+    }
+    let ids: Vec<&str> = undo.iter().map(|r| releases[*r].id.as_str()).collect();
+    if !yes
+        && !output::confirm(&format!(
+            "Revert {} change(s) from release(s) {}?",
+            plan.len(),
+            ids.join(", ")
+        ))
+    {
+        output::print_status("Aborted.");
+        return Ok(());
+    }
+
+    let mut reverted = Vec::new();
+    let mut result = Ok(());
+    for (_, change, _, request) in &plan {
+        let sent = match request {
+            Request::Post(path, body) => client.post(path, body).await,
+            Request::Patch(path, body) => client.patch(path, body).await,
+            Request::Delete(path) => client.delete(path).await,
+        };
+        if let Err(e) = sent {
+            result = Err(OrbitError::Input(format!(
+                "Cannot revert {} {}: {e}",
+                change.kind, change.name
+            )));
+            break;
+        }
+        reverted.push(state_key(&change.kind, &change.name));
+    }
+    // Releases whose changes were all reverted no longer count.
+    let finished: Vec<usize> = undo
+        .iter()
+        .copied()
+        .filter(|r| !plan[reverted.len()..].iter().any(|(p, ..)| p == r))
+        .collect();
+    if !client.dry_run() {
+        let now = Utc::now();
+        for r in finished {
+            releases[r].rolled_back_at = Some(now);
+        }
+        // Reverted resources differ from their recorded hashes now.
+        DeployState::load(client).forget(&reverted);
+        history.save()?;
+    }
+    result?;
+
+    if output::is_machine_readable(output_format) {
+        output::render(&json!(rows), REVERT_COLUMNS, output_format)?;
+    } else {
+        let now_at = live.get(undo.len()).map(|r| &history.releases()[*r].id);
+        output::print_success(&match now_at {
+            Some(id) => format!(
+                "Rolled back {} deploy(s); release {id} is in effect.",
+                undo.len()
+            ),
+            None => format!("Rolled back {} deploy(s).", undo.len()),
+        });
+    }
+    Ok(())
+}
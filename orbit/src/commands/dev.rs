@@ -0,0 +1,174 @@
+//! `orbit functions dev`: an inner loop that pushes the local directory on
+//! every save, optionally invokes the function with a saved payload, and
+//! tails its logs. Runs until interrupted.
+
+use crate::client::NovaClient;
+use crate::commands::logs;
+use crate::error::{OrbitError, Result};
+use crate::output;
+use crate::package;
+use notify::{RecursiveMode, Watcher};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+/// Quiet time after a change before pushing, so one save touching several
+/// files (or an editor's write-and-rename) pushes once.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Log entries fetched per poll.
+const LOG_TAIL: u32 = 20;
+
+pub struct DevOptions {
+    pub dir: String,
+    pub entry_point: Option<String>,
+    /// Payload to invoke with after each push
+    pub payload: Option<Value>,
+    pub logs: bool,
+    pub log_interval: Duration,
+}
+
+fn timestamp() -> String {
+    chrono::Local::now().format("%H:%M:%S").to_string()
+}
+
+/// The latest log entries, oldest first; none when they cannot be fetched.
+async fn recent_logs(client: &NovaClient, name: &str) -> Vec<Value> {
+    match client
+        .get(&format!("/functions/{name}/logs?tail={LOG_TAIL}"))
+        .await
+    {
+        Ok(result) => logs::entries(&result),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn log_id(entry: &Value) -> String {
+    logs::field(entry, &["request_id", "id"])
+        .map(output::format_value)
+        .unwrap_or_default()
+}
+
+/// Prints log entries not printed before, oldest first.
+async fn print_new_logs(client: &NovaClient, name: &str, seen: &mut HashSet<String>) {
+    for entry in recent_logs(client, name).await {
+        let id = log_id(&entry);
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        let status = match entry["success"].as_bool() {
+            Some(true) => "success",
+            Some(false) => "failed",
+            None => "-",
+        };
+        let detail = logs::field(&entry, &["error", "error_message"])
+            .or_else(|| logs::field(&entry, &["output"]))
+            .map(output::format_value)
+            .unwrap_or_default();
+        output::print_line(&format!(
+            "{} [log] {id} {status} {}ms {detail}",
+            timestamp(),
+            entry["duration_ms"].as_i64().unwrap_or(0),
+        ));
+    }
+}
+
+async fn invoke(client: &NovaClient, name: &str, payload: &Value) {
+    match client
+        .post(&format!("/functions/{name}/invoke"), payload)
+        .await
+    {
+        Ok(result) => match result["error"].as_str().filter(|e| !e.is_empty()) {
+            Some(error) => output::print_error(&format!("{} [invoke] {error}", timestamp())),
+            None => output::print_line(&format!(
+                "{} [invoke] {}ms {}",
+                timestamp(),
+                result["duration_ms"].as_i64().unwrap_or(0),
+                output::format_value(&result["output"])
+            )),
+        },
+        Err(e) => output::print_error(&format!("{} [invoke] {e}", timestamp())),
+    }
+}
+
+/// Packages and pushes the directory unless its files are unchanged since
+/// `last`, the manifest of the previous push. Returns whether it pushed.
+async fn push(
+    client: &NovaClient,
+    name: &str,
+    options: &DevOptions,
+    last: &mut Vec<Value>,
+) -> Result<bool> {
+    let package = package::zip_dir(Path::new(&options.dir))?;
+    if package.manifest == *last {
+        return Ok(false);
+    }
+    let manifest = package.manifest.clone();
+    crate::commands::code::upload(client, name, package, options.entry_point.as_deref()).await?;
+    output::print_success(&format!(
+        "{} [push] {} file(s) pushed to '{name}'.",
+        timestamp(),
+        manifest.len()
+    ));
+    *last = manifest;
+    Ok(true)
+}
+
+pub async fn run(name: &str, options: DevOptions, client: &NovaClient) -> Result<()> {
+    output::disable_pager();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })
+    .map_err(|e| OrbitError::Input(format!("Cannot watch {}: {e}", options.dir)))?;
+    watcher
+        .watch(Path::new(&options.dir), RecursiveMode::Recursive)
+        .map_err(|e| OrbitError::Input(format!("Cannot watch {}: {e}", options.dir)))?;
+
+    let mut last = Vec::new();
+    let mut seen = HashSet::new();
+    if options.logs {
+        // Only entries from this session are of interest.
+        seen.extend(recent_logs(client, name).await.iter().map(log_id));
+    }
+    push(client, name, &options, &mut last).await?;
+    if let Some(payload) = &options.payload {
+        invoke(client, name, payload).await;
+    }
+    output::print_status(&format!(
+        "Watching {} for changes to '{name}'. Press Ctrl-C to stop.",
+        options.dir
+    ));
+
+    // An interval rather than a sleep per iteration, so a stream of file
+    // events doesn't hold off log polling.
+    let mut log_poll = tokio::time::interval(options.log_interval);
+    log_poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            event = rx.recv() => {
+                let Some(event) = event else { break };
+                if let Err(e) = event {
+                    output::print_warning(&format!("Watch error: {e}"));
+                    continue;
+                }
+                // Let the burst of events from one save settle.
+                while let Ok(Some(_)) = tokio::time::timeout(DEBOUNCE, rx.recv()).await {}
+                match push(client, name, &options, &mut last).await {
+                    Ok(true) => {
+                        if let Some(payload) = &options.payload {
+                            invoke(client, name, payload).await;
+                        }
+                    }
+                    Ok(false) => {}
+                    Err(e) => output::print_error(&format!("{} [push] {e}", timestamp())),
+                }
+            }
+            _ = log_poll.tick(), if options.logs => {
+                print_new_logs(client, name, &mut seen).await;
+            }
+        }
+    }
+    Ok(())
+}
@@ -43,7 +43,7 @@ pub async fn run(cmd: DiagnosticsCmd, client: &NovaClient, output_format: &str)
             let result = client
                 .get(&format!("/functions/{name}/diagnostics"))
                 .await?;
-            output::render_single(&result, DIAGNOSTICS_COLUMNS, output_format);
+            output::render_single(&result, DIAGNOSTICS_COLUMNS, output_format)?;
         }
         DiagnosticsCmd::Analyze { name } => {
             let result = client
@@ -52,19 +52,17 @@ pub async fn run(cmd: DiagnosticsCmd, client: &NovaClient, output_format: &str)
                     &json!({}),
                 )
                 .await?;
-            output::render_single(&result, DIAGNOSTICS_COLUMNS, output_format);
+            output::render_single(&result, DIAGNOSTICS_COLUMNS, output_format)?;
         }
         DiagnosticsCmd::Recommendations { name } => {
             let result = client
                 .get(&format!("/functions/{name}/recommendations"))
                 .await?;
-            output::render(&result, RECOMMENDATION_COLUMNS, output_format);
+            output::render(&result, RECOMMENDATION_COLUMNS, output_format)?;
         }
         DiagnosticsCmd::SloStatus { name } => {
-            let result = client
-                .get(&format!("/functions/{name}/slo/status"))
-                .await?;
-            output::render_single(&result, SLO_STATUS_COLUMNS, output_format);
+            let result = client.get(&format!("/functions/{name}/slo/status")).await?;
+            output::render_single(&result, SLO_STATUS_COLUMNS, output_format)?;
         }
     }
     Ok(())
@@ -22,16 +22,16 @@ const DLQ_COLUMNS: &[Column] = &[
 pub async fn run(cmd: DlqCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         DlqCmd::List => {
-            let result = client.get("/async-invocations/dlq").await?;
-            output::render(&result, DLQ_COLUMNS, output_format);
+            let result = client.get_paged("/async-invocations/dlq").await?;
+            output::render(&result, DLQ_COLUMNS, output_format)?;
         }
         DlqCmd::RetryAll => {
             let result = client
                 .post("/async-invocations/dlq/retry-all", &json!({}))
                 .await?;
             output::print_success("All DLQ entries queued for retry.");
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&result, &[], output_format);
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, &[], output_format)?;
             }
         }
     }
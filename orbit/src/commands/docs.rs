@@ -88,22 +88,18 @@ pub async fn run(cmd: DocsCmd, client: &NovaClient, output_format: &str) -> Resu
     }
 }
 
-async fn run_fn_docs(
-    cmd: FnDocsSubCmd,
-    client: &NovaClient,
-    output_format: &str,
-) -> Result<()> {
+async fn run_fn_docs(cmd: FnDocsSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         FnDocsSubCmd::Get { name } => {
             let result = client.get(&format!("/functions/{name}/docs")).await?;
-            output::render_single(&result, DOC_COLUMNS, output_format);
+            output::render_single(&result, DOC_COLUMNS, output_format)?;
         }
         FnDocsSubCmd::Save { name, content } => {
             let body = json!({ "content": content });
             let result = client
                 .put(&format!("/functions/{name}/docs"), &body)
                 .await?;
-            output::render_single(&result, DOC_COLUMNS, output_format);
+            output::render_single(&result, DOC_COLUMNS, output_format)?;
         }
         FnDocsSubCmd::Delete { name } => {
             client.delete(&format!("/functions/{name}/docs")).await?;
@@ -113,22 +109,18 @@ async fn run_fn_docs(
     Ok(())
 }
 
-async fn run_wf_docs(
-    cmd: WfDocsSubCmd,
-    client: &NovaClient,
-    output_format: &str,
-) -> Result<()> {
+async fn run_wf_docs(cmd: WfDocsSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         WfDocsSubCmd::Get { name } => {
             let result = client.get(&format!("/workflows/{name}/docs")).await?;
-            output::render_single(&result, DOC_COLUMNS, output_format);
+            output::render_single(&result, DOC_COLUMNS, output_format)?;
         }
         WfDocsSubCmd::Save { name, content } => {
             let body = json!({ "content": content });
             let result = client
                 .put(&format!("/workflows/{name}/docs"), &body)
                 .await?;
-            output::render_single(&result, DOC_COLUMNS, output_format);
+            output::render_single(&result, DOC_COLUMNS, output_format)?;
         }
         WfDocsSubCmd::Delete { name } => {
             client.delete(&format!("/workflows/{name}/docs")).await?;
@@ -138,21 +130,17 @@ async fn run_wf_docs(
     Ok(())
 }
 
-async fn run_shares(
-    cmd: SharesSubCmd,
-    client: &NovaClient,
-    output_format: &str,
-) -> Result<()> {
+async fn run_shares(cmd: SharesSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         SharesSubCmd::Create { title, functions } => {
             let fn_list: Vec<&str> = functions.split(',').map(|s| s.trim()).collect();
             let body = json!({ "title": title, "functions": fn_list });
             let result = client.post("/api-docs/shares", &body).await?;
-            output::render_single(&result, SHARE_COLUMNS, output_format);
+            output::render_single(&result, SHARE_COLUMNS, output_format)?;
         }
         SharesSubCmd::List => {
             let result = client.get("/api-docs/shares").await?;
-            output::render(&result, SHARE_COLUMNS, output_format);
+            output::render(&result, SHARE_COLUMNS, output_format)?;
         }
         SharesSubCmd::Delete { id } => {
             client.delete(&format!("/api-docs/shares/{id}")).await?;
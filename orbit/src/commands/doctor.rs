@@ -0,0 +1,233 @@
+//! `orbit doctor`: checks the local setup and the connection to the server,
+//! with a remediation hint for anything that is not right.
+
+use crate::client::NovaClient;
+use crate::config::{OrbitConfig, ProjectConfig};
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use serde_json::{Value, json};
+use std::time::Instant;
+
+const DOCTOR_COLUMNS: &[Column] = &[
+    Column::new("Check", "check"),
+    Column::new("Status", "status"),
+    Column::new("Detail", "detail"),
+    Column::new("Hint", "hint"),
+];
+
+/// Clock skew beyond which signed requests and token expiry misbehave.
+const SKEW_WARN_SECS: i64 = 30;
+const SKEW_FAIL_SECS: i64 = 300;
+
+fn check(name: &str, status: &str, detail: impl Into<String>, hint: Option<&str>) -> Value {
+    json!({ "check": name, "status": status, "detail": detail.into(), "hint": hint })
+}
+
+fn config_checks() -> Vec<Value> {
+    let mut checks = Vec::new();
+    let path = OrbitConfig::config_path();
+    checks.push(match std::fs::read_to_string(&path) {
+        Err(_) => check(
+            "config file",
+            "warn",
+            format!("{} not found, using defaults", path.display()),
+            Some("Run 'orbit config set server <url>'"),
+        ),
+        Ok(content) => match OrbitConfig::parse(&content) {
+            Ok(_) if path == crate::config::legacy_config_path() => check(
+                "config file",
+                "warn",
+                format!("{} (legacy location)", path.display()),
+                Some("Run 'orbit config migrate' to move it to the XDG config directory"),
+            ),
+            Ok(_) => check("config file", "pass", path.display().to_string(), None),
+            Err(e) => check(
+                "config file",
+                "fail",
+                e.to_string(),
+                Some("Fix it with 'orbit config edit'"),
+            ),
+        },
+    });
+    checks.push(match ProjectConfig::discover() {
+        Ok(Some((path, _))) => check("project config", "pass", path.display().to_string(), None),
+        Ok(None) => check("project config", "pass", "none", None),
+        Err(e) => check(
+            "project config",
+            "fail",
+            e.to_string(),
+            Some("Project files may only set context, server, tenant, namespace, output, timeout"),
+        ),
+    });
+    checks
+}
+
+fn toolchain_checks() -> Vec<Value> {
+    // The toolchains `functions pull --test` runs handlers with, and those
+    // that build scaffolded and example handlers in compiled languages.
+    const RUNS: &str = "not found; 'functions pull --test' cannot run these handlers";
+    const BUILDS: &str = "not found; these handlers cannot be built locally";
+    [
+        (
+            "python",
+            &["python3", "python"][..],
+            RUNS,
+            "Install Python 3.10+",
+        ),
+        ("node", &["node"][..], RUNS, "Install Node.js 18+"),
+        ("go", &["go"][..], BUILDS, "Install Go 1.22+"),
+        (
+            "rust",
+            &["cargo", "rustc"][..],
+            BUILDS,
+            "Install Rust with rustup",
+        ),
+        ("java", &["java"][..], BUILDS, "Install JDK 17+"),
+    ]
+    .into_iter()
+    .map(|(label, candidates, missing, hint)| {
+        match crate::commands::functions::find_available_binary(candidates) {
+            Some(bin) => check(&format!("toolchain: {label}"), "pass", bin, None),
+            None => check(&format!("toolchain: {label}"), "warn", missing, Some(hint)),
+        }
+    })
+    .collect()
+}
+
+/// Compares dotted numeric versions, ignoring any pre-release suffix.
+fn version_lt(a: &str, b: &str) -> bool {
+    let parse = |v: &str| -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split(['.', '-', '+'])
+            .map_while(|p| p.parse().ok())
+            .collect()
+    };
+    parse(a) < parse(b)
+}
+
+async fn server_checks(client: &NovaClient) -> Vec<Value> {
+    let mut checks = Vec::new();
+
+    let started = Instant::now();
+    let server_date = match client.server_date("/health/live").await {
+        Ok(date) => {
+            checks.push(check(
+                "server reachable",
+                "pass",
+                format!(
+                    "{} ({} ms)",
+                    client.base_url(),
+                    started.elapsed().as_millis()
+                ),
+                None,
+            ));
+            date
+        }
+        Err(e) => {
+            checks.push(check(
+                "server reachable",
+                "fail",
+                e.to_string(),
+                Some("Check --server / 'orbit config set server', proxy and TLS settings"),
+            ));
+            for name in ["auth", "clock skew", "server version"] {
+                checks.push(check(name, "skip", "server unreachable", None));
+            }
+            return checks;
+        }
+    };
+
+    checks.push(match client.auth_method() {
+        "none" => check(
+            "auth",
+            "warn",
+            "no credentials configured",
+            Some("Run 'orbit login' or 'orbit config set api_key <key>'"),
+        ),
+        method => match client.get("/auth/whoami").await {
+            Ok(id) => check(
+                "auth",
+                "pass",
+                format!(
+                    "{method}: {}",
+                    id["subject"].as_str().unwrap_or("authenticated")
+                ),
+                None,
+            ),
+            Err(OrbitError::Api { status: 401, .. }) => check(
+                "auth",
+                "fail",
+                format!("{method}: credentials rejected (401)"),
+                Some("Refresh the key or run 'orbit login' again"),
+            ),
+            Err(OrbitError::Api { status: 403, .. }) => check(
+                "auth",
+                "warn",
+                format!("{method}: authenticated but not allowed to read identity (403)"),
+                Some("Run 'orbit whoami' with a key that has identity scope"),
+            ),
+            Err(e) => check("auth", "warn", e.to_string(), None),
+        },
+    });
+
+    checks.push(match server_date {
+        Some(date) => {
+            let skew = (chrono::Utc::now() - date).num_seconds();
+            let detail = format!("{skew:+}s relative to server");
+            let hint = Some("Sync the local clock (e.g. enable NTP)");
+            match skew.abs() {
+                s if s >= SKEW_FAIL_SECS => check("clock skew", "fail", detail, hint),
+                s if s >= SKEW_WARN_SECS => check("clock skew", "warn", detail, hint),
+                _ => check("clock skew", "pass", detail, None),
+            }
+        }
+        None => check("clock skew", "skip", "server sent no Date header", None),
+    });
+
+    let local = env!("CARGO_PKG_VERSION");
+    checks.push(match client.get("/health").await {
+        Ok(health) => {
+            let server = health["version"].as_str().unwrap_or("unknown");
+            let detail = format!("orbit {local}, server {server}");
+            match health["min_cli_version"].as_str() {
+                Some(min) if version_lt(local, min) => check(
+                    "server version",
+                    "fail",
+                    format!("{detail}; server requires orbit {min}+"),
+                    Some("Upgrade orbit"),
+                ),
+                _ if server == "unknown" => check(
+                    "server version",
+                    "warn",
+                    detail,
+                    Some("Server does not report its version; compatibility unknown"),
+                ),
+                _ => check("server version", "pass", detail, None),
+            }
+        }
+        Err(e) => check("server version", "warn", e.to_string(), None),
+    });
+
+    checks
+}
+
+pub async fn run(client: &NovaClient, output_format: &str) -> Result<()> {
+    let mut checks = config_checks();
+    checks.extend(server_checks(client).await);
+    checks.extend(toolchain_checks());
+
+    let count = |status: &str| checks.iter().filter(|c| c["status"] == status).count();
+    let (failed, warned) = (count("fail"), count("warn"));
+    output::render(&Value::Array(checks), DOCTOR_COLUMNS, output_format)?;
+    if failed > 0 {
+        return Err(OrbitError::Input(format!(
+            "{failed} check(s) failed, {warned} warning(s)"
+        )));
+    }
+    if warned > 0 {
+        output::print_warning(&format!("All checks passed with {warned} warning(s)."));
+    } else {
+        output::print_success("All checks passed.");
+    }
+    Ok(())
+}
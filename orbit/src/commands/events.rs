@@ -150,7 +150,7 @@ const DELIVERY_COLUMNS: &[Column] = &[
     Column::new("Message", "message_id"),
     Column::new("Status", "status"),
     Column::new("Attempt", "attempt"),
-    Column::wide("Error", "error"),
+    Column::wide("Error", "error").max_width(40),
     Column::new("Delivered", "delivered_at"),
 ];
 
@@ -158,7 +158,7 @@ const MSG_COLUMNS: &[Column] = &[
     Column::new("ID", "id"),
     Column::new("Sequence", "sequence"),
     Column::new("Key", "ordering_key"),
-    Column::wide("Payload", "payload"),
+    Column::wide("Payload", "payload").max_width(40),
     Column::new("Published", "published_at"),
 ];
 
@@ -185,15 +185,15 @@ pub async fn run_topics(cmd: TopicsCmd, client: &NovaClient, output_format: &str
                 body["retention_hours"] = json!(r);
             }
             let result = client.post("/topics", &body).await?;
-            output::render_single(&result, TOPIC_COLUMNS, output_format);
+            output::render_single(&result, TOPIC_COLUMNS, output_format)?;
         }
         TopicsCmd::List => {
-            let result = client.get("/topics").await?;
-            output::render(&result, TOPIC_COLUMNS, output_format);
+            let result = client.get_paged("/topics").await?;
+            output::render(&result, TOPIC_COLUMNS, output_format)?;
         }
         TopicsCmd::Get { name } => {
             let result = client.get(&format!("/topics/{name}")).await?;
-            output::render_single(&result, TOPIC_COLUMNS, output_format);
+            output::render_single(&result, TOPIC_COLUMNS, output_format)?;
         }
         TopicsCmd::Delete { name } => {
             client.delete(&format!("/topics/{name}")).await?;
@@ -213,11 +213,13 @@ pub async fn run_topics(cmd: TopicsCmd, client: &NovaClient, output_format: &str
             let result = client
                 .post(&format!("/topics/{name}/publish"), &body)
                 .await?;
-            output::render_single(&result, MSG_COLUMNS, output_format);
+            output::render_single(&result, MSG_COLUMNS, output_format)?;
         }
         TopicsCmd::Messages { name } => {
-            let result = client.get(&format!("/topics/{name}/messages")).await?;
-            output::render(&result, MSG_COLUMNS, output_format);
+            let result = client
+                .get_paged(&format!("/topics/{name}/messages"))
+                .await?;
+            output::render(&result, MSG_COLUMNS, output_format)?;
         }
         TopicsCmd::Subscriptions { cmd } => match cmd {
             TopicSubsCmd::Create {
@@ -240,13 +242,13 @@ pub async fn run_topics(cmd: TopicsCmd, client: &NovaClient, output_format: &str
                 let result = client
                     .post(&format!("/topics/{topic}/subscriptions"), &body)
                     .await?;
-                output::render_single(&result, SUB_COLUMNS, output_format);
+                output::render_single(&result, SUB_COLUMNS, output_format)?;
             }
             TopicSubsCmd::List { topic } => {
                 let result = client
-                    .get(&format!("/topics/{topic}/subscriptions"))
+                    .get_paged(&format!("/topics/{topic}/subscriptions"))
                     .await?;
-                output::render(&result, SUB_COLUMNS, output_format);
+                output::render(&result, SUB_COLUMNS, output_format)?;
             }
         },
         TopicsCmd::Outbox { cmd } => match cmd {
@@ -264,21 +266,21 @@ pub async fn run_topics(cmd: TopicsCmd, client: &NovaClient, output_format: &str
                 let result = client
                     .post(&format!("/topics/{topic}/outbox"), &body)
                     .await?;
-                output::render_single(&result, OUTBOX_COLUMNS, output_format);
+                output::render_single(&result, OUTBOX_COLUMNS, output_format)?;
             }
             OutboxSubCmd::List { topic, status } => {
                 let mut path = format!("/topics/{topic}/outbox");
                 if let Some(s) = status {
                     path = format!("{path}?status={s}");
                 }
-                let result = client.get(&path).await?;
-                output::render(&result, OUTBOX_COLUMNS, output_format);
+                let result = client.get_paged(&path).await?;
+                output::render(&result, OUTBOX_COLUMNS, output_format)?;
             }
             OutboxSubCmd::Retry { id } => {
                 let result = client
                     .post(&format!("/outbox/{id}/retry"), &json!({}))
                     .await?;
-                output::render_single(&result, OUTBOX_COLUMNS, output_format);
+                output::render_single(&result, OUTBOX_COLUMNS, output_format)?;
             }
         },
     }
@@ -293,7 +295,7 @@ pub async fn run_subscriptions(
     match cmd {
         SubscriptionsCmd::Get { id } => {
             let result = client.get(&format!("/subscriptions/{id}")).await?;
-            output::render_single(&result, SUB_COLUMNS, output_format);
+            output::render_single(&result, SUB_COLUMNS, output_format)?;
         }
         SubscriptionsCmd::Update {
             id,
@@ -312,7 +314,7 @@ pub async fn run_subscriptions(
                 body["max_inflight"] = json!(m);
             }
             let result = client.patch(&format!("/subscriptions/{id}"), &body).await?;
-            output::render_single(&result, SUB_COLUMNS, output_format);
+            output::render_single(&result, SUB_COLUMNS, output_format)?;
         }
         SubscriptionsCmd::Delete { id } => {
             client.delete(&format!("/subscriptions/{id}")).await?;
@@ -320,9 +322,9 @@ pub async fn run_subscriptions(
         }
         SubscriptionsCmd::Deliveries { id } => {
             let result = client
-                .get(&format!("/subscriptions/{id}/deliveries"))
+                .get_paged(&format!("/subscriptions/{id}/deliveries"))
                 .await?;
-            output::render(&result, DELIVERY_COLUMNS, output_format);
+            output::render(&result, DELIVERY_COLUMNS, output_format)?;
         }
         SubscriptionsCmd::Replay {
             id,
@@ -340,8 +342,8 @@ pub async fn run_subscriptions(
                 .post(&format!("/subscriptions/{id}/replay"), &body)
                 .await?;
             output::print_success("Replay initiated.");
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&result, &[], output_format);
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, &[], output_format)?;
             }
         }
         SubscriptionsCmd::Seek {
@@ -360,8 +362,8 @@ pub async fn run_subscriptions(
                 .post(&format!("/subscriptions/{id}/seek"), &body)
                 .await?;
             output::print_success("Seek completed.");
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&result, &[], output_format);
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, &[], output_format)?;
             }
         }
     }
@@ -376,13 +378,13 @@ pub async fn run_deliveries(
     match cmd {
         DeliveriesCmd::Get { id } => {
             let result = client.get(&format!("/deliveries/{id}")).await?;
-            output::render_single(&result, DELIVERY_COLUMNS, output_format);
+            output::render_single(&result, DELIVERY_COLUMNS, output_format)?;
         }
         DeliveriesCmd::Retry { id } => {
             let result = client
                 .post(&format!("/deliveries/{id}/retry"), &json!({}))
                 .await?;
-            output::render_single(&result, DELIVERY_COLUMNS, output_format);
+            output::render_single(&result, DELIVERY_COLUMNS, output_format)?;
         }
     }
     Ok(())
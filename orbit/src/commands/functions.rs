@@ -1,34 +1,52 @@
 use crate::client::NovaClient;
+use crate::enrich::{self, Enrichment};
 use crate::error::Result;
 use crate::output::{self, Column};
-use clap::Subcommand;
+use clap::{Args, Subcommand};
 use serde_json::{Value, json};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+/// The opt-out shared by the commands that upload code; compression itself
+/// is off unless enabled with `--compress` or `config set compress true`.
+#[derive(Args)]
+pub struct CompressArgs {
+    /// Send this upload as plain JSON even when compression is enabled
+    #[arg(long)]
+    pub no_compress: bool,
+}
+
 #[derive(Subcommand)]
 pub enum FunctionsCmd {
     /// Create a new function
     Create {
+        /// Function spec (YAML, JSON or TOML) with the fields of the request;
+        /// flags override its values
+        #[arg(short, long)]
+        file: Option<String>,
         /// Function name
+        #[arg(long, required_unless_present = "file")]
+        name: Option<String>,
+        /// Runtime (python, go, rust, node, etc.); the namespace default if
+        /// omitted
         #[arg(long)]
-        name: String,
-        /// Runtime (python, go, rust, node, etc.)
-        #[arg(long)]
-        runtime: String,
+        runtime: Option<String>,
         /// Source code (inline string)
         #[arg(long)]
         code: Option<String>,
         /// Path to code file
         #[arg(long)]
         code_path: Option<String>,
-        /// Handler entry point
+        /// Directory to zip and upload as the code (honors .orbitignore)
+        #[arg(long, conflicts_with_all = ["code", "code_path"])]
+        code_dir: Option<String>,
+        /// Handler entry point; the namespace default if omitted
         #[arg(long)]
         handler: Option<String>,
-        /// Memory in MB
+        /// Memory in MB; the namespace default if omitted
         #[arg(long)]
         memory: Option<i64>,
-        /// Timeout in seconds
+        /// Timeout in seconds; the namespace default if omitted
         #[arg(long)]
         timeout: Option<i64>,
         /// Minimum replicas
@@ -58,9 +76,67 @@ pub enum FunctionsCmd {
         /// Execution mode (process or persistent)
         #[arg(long)]
         mode: Option<String>,
+        /// CPU architecture of the worker pool (x86_64, aarch64)
+        #[arg(long)]
+        arch: Option<String>,
+        /// Operating system of the worker pool (e.g. linux)
+        #[arg(long)]
+        os: Option<String>,
         /// Environment variables (KEY=VAL)
         #[arg(long = "env", value_name = "KEY=VAL")]
         env_vars: Vec<String>,
+        /// Environment variables encrypted client-side with the tenant key
+        /// (KEY=VAL)
+        #[arg(long = "env-secret", value_name = "KEY=VAL")]
+        env_secrets: Vec<String>,
+        #[command(flatten)]
+        compress: CompressArgs,
+    },
+    /// Generate a starter project (handler, payload.json, function.toml,
+    /// .orbitignore) ready for `functions deploy`
+    Init {
+        /// Function name
+        name: String,
+        /// Runtime (python, node, go or rust)
+        #[arg(long)]
+        runtime: String,
+        /// Directory to create (defaults to the function name)
+        #[arg(long)]
+        dir: Option<String>,
+        /// Write into a directory that is not empty, overwriting files
+        #[arg(long)]
+        force: bool,
+    },
+    /// Push a local directory on every save, optionally invoking with a
+    /// payload, and tail the function's logs until interrupted
+    Dev {
+        /// Function name (must exist; see `functions deploy`)
+        name: String,
+        /// Directory to watch and push (honors .orbitignore)
+        #[arg(long, default_value = ".")]
+        dir: String,
+        /// Entry file within --dir (detected by the server if omitted)
+        #[arg(long)]
+        entry_point: Option<String>,
+        /// JSON payload to invoke with after each push
+        #[arg(long)]
+        payload: Option<String>,
+        /// Path to a payload file to invoke with after each push
+        #[arg(long, conflicts_with = "payload")]
+        payload_file: Option<String>,
+        /// Don't tail the function's logs
+        #[arg(long)]
+        no_logs: bool,
+        /// How often to poll for new logs (e.g. 2s, 10s)
+        #[arg(long, default_value = "2s")]
+        log_interval: String,
+    },
+    /// Create or update a function from a directory holding its source and a
+    /// function.toml or nova.yaml manifest
+    Deploy {
+        /// Function directory
+        #[arg(default_value = ".")]
+        dir: String,
     },
     /// List all functions
     List {
@@ -70,16 +146,42 @@ pub enum FunctionsCmd {
         /// Limit results
         #[arg(long)]
         limit: Option<u32>,
+        /// Add each function's cost over the last 24h (one request per row)
+        #[arg(long)]
+        with_cost: bool,
+        /// Add each function's scaling policy (one request per row)
+        #[arg(long)]
+        with_scaling: bool,
     },
     /// Get function details
     Get {
         /// Function name
         name: String,
+        /// Reconstruct the configuration at a past time from version and
+        /// audit history (timestamp, YYYY-MM-DD, or a duration ago like 6h)
+        #[arg(long)]
+        at: Option<String>,
+    },
+    /// Show two functions' configurations side by side, marking the fields
+    /// that differ
+    Compare {
+        /// First function
+        a: String,
+        /// Second function
+        b: String,
+        /// Show only the fields that differ
+        #[arg(long)]
+        diff_only: bool,
     },
     /// Update a function
     Update {
         /// Function name
-        name: String,
+        #[arg(required_unless_present = "file")]
+        name: Option<String>,
+        /// Function spec (YAML, JSON or TOML) with the fields to change;
+        /// flags override its values
+        #[arg(short, long)]
+        file: Option<String>,
         /// Handler entry point
         #[arg(long)]
         handler: Option<String>,
@@ -95,6 +197,9 @@ pub enum FunctionsCmd {
         /// Path to code file
         #[arg(long)]
         code_path: Option<String>,
+        /// Directory to zip and upload as the code (honors .orbitignore)
+        #[arg(long, conflicts_with_all = ["code", "code_path"])]
+        code_dir: Option<String>,
         /// Minimum replicas
         #[arg(long)]
         min_replicas: Option<i64>,
@@ -122,9 +227,30 @@ pub enum FunctionsCmd {
         /// Execution mode
         #[arg(long)]
         mode: Option<String>,
+        /// CPU architecture of the worker pool (x86_64, aarch64)
+        #[arg(long)]
+        arch: Option<String>,
+        /// Operating system of the worker pool (e.g. linux)
+        #[arg(long)]
+        os: Option<String>,
         /// Environment variables (KEY=VAL)
         #[arg(long = "env", value_name = "KEY=VAL")]
         env_vars: Vec<String>,
+        /// Environment variables encrypted client-side with the tenant key
+        /// (KEY=VAL)
+        #[arg(long = "env-secret", value_name = "KEY=VAL")]
+        env_secrets: Vec<String>,
+        #[command(flatten)]
+        compress: CompressArgs,
+    },
+    /// Encrypt environment variables client-side and add them to a function
+    EncryptEnv {
+        /// Function name
+        name: String,
+        /// Variable to encrypt with the tenant key and merge into the
+        /// function's environment (KEY=VAL, repeatable)
+        #[arg(long = "env-secret", value_name = "KEY=VAL", required = true)]
+        env_secrets: Vec<String>,
     },
     /// Delete a function
     Delete {
@@ -161,6 +287,20 @@ pub enum FunctionsCmd {
         /// Function name
         name: String,
     },
+    /// Diff local code against the deployed code, failing when they differ
+    Diff {
+        /// Function name
+        name: String,
+        /// Local function directory [default: .]
+        #[arg(long, conflicts_with = "file")]
+        dir: Option<String>,
+        /// Local file compared with the deployed entry file
+        #[arg(long)]
+        file: Option<String>,
+        /// Lines of context around each change
+        #[arg(short = 'U', long, default_value_t = 3)]
+        context: usize,
+    },
     /// Manage function versions
     Versions {
         #[command(subcommand)]
@@ -176,6 +316,15 @@ pub enum FunctionsCmd {
         /// Path to payload file
         #[arg(long)]
         payload_file: Option<String>,
+        /// Stream incremental output over a WebSocket
+        #[arg(long)]
+        stream: bool,
+        /// Invoke this version directly, without shifting traffic
+        #[arg(long, conflicts_with = "alias")]
+        version: Option<u32>,
+        /// Invoke the version(s) behind this alias
+        #[arg(long)]
+        alias: Option<String>,
     },
     /// Invoke a function asynchronously
     InvokeAsync {
@@ -190,6 +339,15 @@ pub enum FunctionsCmd {
         /// Idempotency key
         #[arg(long)]
         idempotency_key: Option<String>,
+        /// Run no earlier than this long from now (e.g. 30s, 5m, 2h)
+        #[arg(long)]
+        delay: Option<String>,
+        /// Queue priority
+        #[arg(long, value_parser = ["high", "normal", "low"])]
+        priority: Option<String>,
+        /// URL the result is POSTed to when the invocation finishes
+        #[arg(long)]
+        callback_url: Option<String>,
     },
     /// Manage async invocations
     AsyncInvocations {
@@ -206,6 +364,9 @@ pub enum FunctionsCmd {
         /// Filter by request ID
         #[arg(long)]
         request_id: Option<String>,
+        /// Keep printing new log entries as they arrive
+        #[arg(short, long, conflicts_with = "request_id")]
+        follow: bool,
     },
     /// Get function metrics
     Metrics {
@@ -215,6 +376,17 @@ pub enum FunctionsCmd {
         #[arg(long)]
         range: Option<String>,
     },
+    /// Show a histogram of invocation durations, split into warm and cold
+    Latency {
+        /// Function name
+        name: String,
+        /// Time range (e.g. 1h, 5m, 1d)
+        #[arg(long, default_value = "1h")]
+        range: String,
+        /// Maximum number of invocations to sample
+        #[arg(long, default_value_t = 5000)]
+        samples: usize,
+    },
     /// Get function invocation heatmap
     Heatmap {
         /// Function name
@@ -223,6 +395,41 @@ pub enum FunctionsCmd {
         #[arg(long, default_value = "52")]
         weeks: u32,
     },
+    /// List functions whose error rate or latency deviates from baseline
+    Anomalies {
+        /// Time range used as the baseline (e.g. 6h, 24h, 7d)
+        #[arg(long, default_value = "24h")]
+        range: String,
+        /// Minimum absolute z-score to report
+        #[arg(long, default_value_t = 3.0)]
+        threshold: f64,
+    },
+    /// Show memory usage per instance against the configured size
+    Memory {
+        /// Function name
+        name: String,
+    },
+    /// Profile running instances and download the pprof data
+    Profile {
+        /// Function name
+        name: String,
+        /// How long to sample for (e.g. 30s, 5m)
+        #[arg(long, default_value = "60s")]
+        duration: String,
+        /// Profile type: cpu or memory
+        #[arg(long = "type", default_value = "cpu", value_parser = ["cpu", "memory"])]
+        kind: String,
+        /// Output file (default: <name>-<type>-<id>.pprof)
+        #[arg(long)]
+        out: Option<String>,
+        /// Print a flamegraph summary of the hottest frames
+        #[arg(long)]
+        flamegraph: bool,
+        /// Give up if the profile is not ready this long after sampling
+        /// ends (e.g. 30s, 10m)
+        #[arg(long, default_value = "5m")]
+        max_wait: String,
+    },
     /// Manage auto-scaling policy
     Scaling {
         #[command(subcommand)]
@@ -233,6 +440,27 @@ pub enum FunctionsCmd {
         #[command(subcommand)]
         cmd: CapacitySubCmd,
     },
+    /// Temporarily cap the invocation rate, e.g. during an incident
+    Throttle {
+        #[command(subcommand)]
+        cmd: ThrottleSubCmd,
+    },
+    /// Manage aliases pointing at versions
+    Aliases {
+        #[command(subcommand)]
+        cmd: AliasesSubCmd,
+    },
+    /// Trial a new version on a share of an alias's traffic, then promote
+    /// or roll it back
+    Canary {
+        #[command(subcommand)]
+        cmd: CanarySubCmd,
+    },
+    /// Cap daily invocations and spend
+    Caps {
+        #[command(subcommand)]
+        cmd: CapsSubCmd,
+    },
     /// Manage schedules
     Schedules {
         #[command(subcommand)]
@@ -263,6 +491,14 @@ pub enum CodeSubCmd {
         /// Path to code file
         #[arg(long)]
         file: Option<String>,
+        /// Directory to zip and upload as the code (honors .orbitignore)
+        #[arg(long, conflicts_with_all = ["code", "file"])]
+        code_dir: Option<String>,
+        /// Entry file within --code-dir (detected by the server if omitted)
+        #[arg(long, requires = "code_dir")]
+        entry_point: Option<String>,
+        #[command(flatten)]
+        compress: CompressArgs,
     },
 }
 
@@ -272,6 +508,17 @@ pub enum VersionsSubCmd {
     List { name: String },
     /// Get specific version
     Get { name: String, version: u32 },
+    /// Make a previous version's code and config active again, after
+    /// showing what will change
+    Rollback {
+        name: String,
+        /// Version to restore (defaults to the one before the active version)
+        #[arg(long)]
+        to: Option<u32>,
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -306,6 +553,43 @@ pub enum ScalingSubCmd {
     },
     /// Delete scaling policy
     Delete { name: String },
+    /// Manage time-based minimum replica profiles
+    Schedule {
+        #[command(subcommand)]
+        cmd: ScalingScheduleSubCmd,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ScalingScheduleSubCmd {
+    /// Raise the replica floor on a cron schedule
+    Add {
+        name: String,
+        /// Cron expression for when the profile starts (e.g. "0 9 * * 1-5")
+        #[arg(long)]
+        cron: String,
+        /// Minimum replicas while the profile is active
+        #[arg(long)]
+        min: i64,
+        /// Maximum replicas while the profile is active
+        #[arg(long)]
+        max: Option<i64>,
+        /// How long the profile stays active (e.g. 9h); until the next
+        /// profile starts when omitted
+        #[arg(long)]
+        duration: Option<String>,
+        /// IANA time zone the cron expression is evaluated in (default UTC)
+        #[arg(long)]
+        timezone: Option<String>,
+    },
+    /// List replica profiles
+    List { name: String },
+    /// Remove a replica profile
+    Remove {
+        name: String,
+        /// Profile ID
+        id: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -328,6 +612,142 @@ pub enum CapacitySubCmd {
     Delete { name: String },
 }
 
+#[derive(Subcommand)]
+pub enum ThrottleSubCmd {
+    /// Cap invocations per second until the throttle expires
+    Set {
+        name: String,
+        /// Maximum invocations per second
+        #[arg(long)]
+        rps: u32,
+        /// How long the throttle stays in place (e.g. 30m, 2h)
+        #[arg(long)]
+        until: String,
+        /// Why the throttle was applied, shown in status
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Lift the throttle before it expires
+    Clear { name: String },
+    /// Show the active throttle and the time remaining
+    Status { name: String },
+}
+
+#[derive(Subcommand)]
+pub enum AliasesSubCmd {
+    /// Point a new alias at a version
+    Create {
+        name: String,
+        /// Alias name (e.g. prod, staging)
+        alias: String,
+        /// Version the alias routes to
+        #[arg(long)]
+        version: u32,
+        /// Send a share of traffic to another version, as VERSION=PERCENT
+        /// (e.g. 4=10)
+        #[arg(long)]
+        split: Option<String>,
+    },
+    /// Repoint an alias or change its traffic split
+    Update {
+        name: String,
+        alias: String,
+        /// Version the alias routes to
+        #[arg(long)]
+        version: Option<u32>,
+        /// Send a share of traffic to another version, as VERSION=PERCENT
+        #[arg(long, conflicts_with = "no_split")]
+        split: Option<String>,
+        /// Route all traffic to the main version again
+        #[arg(long)]
+        no_split: bool,
+    },
+    /// List a function's aliases
+    List { name: String },
+    /// Delete an alias
+    Delete { name: String, alias: String },
+}
+
+#[derive(Subcommand)]
+pub enum CanarySubCmd {
+    /// Shift a share of traffic to a new version and bake it, promoting it
+    /// if the error rate stays under the limit and rolling back otherwise
+    Start {
+        name: String,
+        /// Alias whose traffic is split
+        #[arg(long, default_value = "live")]
+        alias: String,
+        /// Version to trial (defaults to the latest published version)
+        #[arg(long, conflicts_with = "code_dir")]
+        version: Option<u32>,
+        /// Publish this directory as the version to trial. The alias stays
+        /// on the stable version while it is uploaded; unqualified
+        /// invocations run the new code until the stable version is
+        /// re-activated right after.
+        #[arg(long)]
+        code_dir: Option<String>,
+        /// Share of traffic sent to the new version
+        #[arg(long, default_value_t = 10)]
+        percent: u32,
+        /// How long to watch before promoting (e.g. 10m, 1h)
+        #[arg(long, default_value = "10m")]
+        bake: String,
+        /// Roll back once the error rate exceeds this percentage
+        #[arg(long, default_value_t = 1.0)]
+        max_error_rate: f64,
+        /// How often to check the error rate while baking
+        #[arg(long, default_value = "30s")]
+        interval: String,
+        /// Roll back instead of promoting when fewer invocations than this
+        /// ran during the bake
+        #[arg(long, default_value_t = 10)]
+        min_invocations: u64,
+        /// Shift the traffic and return; finish with promote or abort
+        #[arg(long)]
+        no_wait: bool,
+    },
+    /// Show the running canary and the error rate since it started
+    Status {
+        name: String,
+        #[arg(long, default_value = "live")]
+        alias: String,
+    },
+    /// Send all traffic to the canary version
+    Promote {
+        name: String,
+        #[arg(long, default_value = "live")]
+        alias: String,
+    },
+    /// Send all traffic back to the stable version
+    Abort {
+        name: String,
+        #[arg(long, default_value = "live")]
+        alias: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CapsSubCmd {
+    /// Set daily ceilings, replacing any existing ones
+    Set {
+        name: String,
+        /// Invocations allowed per UTC day (e.g. 50000, 1M)
+        #[arg(long)]
+        max_invocations_per_day: Option<String>,
+        /// Spend allowed per UTC day, in the billing currency
+        #[arg(long)]
+        max_cost_per_day: Option<f64>,
+        /// What happens once a cap is reached: throttle new invocations,
+        /// disable the function, or only send an alert
+        #[arg(long, default_value = "throttle", value_parser = ["throttle", "disable", "alert"])]
+        on_breach: String,
+    },
+    /// Show the caps and today's usage against them
+    Get { name: String },
+    /// Remove the caps
+    Clear { name: String },
+}
+
 #[derive(Subcommand)]
 pub enum SchedulesSubCmd {
     /// Create a schedule
@@ -365,6 +785,18 @@ pub enum SnapshotSubCmd {
     Create { name: String },
     /// Delete a snapshot
     Delete { name: String },
+    /// Compare warm, snapshot-restored and cold-start invocation latency.
+    /// Evicts the function's instances and deletes and recreates its
+    /// snapshot between runs; the snapshot is left as it was found.
+    Bench {
+        name: String,
+        /// Invocations per boot mode
+        #[arg(long, default_value_t = 20)]
+        iterations: u32,
+        /// JSON payload sent with every invocation
+        #[arg(long)]
+        payload: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -387,10 +819,44 @@ const FN_COLUMNS: &[Column] = &[
     Column::new("Timeout", "timeout_s"),
     Column::new("Mode", "mode"),
     Column::wide("Handler", "handler"),
+    Column::wide("Arch", "arch"),
+    Column::wide("OS", "os"),
     Column::wide("Version", "version"),
     Column::wide("Created", "created_at"),
 ];
 
+fn function_path(suffix: &str, row: &Value) -> Option<String> {
+    let name = row.get("name")?.as_str()?;
+    Some(format!("/functions/{name}/{suffix}"))
+}
+
+const COST_ENRICHMENT: Enrichment = Enrichment {
+    label: "cost",
+    field: "cost",
+    path: |row| function_path("cost?window=86400", row),
+    columns: &[
+        Column::new("Invocations 24h", "cost.invocations"),
+        Column::new("Cost 24h", "cost.total_cost"),
+    ],
+};
+
+const SCALING_ENRICHMENT: Enrichment = Enrichment {
+    label: "scaling",
+    field: "scaling",
+    path: |row| function_path("scaling", row),
+    columns: &[
+        Column::new("Min Replicas", "scaling.min_replicas"),
+        Column::new("Max Replicas", "scaling.max_replicas"),
+        Column::wide("Target Util", "scaling.target_utilization"),
+    ],
+};
+
+/// Shown ahead of the details by `functions get --at`.
+const FN_AS_OF_COLUMNS: &[Column] = &[
+    Column::new("As Of", "as_of"),
+    Column::new("Reconstructed From", "reconstructed_from"),
+];
+
 const FN_DETAIL_COLUMNS: &[Column] = &[
     Column::new("Name", "name"),
     Column::new("Runtime", "runtime"),
@@ -398,10 +864,16 @@ const FN_DETAIL_COLUMNS: &[Column] = &[
     Column::new("Memory (MB)", "memory_mb"),
     Column::new("Timeout (s)", "timeout_s"),
     Column::new("Mode", "mode"),
+    Column::new("Arch", "arch"),
+    Column::new("OS", "os"),
     Column::new("Version", "version"),
     Column::new("Code Hash", "code_hash"),
     Column::new("Min Replicas", "min_replicas"),
     Column::new("Max Replicas", "max_replicas"),
+    Column::new("Throttle", "throttle_status"),
+    Column::wide("Disk Bandwidth", "limits.disk_bandwidth"),
+    Column::wide("Net RX Bandwidth", "limits.net_rx_bandwidth"),
+    Column::wide("Net TX Bandwidth", "limits.net_tx_bandwidth"),
     Column::new("Created", "created_at"),
     Column::new("Updated", "updated_at"),
 ];
@@ -416,8 +888,68 @@ const FN_PULL_COLUMNS: &[Column] = &[
     Column::new("Local Test", "local_test"),
 ];
 
+/// Maps common aliases to the architecture names Nova workers report.
+fn normalize_arch(arch: &str) -> &str {
+    match arch {
+        "amd64" | "x64" => "x86_64",
+        "arm64" => "aarch64",
+        other => other,
+    }
+}
+
+/// Checks that some worker node advertises the requested platform through
+/// its `arch`/`os` labels. Skipped when node metadata is unavailable or no
+/// node carries platform labels.
+async fn validate_platform(
+    client: &NovaClient,
+    arch: Option<&str>,
+    os: Option<&str>,
+) -> Result<()> {
+    let Ok(nodes) = client.get_all("/cluster/nodes").await else {
+        return Ok(());
+    };
+    let platforms: Vec<(Option<&str>, Option<&str>)> = nodes
+        .as_array()
+        .map(|nodes| {
+            nodes
+                .iter()
+                .map(|n| (n["labels"]["arch"].as_str(), n["labels"]["os"].as_str()))
+                .filter(|(a, o)| a.is_some() || o.is_some())
+                .collect()
+        })
+        .unwrap_or_default();
+    if platforms.is_empty() {
+        return Ok(());
+    }
+    let supported = platforms.iter().any(|(a, o)| {
+        arch.is_none_or(|want| a.map(normalize_arch) == Some(want))
+            && os.is_none_or(|want| *o == Some(want))
+    });
+    if supported {
+        return Ok(());
+    }
+    let mut available: Vec<String> = platforms
+        .iter()
+        .map(|(a, o)| {
+            format!(
+                "{}/{}",
+                o.unwrap_or("?"),
+                a.map(normalize_arch).unwrap_or("?")
+            )
+        })
+        .collect();
+    available.sort();
+    available.dedup();
+    Err(crate::error::OrbitError::Input(format!(
+        "No worker offers {}/{}. Available: {}",
+        os.unwrap_or("*"),
+        arch.unwrap_or("*"),
+        available.join(", ")
+    )))
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-enum RuntimeFamily {
+pub(crate) enum RuntimeFamily {
     Python,
     Node,
     Go,
@@ -441,6 +973,22 @@ fn parse_env_vars(env_vars: &[String]) -> Value {
     Value::Object(map)
 }
 
+/// Encrypts each `KEY=VAL` in `secrets` with the tenant's public key and adds
+/// the ciphertext to the `env` map.
+async fn seal_env_secrets(client: &NovaClient, secrets: &[String], env: &mut Value) -> Result<()> {
+    if secrets.is_empty() {
+        return Ok(());
+    }
+    let key = crate::envelope::TenantKey::fetch(client).await?;
+    for item in secrets {
+        let (k, v) = item.split_once('=').ok_or_else(|| {
+            crate::error::OrbitError::Input(format!("Invalid secret '{item}': expected KEY=VAL"))
+        })?;
+        env[k] = json!(key.seal(v)?);
+    }
+    Ok(())
+}
+
 fn parse_json_payload(payload: Option<String>, payload_file: Option<String>) -> Result<Value> {
     match (payload, payload_file) {
         (Some(p), _) => serde_json::from_str(&p)
@@ -456,7 +1004,7 @@ fn parse_json_payload(payload: Option<String>, payload_file: Option<String>) ->
     }
 }
 
-fn detect_runtime_family(runtime: &str) -> RuntimeFamily {
+pub(crate) fn detect_runtime_family(runtime: &str) -> RuntimeFamily {
     let rt = runtime.to_lowercase();
     if rt.contains("python") {
         RuntimeFamily::Python
@@ -473,7 +1021,7 @@ fn detect_runtime_family(runtime: &str) -> RuntimeFamily {
     }
 }
 
-fn source_file_rel_path(runtime: &str, handler: &str) -> String {
+pub(crate) fn source_file_rel_path(runtime: &str, handler: &str) -> String {
     let family = detect_runtime_family(runtime);
     let module = handler
         .rsplit_once('.')
@@ -502,7 +1050,7 @@ fn source_file_rel_path(runtime: &str, handler: &str) -> String {
     }
 }
 
-fn find_available_binary(candidates: &[&str]) -> Option<String> {
+pub(crate) fn find_available_binary(candidates: &[&str]) -> Option<String> {
     for candidate in candidates {
         let status = Command::new(candidate)
             .arg("--version")
@@ -739,6 +1287,7 @@ fn run_local_test(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_pull(
     name: String,
     output_dir: String,
@@ -842,7 +1391,7 @@ async fn run_pull(
         "metadata_file": metadata_path.to_string_lossy().to_string(),
         "local_test": local_test_status,
     });
-    output::render_single(&summary, FN_PULL_COLUMNS, output_format);
+    output::render_single(&summary, FN_PULL_COLUMNS, output_format)?;
 
     Ok(())
 }
@@ -850,10 +1399,12 @@ async fn run_pull(
 pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         FunctionsCmd::Create {
+            file,
             name,
             runtime,
             code,
             code_path,
+            code_dir,
             handler,
             memory,
             timeout,
@@ -866,7 +1417,11 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
             net_rx_bandwidth,
             net_tx_bandwidth,
             mode,
+            arch,
+            os,
             env_vars,
+            env_secrets,
+            compress,
         } => {
             let code_value = match (&code, &code_path) {
                 (Some(c), _) => Some(Value::String(c.clone())),
@@ -878,11 +1433,63 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
                 }
                 _ => None,
             };
+            // Creating needs inline code: the directory's entry file, with
+            // the whole directory uploaded once the function exists.
+            let code_value = match &code_dir {
+                Some(dir) => {
+                    let files = crate::package::list_files(Path::new(dir), &[])?;
+                    let entry = crate::package::entry_file(&files, None).ok_or_else(|| {
+                        crate::error::OrbitError::Input(format!("No entry file found in {dir}"))
+                    })?;
+                    let path = Path::new(dir).join(entry);
+                    let content = std::fs::read_to_string(&path).map_err(|e| {
+                        crate::error::OrbitError::Input(format!(
+                            "Cannot read file {}: {e}",
+                            path.display()
+                        ))
+                    })?;
+                    Some(Value::String(content))
+                }
+                None => code_value,
+            };
 
-            let mut body = json!({
-                "name": name,
-                "runtime": runtime,
-            });
+            // Settings left out of both the flags and the file fall back to
+            // the namespace defaults, when they can be read.
+            let defaults = crate::commands::namespace::fetch(client)
+                .await
+                .unwrap_or_else(|e| {
+                    output::print_warning(&format!(
+                        "Cannot read the namespace defaults, creating without them: {e}"
+                    ));
+                    Value::Null
+                });
+            let mut body = match &file {
+                Some(f) => crate::manifest::load_request_file(Path::new(f))?,
+                None => json!({}),
+            };
+            if let Some(n) = name {
+                body["name"] = json!(n);
+            }
+            if let Some(r) = runtime {
+                body["runtime"] = json!(r);
+            }
+            for key in ["runtime", "handler", "memory_mb", "timeout_s"] {
+                if body.get(key).is_none() && !defaults[key].is_null() {
+                    body[key] = defaults[key].clone();
+                }
+            }
+            if body.get("name").is_none() {
+                return Err(crate::error::OrbitError::Input(
+                    "--name is required: the spec file has no name".into(),
+                ));
+            }
+            if body.get("runtime").is_none() {
+                return Err(crate::error::OrbitError::Input(
+                    "--runtime is required: neither the spec file nor the namespace defaults \
+                     set one"
+                        .into(),
+                ));
+            }
             if let Some(c) = code_value {
                 body["code"] = c;
             }
@@ -907,40 +1514,107 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
             if let Some(v) = instance_concurrency {
                 body["instance_concurrency"] = json!(v);
             }
-            if vcpus.is_some()
-                || disk_iops.is_some()
-                || disk_bandwidth.is_some()
-                || net_rx_bandwidth.is_some()
-                || net_tx_bandwidth.is_some()
-            {
-                let mut limits = json!({});
-                if let Some(v) = vcpus {
-                    limits["vcpus"] = json!(v);
-                }
-                if let Some(v) = disk_iops {
-                    limits["disk_iops"] = json!(v);
-                }
-                if let Some(v) = disk_bandwidth {
-                    limits["disk_bandwidth"] = json!(v);
-                }
-                if let Some(v) = net_rx_bandwidth {
-                    limits["net_rx_bandwidth"] = json!(v);
-                }
-                if let Some(v) = net_tx_bandwidth {
-                    limits["net_tx_bandwidth"] = json!(v);
-                }
-                body["limits"] = limits;
+            // Set limit by limit so flags override only their own field of a
+            // file's limits.
+            if let Some(v) = vcpus {
+                body["limits"]["vcpus"] = json!(v);
+            }
+            if let Some(v) = disk_iops {
+                body["limits"]["disk_iops"] = json!(v);
+            }
+            if let Some(v) = disk_bandwidth {
+                body["limits"]["disk_bandwidth"] = json!(v);
+            }
+            if let Some(v) = net_rx_bandwidth {
+                body["limits"]["net_rx_bandwidth"] = json!(v);
+            }
+            if let Some(v) = net_tx_bandwidth {
+                body["limits"]["net_tx_bandwidth"] = json!(v);
             }
             if let Some(m) = mode {
                 body["mode"] = json!(m);
             }
-            if !env_vars.is_empty() {
-                body["env_vars"] = parse_env_vars(&env_vars);
+            let arch = arch.as_deref().map(normalize_arch);
+            if arch.is_some() || os.is_some() {
+                validate_platform(client, arch, os.as_deref()).await?;
+            }
+            if let Some(a) = arch {
+                body["arch"] = json!(a);
+            }
+            if let Some(o) = &os {
+                body["os"] = json!(o);
+            }
+            let file_env = body["env_vars"].as_object().cloned();
+            let default_env = defaults["env_vars"].as_object();
+            if !env_vars.is_empty()
+                || !env_secrets.is_empty()
+                || file_env.is_some()
+                || default_env.is_some()
+            {
+                let mut env = parse_env_vars(&env_vars);
+                seal_env_secrets(client, &env_secrets, &mut env).await?;
+                if let Some(env) = env.as_object_mut() {
+                    for (key, value) in file_env.iter().chain(default_env).flatten() {
+                        env.entry(key.clone()).or_insert_with(|| value.clone());
+                    }
+                }
+                body["env_vars"] = env;
+            }
+            let mut result = if compress.no_compress {
+                client.post("/functions", &body).await?
+            } else {
+                client.post_compressed("/functions", &body).await?
+            };
+            if let Some(dir) = &code_dir {
+                let name = body["name"].as_str().unwrap_or_default();
+                let upload = crate::commands::code::upload_dir(client, name, dir, None).await?;
+                result["files"] = upload["files"].clone();
             }
-            let result = client.post("/functions", &body).await?;
-            output::render_single(&result, FN_DETAIL_COLUMNS, output_format);
+            output::render_single(&result, FN_DETAIL_COLUMNS, output_format)?;
         }
-        FunctionsCmd::List { search, limit } => {
+        FunctionsCmd::Init {
+            name,
+            runtime,
+            dir,
+            force,
+        } => {
+            crate::commands::scaffold::run(name, runtime, dir, force, output_format)?;
+        }
+        FunctionsCmd::Dev {
+            name,
+            dir,
+            entry_point,
+            payload,
+            payload_file,
+            no_logs,
+            log_interval,
+        } => {
+            let payload = if payload.is_some() || payload_file.is_some() {
+                Some(parse_json_payload(payload, payload_file)?)
+            } else {
+                None
+            };
+            let options = crate::commands::dev::DevOptions {
+                dir,
+                entry_point,
+                payload,
+                logs: !no_logs,
+                log_interval: crate::duration::parse(&log_interval)?
+                    .to_std()
+                    .unwrap_or_default(),
+            };
+            crate::commands::dev::run(&name, options, client).await?;
+        }
+        FunctionsCmd::Deploy { dir } => {
+            let result = crate::commands::deploy::run_dir(&dir, client).await?;
+            output::render_single(&result, FN_DETAIL_COLUMNS, output_format)?;
+        }
+        FunctionsCmd::List {
+            search,
+            limit,
+            with_cost,
+            with_scaling,
+        } => {
             let mut path = "/functions".to_string();
             let mut params = vec![];
             if let Some(s) = search {
@@ -952,20 +1626,51 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
             if !params.is_empty() {
                 path = format!("{}?{}", path, params.join("&"));
             }
-            let result = client.get(&path).await?;
-            output::render(&result, FN_COLUMNS, output_format);
+            let mut result = client.get_paged(&path).await?;
+            let enrichments: Vec<&Enrichment> = [
+                (with_cost, &COST_ENRICHMENT),
+                (with_scaling, &SCALING_ENRICHMENT),
+            ]
+            .into_iter()
+            .filter_map(|(on, e)| on.then_some(e))
+            .collect();
+            enrich::apply(client, &mut result, &enrichments).await;
+            let columns = enrich::columns(FN_COLUMNS, &enrichments);
+            output::render(&result, &columns, output_format)?;
+        }
+        FunctionsCmd::Get { name, at: Some(at) } => {
+            let at = crate::commands::versions::parse_at(&at)?;
+            let result = crate::commands::versions::config_at(client, &name, at).await?;
+            if let Some(n) = result["skipped_changes"].as_i64().filter(|n| *n > 0) {
+                output::print_warning(&format!(
+                    "{n} audited change(s) had an unreadable request body and were not replayed."
+                ));
+            }
+            let columns = [FN_AS_OF_COLUMNS, FN_DETAIL_COLUMNS].concat();
+            output::render_single(&result, &columns, output_format)?;
+        }
+        FunctionsCmd::Get { name, at: None } => {
+            let mut result = client.get(&format!("/functions/{name}")).await?;
+            // Best effort: servers without throttling simply show none.
+            if let Ok(throttle) = client.get(&format!("/functions/{name}/throttle")).await
+                && let Some(summary) = crate::commands::throttle::summary(&throttle)
+            {
+                result["throttle_status"] = json!(summary);
+            }
+            output::render_single(&result, FN_DETAIL_COLUMNS, output_format)?;
         }
-        FunctionsCmd::Get { name } => {
-            let result = client.get(&format!("/functions/{name}")).await?;
-            output::render_single(&result, FN_DETAIL_COLUMNS, output_format);
+        FunctionsCmd::Compare { a, b, diff_only } => {
+            crate::commands::compare::run(&a, &b, diff_only, client, output_format).await?;
         }
         FunctionsCmd::Update {
             name,
+            file,
             handler,
             memory,
             timeout,
             code,
             code_path,
+            code_dir,
             min_replicas,
             max_replicas,
             instance_concurrency,
@@ -975,9 +1680,26 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
             net_rx_bandwidth,
             net_tx_bandwidth,
             mode,
+            arch,
+            os,
             env_vars,
+            env_secrets,
+            compress,
         } => {
-            let mut body = json!({});
+            let mut body = match &file {
+                Some(f) => crate::manifest::load_request_file(Path::new(f))?,
+                None => json!({}),
+            };
+            // The file's name picks the function; it is not a field to change.
+            let file_name = body
+                .as_object_mut()
+                .and_then(|b| b.remove("name"))
+                .and_then(|n| n.as_str().map(String::from));
+            let name = name.or(file_name).ok_or_else(|| {
+                crate::error::OrbitError::Input(
+                    "A function name is required: the spec file has no name".into(),
+                )
+            })?;
             let code_value = match (&code, &code_path) {
                 (Some(c), _) => Some(Value::String(c.clone())),
                 (_, Some(path)) => {
@@ -1009,38 +1731,76 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
             if let Some(v) = instance_concurrency {
                 body["instance_concurrency"] = json!(v);
             }
-            if vcpus.is_some()
-                || disk_iops.is_some()
-                || disk_bandwidth.is_some()
-                || net_rx_bandwidth.is_some()
-                || net_tx_bandwidth.is_some()
-            {
-                let mut limits = json!({});
-                if let Some(v) = vcpus {
-                    limits["vcpus"] = json!(v);
-                }
-                if let Some(v) = disk_iops {
-                    limits["disk_iops"] = json!(v);
-                }
-                if let Some(v) = disk_bandwidth {
-                    limits["disk_bandwidth"] = json!(v);
-                }
-                if let Some(v) = net_rx_bandwidth {
-                    limits["net_rx_bandwidth"] = json!(v);
-                }
-                if let Some(v) = net_tx_bandwidth {
-                    limits["net_tx_bandwidth"] = json!(v);
-                }
-                body["limits"] = limits;
+            // Set limit by limit so flags override only their own field of a
+            // file's limits.
+            if let Some(v) = vcpus {
+                body["limits"]["vcpus"] = json!(v);
+            }
+            if let Some(v) = disk_iops {
+                body["limits"]["disk_iops"] = json!(v);
+            }
+            if let Some(v) = disk_bandwidth {
+                body["limits"]["disk_bandwidth"] = json!(v);
+            }
+            if let Some(v) = net_rx_bandwidth {
+                body["limits"]["net_rx_bandwidth"] = json!(v);
+            }
+            if let Some(v) = net_tx_bandwidth {
+                body["limits"]["net_tx_bandwidth"] = json!(v);
             }
             if let Some(m) = mode {
                 body["mode"] = json!(m);
             }
-            if !env_vars.is_empty() {
-                body["env_vars"] = parse_env_vars(&env_vars);
+            let arch = arch.as_deref().map(normalize_arch);
+            if arch.is_some() || os.is_some() {
+                validate_platform(client, arch, os.as_deref()).await?;
+            }
+            if let Some(a) = arch {
+                body["arch"] = json!(a);
             }
-            let result = client.patch(&format!("/functions/{name}"), &body).await?;
-            output::render_single(&result, FN_DETAIL_COLUMNS, output_format);
+            if let Some(o) = &os {
+                body["os"] = json!(o);
+            }
+            if !env_vars.is_empty() || !env_secrets.is_empty() {
+                let mut env = parse_env_vars(&env_vars);
+                seal_env_secrets(client, &env_secrets, &mut env).await?;
+                if let (Some(env), Some(file_env)) =
+                    (env.as_object_mut(), body["env_vars"].as_object())
+                {
+                    for (key, value) in file_env {
+                        env.entry(key.clone()).or_insert_with(|| value.clone());
+                    }
+                }
+                body["env_vars"] = env;
+            }
+            let path = format!("/functions/{name}");
+            let mut result = match &code_dir {
+                // Only the code changes: nothing to patch.
+                Some(_) if body.as_object().is_some_and(|b| b.is_empty()) => {
+                    client.get(&path).await?
+                }
+                _ if compress.no_compress => client.patch(&path, &body).await?,
+                _ => client.patch_compressed(&path, &body).await?,
+            };
+            if let Some(dir) = &code_dir {
+                let upload = crate::commands::code::upload_dir(client, &name, dir, None).await?;
+                result["files"] = upload["files"].clone();
+            }
+            output::render_single(&result, FN_DETAIL_COLUMNS, output_format)?;
+        }
+        FunctionsCmd::EncryptEnv { name, env_secrets } => {
+            let mut env = json!({});
+            seal_env_secrets(client, &env_secrets, &mut env).await?;
+            client
+                .patch(
+                    &format!("/functions/{name}"),
+                    &json!({ "env_vars": env, "merge_env_vars": true }),
+                )
+                .await?;
+            output::print_success(&format!(
+                "Encrypted {} variable(s) into function '{name}'.",
+                env_secrets.len()
+            ));
         }
         FunctionsCmd::Delete { name } => {
             client.delete(&format!("/functions/{name}")).await?;
@@ -1069,6 +1829,14 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
             )
             .await?;
         }
+        FunctionsCmd::Diff {
+            name,
+            dir,
+            file,
+            context,
+        } => {
+            crate::commands::code::diff(client, &name, dir, file, context, output_format).await?;
+        }
         FunctionsCmd::Files { name } => {
             let result = client.get(&format!("/functions/{name}/files")).await?;
             let render_target = result.get("items").unwrap_or(&result).clone();
@@ -1076,7 +1844,7 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
                 &render_target,
                 &[Column::new("File", "path"), Column::new("Size", "size")],
                 output_format,
-            );
+            )?;
         }
         FunctionsCmd::Versions { cmd } => {
             crate::commands::versions::run(cmd, client, output_format).await?;
@@ -1085,11 +1853,33 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
             name,
             payload,
             payload_file,
+            stream: true,
+            version,
+            alias,
+        } => {
+            crate::commands::invoke::run_invoke_stream(
+                &name,
+                payload,
+                payload_file,
+                version.map(|v| v.to_string()).or(alias),
+                client,
+                output_format,
+            )
+            .await?;
+        }
+        FunctionsCmd::Invoke {
+            name,
+            payload,
+            payload_file,
+            stream: false,
+            version,
+            alias,
         } => {
             crate::commands::invoke::run_invoke(
                 &name,
                 payload,
                 payload_file,
+                version.map(|v| v.to_string()).or(alias),
                 client,
                 output_format,
             )
@@ -1100,12 +1890,20 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
             payload,
             max_attempts,
             idempotency_key,
+            delay,
+            priority,
+            callback_url,
         } => {
             crate::commands::invoke::run_invoke_async(
                 &name,
                 payload,
                 max_attempts,
                 idempotency_key,
+                crate::commands::invoke::AsyncOptions {
+                    delay,
+                    priority,
+                    callback_url,
+                },
                 client,
                 output_format,
             )
@@ -1114,25 +1912,81 @@ pub async fn run(cmd: FunctionsCmd, client: &NovaClient, output_format: &str) ->
         FunctionsCmd::AsyncInvocations { cmd } => {
             crate::commands::async_invocations::run_fn(cmd, client, output_format).await?;
         }
+        FunctionsCmd::Logs {
+            name,
+            tail,
+            follow: true,
+            ..
+        } => {
+            crate::commands::logs::follow(&name, tail, client, output_format).await?;
+        }
         FunctionsCmd::Logs {
             name,
             tail,
             request_id,
+            ..
         } => {
             crate::commands::logs::run(&name, tail, request_id, client, output_format).await?;
         }
         FunctionsCmd::Metrics { name, range } => {
             crate::commands::metrics::run_fn_metrics(&name, range, client, output_format).await?;
         }
+        FunctionsCmd::Latency {
+            name,
+            range,
+            samples,
+        } => {
+            crate::commands::metrics::run_fn_latency(&name, &range, samples, client, output_format)
+                .await?;
+        }
         FunctionsCmd::Heatmap { name, weeks } => {
             crate::commands::metrics::run_fn_heatmap(&name, weeks, client, output_format).await?;
         }
+        FunctionsCmd::Anomalies { range, threshold } => {
+            crate::commands::metrics::run_fn_anomalies(&range, threshold, client, output_format)
+                .await?;
+        }
+        FunctionsCmd::Memory { name } => {
+            crate::commands::metrics::run_fn_memory(&name, client, output_format).await?;
+        }
+        FunctionsCmd::Profile {
+            name,
+            duration,
+            kind,
+            out,
+            flamegraph,
+            max_wait,
+        } => {
+            crate::commands::profile::run_fn_profile(
+                &name,
+                &duration,
+                &kind,
+                out,
+                flamegraph,
+                &max_wait,
+                client,
+                output_format,
+            )
+            .await?;
+        }
         FunctionsCmd::Scaling { cmd } => {
             crate::commands::scaling::run(cmd, client, output_format).await?;
         }
         FunctionsCmd::Capacity { cmd } => {
             crate::commands::capacity::run(cmd, client, output_format).await?;
         }
+        FunctionsCmd::Throttle { cmd } => {
+            crate::commands::throttle::run(cmd, client, output_format).await?;
+        }
+        FunctionsCmd::Aliases { cmd } => {
+            crate::commands::aliases::run(cmd, client, output_format).await?;
+        }
+        FunctionsCmd::Canary { cmd } => {
+            crate::commands::canary::run(cmd, client, output_format).await?;
+        }
+        FunctionsCmd::Caps { cmd } => {
+            crate::commands::caps::run(cmd, client, output_format).await?;
+        }
         FunctionsCmd::Schedules { cmd } => {
             crate::commands::schedules::run(cmd, client, output_format).await?;
         }
@@ -23,6 +23,9 @@ pub enum RoutesCmd {
         path: String,
         #[arg(long)]
         function: String,
+        /// Route to this alias of the function instead of its latest version
+        #[arg(long)]
+        alias: Option<String>,
         #[arg(long)]
         methods: Vec<String>,
         #[arg(long)]
@@ -41,6 +44,9 @@ pub enum RoutesCmd {
         path: Option<String>,
         #[arg(long)]
         function: Option<String>,
+        /// Route to this alias of the function ("" for the latest version)
+        #[arg(long)]
+        alias: Option<String>,
         #[arg(long)]
         enabled: Option<bool>,
     },
@@ -54,6 +60,7 @@ const ROUTE_COLUMNS: &[Column] = &[
     Column::new("Path", "path"),
     Column::new("Methods", "methods"),
     Column::new("Function", "function_name"),
+    Column::new("Alias", "function_alias"),
     Column::new("Auth", "auth_strategy"),
     Column::wide("Enabled", "enabled"),
     Column::new("Created", "created_at"),
@@ -71,6 +78,7 @@ async fn run_routes(cmd: RoutesCmd, client: &NovaClient, output_format: &str) ->
             domain,
             path,
             function,
+            alias,
             methods,
             auth,
         } => {
@@ -79,6 +87,9 @@ async fn run_routes(cmd: RoutesCmd, client: &NovaClient, output_format: &str) ->
                 "path": path,
                 "function_name": function,
             });
+            if let Some(a) = alias {
+                body["function_alias"] = json!(a);
+            }
             if !methods.is_empty() {
                 body["methods"] = json!(methods);
             }
@@ -86,21 +97,22 @@ async fn run_routes(cmd: RoutesCmd, client: &NovaClient, output_format: &str) ->
                 body["auth_strategy"] = json!(a);
             }
             let result = client.post("/gateway/routes", &body).await?;
-            output::render_single(&result, ROUTE_COLUMNS, output_format);
+            output::render_single(&result, ROUTE_COLUMNS, output_format)?;
         }
         RoutesCmd::List => {
-            let result = client.get("/gateway/routes").await?;
-            output::render(&result, ROUTE_COLUMNS, output_format);
+            let result = client.get_paged("/gateway/routes").await?;
+            output::render(&result, ROUTE_COLUMNS, output_format)?;
         }
         RoutesCmd::Get { id } => {
             let result = client.get(&format!("/gateway/routes/{id}")).await?;
-            output::render_single(&result, ROUTE_COLUMNS, output_format);
+            output::render_single(&result, ROUTE_COLUMNS, output_format)?;
         }
         RoutesCmd::Update {
             id,
             domain,
             path,
             function,
+            alias,
             enabled,
         } => {
             let mut body = json!({});
@@ -113,13 +125,16 @@ async fn run_routes(cmd: RoutesCmd, client: &NovaClient, output_format: &str) ->
             if let Some(f) = function {
                 body["function_name"] = json!(f);
             }
+            if let Some(a) = alias {
+                body["function_alias"] = json!(a);
+            }
             if let Some(e) = enabled {
                 body["enabled"] = json!(e);
             }
             let result = client
                 .patch(&format!("/gateway/routes/{id}"), &body)
                 .await?;
-            output::render_single(&result, ROUTE_COLUMNS, output_format);
+            output::render_single(&result, ROUTE_COLUMNS, output_format)?;
         }
         RoutesCmd::Delete { id } => {
             client.delete(&format!("/gateway/routes/{id}")).await?;
@@ -27,27 +27,27 @@ pub async fn run(cmd: HealthCmd, client: &NovaClient, output_format: &str) -> Re
     match cmd {
         HealthCmd::Status => {
             let result = client.get("/health").await?;
-            output::render_single(&result, HEALTH_COLUMNS, output_format);
+            output::render_single(&result, HEALTH_COLUMNS, output_format)?;
         }
         HealthCmd::Live => {
             let result = client.get("/health/live").await?;
             output::print_success("Liveness: OK");
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&result, &[], output_format);
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, &[], output_format)?;
             }
         }
         HealthCmd::Ready => {
             let result = client.get("/health/ready").await?;
             output::print_success("Readiness: OK");
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&result, &[], output_format);
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, &[], output_format)?;
             }
         }
         HealthCmd::Startup => {
             let result = client.get("/health/startup").await?;
             output::print_success("Startup: OK");
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&result, &[], output_format);
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, &[], output_format)?;
             }
         }
     }
@@ -63,7 +63,7 @@ pub async fn run_stats(client: &NovaClient, output_format: &str) -> Result<()> {
             Column::new("Total Pools", "total_pools"),
         ],
         output_format,
-    );
+    )?;
     Ok(())
 }
 
@@ -76,7 +76,7 @@ pub async fn run_invocations(
     if let Some(l) = limit {
         path = format!("{path}?limit={l}");
     }
-    let result = client.get(&path).await?;
+    let result = client.get_paged(&path).await?;
     output::render(
         &result,
         &[
@@ -88,6 +88,6 @@ pub async fn run_invocations(
             Column::new("Timestamp", "timestamp"),
         ],
         output_format,
-    );
+    )?;
     Ok(())
 }
@@ -1,17 +1,20 @@
 use crate::client::NovaClient;
-use crate::error::Result;
+use crate::error::{OrbitError, Result};
 use crate::output::{self, Column};
-use indicatif::{ProgressBar, ProgressStyle};
+use futures_util::{SinkExt, StreamExt};
+use indicatif::ProgressStyle;
 use serde_json::{Value, json};
+use std::io::Write;
 use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
 
 const INVOKE_COLUMNS: &[Column] = &[
     Column::new("Request ID", "request_id"),
     Column::new("Duration (ms)", "duration_ms"),
     Column::new("Cold Start", "cold_start"),
     Column::new("Version", "version"),
-    Column::new("Output", "output"),
-    Column::new("Error", "error"),
+    Column::new("Output", "output").max_width(40),
+    Column::new("Error", "error").max_width(40),
 ];
 
 const ASYNC_COLUMNS: &[Column] = &[
@@ -19,16 +22,20 @@ const ASYNC_COLUMNS: &[Column] = &[
     Column::new("Function", "function_name"),
     Column::new("Status", "status"),
     Column::new("Attempts", "max_attempts"),
+    Column::new("Priority", "priority"),
+    Column::new("Run At", "next_run_at"),
     Column::new("Created", "created_at"),
+    Column::wide("Callback URL", "callback_url"),
 ];
 
-pub async fn run_invoke(
-    name: &str,
-    payload: Option<String>,
-    payload_file: Option<String>,
-    client: &NovaClient,
-    output_format: &str,
-) -> Result<()> {
+/// Scheduling options for `invoke-async`.
+pub struct AsyncOptions {
+    pub delay: Option<String>,
+    pub priority: Option<String>,
+    pub callback_url: Option<String>,
+}
+
+fn read_payload(payload: Option<String>, payload_file: Option<String>) -> Result<Value> {
     let body: Value = match (payload, payload_file) {
         (Some(p), _) => serde_json::from_str(&p)
             .map_err(|e| crate::error::OrbitError::Input(format!("Invalid JSON payload: {e}")))?,
@@ -42,8 +49,43 @@ pub async fn run_invoke(
         }
         _ => json!({}),
     };
+    Ok(body)
+}
+
+/// `path` with the `qualifier` query parameter (a version number or alias
+/// name) that makes the server invoke that version instead of the one
+/// traffic is routed to.
+fn qualified(path: String, qualifier: Option<&str>) -> String {
+    match qualifier {
+        Some(q) => format!("{path}?qualifier={q}"),
+        None => path,
+    }
+}
+
+/// Fills in the invoked version when the server leaves it out, so it always
+/// shows in the result: the number asked for, or the alias resolved.
+fn echo_qualifier(result: &mut Value, qualifier: Option<&str>) {
+    if let Some(q) = qualifier
+        && result.get("version").is_none_or(Value::is_null)
+    {
+        result["version"] = match q.parse::<u64>() {
+            Ok(v) => json!(v),
+            Err(_) => json!(format!("alias:{q}")),
+        };
+    }
+}
+
+pub async fn run_invoke(
+    name: &str,
+    payload: Option<String>,
+    payload_file: Option<String>,
+    qualifier: Option<String>,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let body = read_payload(payload, payload_file)?;
 
-    let spinner = ProgressBar::new_spinner();
+    let spinner = output::progress_bar("invoke", None);
     spinner.set_style(
         ProgressStyle::default_spinner()
             .template("{spinner:.cyan} Invoking {msg}...")
@@ -52,12 +94,89 @@ pub async fn run_invoke(
     spinner.set_message(name.to_string());
     spinner.enable_steady_tick(Duration::from_millis(80));
 
-    let result = client
-        .post(&format!("/functions/{name}/invoke"), &body)
+    let mut result = client
+        .post(
+            &qualified(format!("/functions/{name}/invoke"), qualifier.as_deref()),
+            &body,
+        )
         .await?;
     spinner.finish_and_clear();
+    echo_qualifier(&mut result, qualifier.as_deref());
 
-    output::render_single(&result, INVOKE_COLUMNS, output_format);
+    output::render_single(&result, INVOKE_COLUMNS, output_format)?;
+    Ok(())
+}
+
+/// Invokes over a WebSocket, printing `chunk` frames as they arrive.
+///
+/// The server answers with JSON frames tagged by `type`: `chunk` carries a
+/// piece of output in `data`, `result` carries the final invoke response and
+/// `error` aborts the invocation.
+pub async fn run_invoke_stream(
+    name: &str,
+    payload: Option<String>,
+    payload_file: Option<String>,
+    qualifier: Option<String>,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let body = read_payload(payload, payload_file)?;
+    let ws_err = |e: tokio_tungstenite::tungstenite::Error| OrbitError::WebSocket(e.to_string());
+
+    let mut socket = client
+        .websocket(&qualified(
+            format!("/functions/{name}/invoke/ws"),
+            qualifier.as_deref(),
+        ))
+        .await?;
+    socket
+        .send(Message::text(body.to_string()))
+        .await
+        .map_err(ws_err)?;
+
+    let mut stdout = std::io::stdout();
+    let mut result = None;
+    while let Some(message) = socket.next().await {
+        let frame: Value = match message.map_err(ws_err)? {
+            Message::Text(text) => serde_json::from_str(text.as_str())
+                .unwrap_or_else(|_| json!({ "type": "chunk", "data": text.as_str() })),
+            Message::Binary(bytes) => {
+                stdout.write_all(&bytes)?;
+                stdout.flush()?;
+                continue;
+            }
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        match frame.get("type").and_then(|t| t.as_str()) {
+            Some("chunk") => {
+                match frame.get("data") {
+                    Some(Value::String(s)) => write!(stdout, "{s}")?,
+                    Some(other) => write!(stdout, "{other}")?,
+                    None => {}
+                }
+                stdout.flush()?;
+            }
+            Some("error") => {
+                let message = frame
+                    .get("error")
+                    .and_then(|e| e.as_str())
+                    .unwrap_or("invocation failed");
+                return Err(OrbitError::WebSocket(message.to_string()));
+            }
+            _ => {
+                result = Some(frame);
+                break;
+            }
+        }
+    }
+    let _ = socket.close(None).await;
+
+    if let Some(mut result) = result {
+        println!();
+        echo_qualifier(&mut result, qualifier.as_deref());
+        output::render_single(&result, INVOKE_COLUMNS, output_format)?;
+    }
     Ok(())
 }
 
@@ -66,6 +185,7 @@ pub async fn run_invoke_async(
     payload: Option<String>,
     max_attempts: Option<i64>,
     idempotency_key: Option<String>,
+    options: AsyncOptions,
     client: &NovaClient,
     output_format: &str,
 ) -> Result<()> {
@@ -81,10 +201,31 @@ pub async fn run_invoke_async(
     if let Some(k) = idempotency_key {
         body["idempotency_key"] = json!(k);
     }
+    if let Some(d) = options.delay {
+        let delay = crate::duration::parse(&d)?;
+        if delay.num_seconds() < 0 {
+            return Err(OrbitError::Input("Delay cannot be negative".into()));
+        }
+        body["delay_s"] = json!(delay.num_seconds());
+    }
+    if let Some(p) = options.priority {
+        body["priority"] = json!(p);
+    }
+    if let Some(url) = options.callback_url {
+        match reqwest::Url::parse(&url) {
+            Ok(u) if u.scheme() == "http" || u.scheme() == "https" => {}
+            _ => {
+                return Err(OrbitError::Input(format!(
+                    "Invalid callback URL '{url}': expected http(s)://..."
+                )));
+            }
+        }
+        body["callback_url"] = json!(url);
+    }
 
     let result = client
         .post(&format!("/functions/{name}/invoke-async"), &body)
         .await?;
-    output::render_single(&result, ASYNC_COLUMNS, output_format);
+    output::render_single(&result, ASYNC_COLUMNS, output_format)?;
     Ok(())
 }
@@ -46,15 +46,15 @@ pub async fn run(cmd: LayersCmd, client: &NovaClient, output_format: &str) -> Re
                 body["version"] = json!(v);
             }
             let result = client.post("/layers", &body).await?;
-            output::render_single(&result, LAYER_COLUMNS, output_format);
+            output::render_single(&result, LAYER_COLUMNS, output_format)?;
         }
         LayersCmd::List => {
-            let result = client.get("/layers").await?;
-            output::render(&result, LAYER_COLUMNS, output_format);
+            let result = client.get_paged("/layers").await?;
+            output::render(&result, LAYER_COLUMNS, output_format)?;
         }
         LayersCmd::Get { name } => {
             let result = client.get(&format!("/layers/{name}")).await?;
-            output::render_single(&result, LAYER_COLUMNS, output_format);
+            output::render_single(&result, LAYER_COLUMNS, output_format)?;
         }
         LayersCmd::Delete { name } => {
             client.delete(&format!("/layers/{name}")).await?;
@@ -72,13 +72,13 @@ pub async fn run_fn(cmd: FnLayersSubCmd, client: &NovaClient, output_format: &st
                 .put(&format!("/functions/{name}/layers"), &body)
                 .await?;
             output::print_success(&format!("Layers set for '{name}'."));
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&result, &[], output_format);
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, &[], output_format)?;
             }
         }
         FnLayersSubCmd::Get { name } => {
             let result = client.get(&format!("/functions/{name}/layers")).await?;
-            output::render(&result, LAYER_COLUMNS, output_format);
+            output::render(&result, LAYER_COLUMNS, output_format)?;
         }
     }
     Ok(())
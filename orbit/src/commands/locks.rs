@@ -0,0 +1,85 @@
+use crate::config::{DeployLock, OrbitConfig};
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::Subcommand;
+use serde_json::json;
+
+const LOCK_COLUMNS: &[Column] = &[
+    Column::new("Until", "until"),
+    Column::new("Reason", "reason"),
+    Column::new("Set By", "set_by"),
+    Column::new("Active", "active"),
+    Column::wide("Set At", "set_at"),
+];
+
+#[derive(Subcommand)]
+pub enum LocksCmd {
+    /// Freeze changes until a point in time (requires --reason)
+    Set {
+        /// End of the freeze: RFC 3339 timestamp, date (YYYY-MM-DD, the
+        /// freeze lifts at the start of that day, UTC), or duration from now
+        /// (e.g. 12h, 3d)
+        #[arg(long)]
+        until: String,
+    },
+    /// Show the current change freeze
+    Show,
+    /// Lift the change freeze
+    Clear,
+}
+
+fn parse_until(input: &str) -> Result<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(input) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    crate::duration::parse(input)
+        .and_then(crate::duration::from_now)
+        .map_err(|_| {
+            OrbitError::Input(format!(
+                "Invalid --until '{input}'. Use a timestamp, YYYY-MM-DD, or a duration like 3d"
+            ))
+        })
+}
+
+pub fn run(cmd: LocksCmd, reason: Option<String>, output_format: &str) -> Result<()> {
+    let mut cfg = OrbitConfig::load();
+    match cmd {
+        LocksCmd::Set { until } => {
+            let reason = reason
+                .ok_or_else(|| OrbitError::Input("--reason is required to set a lock".into()))?;
+            let until = parse_until(&until)?;
+            if until <= Utc::now() {
+                return Err(OrbitError::Input("--until must be in the future".into()));
+            }
+            cfg.lock = Some(DeployLock {
+                until,
+                reason,
+                set_by: std::env::var("USER").ok(),
+                set_at: Utc::now(),
+            });
+            cfg.save()?;
+            output::print_success(&format!("Changes frozen until {}.", until.to_rfc3339()));
+        }
+        LocksCmd::Show => match &cfg.lock {
+            Some(lock) => {
+                let mut row = serde_json::to_value(lock)?;
+                row["active"] = json!(lock.is_active());
+                output::render_single(&row, LOCK_COLUMNS, output_format)?;
+            }
+            None => println!("No change freeze set."),
+        },
+        LocksCmd::Clear => {
+            if cfg.lock.take().is_none() {
+                println!("No change freeze set.");
+                return Ok(());
+            }
+            cfg.save()?;
+            output::print_success("Change freeze lifted.");
+        }
+    }
+    Ok(())
+}
@@ -1,13 +1,17 @@
 use crate::client::NovaClient;
-use crate::error::Result;
+use crate::error::{OrbitError, Result};
 use crate::output::{self, Column};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::time::Duration;
 
 const LOG_COLUMNS: &[Column] = &[
     Column::new("Request ID", "request_id"),
     Column::new("Status", "status"),
     Column::new("Duration (ms)", "duration_ms"),
     Column::new("Cold Start", "cold_start"),
-    Column::wide("Output", "output"),
+    Column::wide("Output", "output").max_width(40),
     Column::wide("Error", "error"),
     Column::new("Timestamp", "timestamp"),
 ];
@@ -30,7 +34,209 @@ pub async fn run(
     if !params.is_empty() {
         path = format!("{}?{}", path, params.join("&"));
     }
-    let result = client.get(&path).await?;
-    output::render(&result, LOG_COLUMNS, output_format);
+    let result = client.get_paged(&path).await?;
+    output::render(&result, LOG_COLUMNS, output_format)?;
     Ok(())
 }
+
+/// Entries fetched to fill the gap after the stream drops.
+const CATCH_UP_TAIL: u32 = 100;
+
+/// How often logs are polled when the server has no log stream.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Bounds of the wait before reconnecting, doubling after each failure.
+const RECONNECT_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(30);
+
+/// The first of `keys` set on `entry`; log entries name some fields
+/// differently depending on where they come from.
+pub(crate) fn field<'a>(entry: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter()
+        .filter_map(|k| entry.get(*k))
+        .find(|v| !v.is_null() && *v != "")
+}
+
+/// The entries of a logs response, oldest first.
+pub(crate) fn entries(result: &Value) -> Vec<Value> {
+    let mut entries = match result.get("items").unwrap_or(result) {
+        Value::Array(entries) => entries.clone(),
+        _ => Vec::new(),
+    };
+    entries.sort_by_key(|e| {
+        ["timestamp", "created_at"]
+            .iter()
+            .find_map(|f| crate::duration::timestamp(e, f))
+    });
+    entries
+}
+
+/// Where following has got to: entries at or before `last` were printed,
+/// those at `last` itself listed in `seen` by request ID.
+#[derive(Default)]
+struct Cursor {
+    last: Option<DateTime<Utc>>,
+    seen: HashSet<String>,
+}
+
+impl Cursor {
+    /// Prints `entry` unless already printed.
+    fn print(&mut self, entry: &Value, output_format: &str) {
+        let id = field(entry, &["request_id", "id"])
+            .map(output::format_value)
+            .unwrap_or_default();
+        let at = ["timestamp", "created_at"]
+            .iter()
+            .find_map(|f| crate::duration::timestamp(entry, f));
+        if let Some(at) = at {
+            match self.last {
+                Some(last) if at < last => return,
+                Some(last) if at == last => {
+                    if !self.seen.insert(id.clone()) {
+                        return;
+                    }
+                }
+                _ => {
+                    self.last = Some(at);
+                    self.seen = HashSet::from([id.clone()]);
+                }
+            }
+        }
+
+        if output::is_machine_readable(output_format) {
+            output::print_line(&entry.to_string());
+            return;
+        }
+        let time = at.map_or_else(
+            || "-".to_string(),
+            |t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S%.3f")
+                    .to_string()
+            },
+        );
+        let status = match field(entry, &["status"]) {
+            Some(s) => output::format_value(s),
+            None => match entry["success"].as_bool() {
+                Some(true) => "success".to_string(),
+                Some(false) => "failed".to_string(),
+                None => "-".to_string(),
+            },
+        };
+        let mut line = format!(
+            "{time} {id} {status} {}ms",
+            entry["duration_ms"].as_i64().unwrap_or(0)
+        );
+        if entry["cold_start"].as_bool() == Some(true) {
+            line.push_str(" (cold)");
+        }
+        let detail =
+            field(entry, &["error", "error_message"]).or_else(|| field(entry, &["output"]));
+        if let Some(detail) = detail {
+            line.push(' ');
+            line.push_str(&output::format_value(detail));
+        }
+        output::print_line(&line);
+        for stream in ["stdout", "stderr"] {
+            for text in entry[stream].as_str().unwrap_or_default().lines() {
+                output::print_line(&format!("    {text}"));
+            }
+        }
+    }
+
+    /// Prints the newest `tail` entries not printed yet, oldest first.
+    async fn catch_up(
+        &mut self,
+        client: &NovaClient,
+        name: &str,
+        tail: u32,
+        output_format: &str,
+    ) -> Result<()> {
+        let mut path = format!("/functions/{name}/logs?tail={tail}");
+        if let Some(last) = self.last {
+            let since = last.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+            path = format!("{path}&since={since}");
+        }
+        let result = client.get(&path).await?;
+        for entry in &entries(&result) {
+            self.print(entry, output_format);
+        }
+        Ok(())
+    }
+}
+
+/// `orbit functions logs --follow`: prints the latest entries, then new ones
+/// as they arrive over the server's log stream, until interrupted. When the
+/// stream drops it reconnects with backoff, filling the gap from the recent
+/// logs; servers without a stream are polled instead.
+pub async fn follow(
+    name: &str,
+    tail: Option<u32>,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    output::disable_pager();
+    let mut cursor = Cursor::default();
+    cursor
+        .catch_up(client, name, tail.unwrap_or(10), output_format)
+        .await?;
+
+    let mut backoff = RECONNECT_MIN;
+    loop {
+        match client
+            .stream(&format!("/functions/{name}/logs/stream"))
+            .await
+        {
+            Ok(mut events) => {
+                backoff = RECONNECT_MIN;
+                while let Some(Ok(entry)) = events.next().await {
+                    cursor.print(&entry, output_format);
+                }
+                output::print_status("Log stream closed; reconnecting.");
+            }
+            Err(OrbitError::Api {
+                status: 404 | 405 | 501,
+                ..
+            }) => return poll(&mut cursor, client, name, output_format).await,
+            Err(e) => output::print_warning(&format!(
+                "Log stream unavailable ({e}); retrying in {}s.",
+                backoff.as_secs()
+            )),
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(RECONNECT_MAX);
+        if let Err(e) = cursor
+            .catch_up(client, name, CATCH_UP_TAIL, output_format)
+            .await
+        {
+            output::print_warning(&format!("Cannot fetch logs: {e}"));
+        }
+    }
+}
+
+/// Follows by polling the recent logs, for servers without a log stream.
+async fn poll(
+    cursor: &mut Cursor,
+    client: &NovaClient,
+    name: &str,
+    output_format: &str,
+) -> Result<()> {
+    let mut ticks = tokio::time::interval(POLL_INTERVAL);
+    ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut failing = false;
+    loop {
+        ticks.tick().await;
+        match cursor
+            .catch_up(client, name, CATCH_UP_TAIL, output_format)
+            .await
+        {
+            Ok(()) => failing = false,
+            // Warn once per outage rather than on every poll.
+            Err(e) if !failing => {
+                output::print_warning(&format!("Cannot fetch logs: {e}; retrying."));
+                failing = true;
+            }
+            Err(_) => {}
+        }
+    }
+}
@@ -1,7 +1,8 @@
-use crate::client::NovaClient;
-use crate::error::Result;
+use crate::client::{DEFAULT_BATCH_CONCURRENCY, NovaClient};
+use crate::error::{OrbitError, Result};
 use crate::output::{self, Column};
 use clap::Subcommand;
+use serde_json::{Value, json};
 
 #[derive(Subcommand)]
 pub enum MetricsCmd {
@@ -32,33 +33,243 @@ const TIMESERIES_COLUMNS: &[Column] = &[
     Column::wide("P99", "p99_ms"),
 ];
 
+const MEMORY_COLUMNS: &[Column] = &[
+    Column::new("Instance", "id"),
+    Column::new("Configured MB", "memory_mb"),
+    Column::new("RSS MB", "rss_mb"),
+    Column::new("Peak RSS MB", "peak_rss_mb"),
+    Column::new("Peak %", "peak_pct"),
+    Column::new("OOM Kills", "oom_kills"),
+    Column::new("GC Runs", "gc.collections"),
+    Column::wide("GC Pause ms", "gc.pause_ms"),
+    Column::wide("Heap MB", "gc.heap_mb"),
+    Column::wide("Node", "node"),
+];
+
+/// Headroom kept above the observed peak when suggesting a memory size.
+const MEMORY_HEADROOM: f64 = 1.25;
+
+const ANOMALY_COLUMNS: &[Column] = &[
+    Column::new("Function", "function"),
+    Column::new("Metric", "metric"),
+    Column::new("Current", "current"),
+    Column::new("Baseline", "baseline"),
+    Column::new("Z-Score", "z_score"),
+    Column::new("Severity", "severity"),
+    Column::wide("Std Dev", "stddev"),
+    Column::wide("Samples", "samples"),
+];
+
+/// Baseline buckets needed before a function is scored.
+const MIN_BASELINE_POINTS: usize = 5;
+
+/// Scores the latest value of `series` against the mean and standard
+/// deviation of the values before it. Returns `(current, mean, stddev, z)`.
+fn z_score(series: &[f64]) -> Option<(f64, f64, f64, f64)> {
+    let (current, baseline) = series.split_last()?;
+    if baseline.len() < MIN_BASELINE_POINTS {
+        return None;
+    }
+    let n = baseline.len() as f64;
+    let mean = baseline.iter().sum::<f64>() / n;
+    let stddev = (baseline.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    if stddev == 0.0 {
+        return None;
+    }
+    Some((*current, mean, stddev, (current - mean) / stddev))
+}
+
 const HEATMAP_COLUMNS: &[Column] = &[Column::new("Date", "date"), Column::new("Count", "count")];
 
+const LATENCY_COLUMNS: &[Column] = &[
+    Column::new("Latency", "range"),
+    Column::new("Count", "count"),
+    Column::new("Warm", "warm"),
+    Column::new("Cold", "cold"),
+    Column::new("", "bar"),
+];
+
+const LATENCY_BAR_WIDTH: usize = 40;
+/// Invocation logs requested per page when sampling durations.
+const LATENCY_PAGE_SIZE: usize = 500;
+
+/// Upper bucket edges in ms on a 1-2-5 scale, so fast and slow modes of a
+/// bimodal distribution both get readable resolution.
+fn latency_edges(max_ms: i64) -> Vec<i64> {
+    let mut edges = Vec::new();
+    let mut decade = 1;
+    loop {
+        for step in [1, 2, 5] {
+            edges.push(step * decade);
+            if step * decade >= max_ms {
+                return edges;
+            }
+        }
+        decade *= 10;
+    }
+}
+
+fn format_ms(ms: i64) -> String {
+    if ms >= 1000 {
+        format!("{}s", ms as f64 / 1000.0)
+    } else {
+        format!("{ms}ms")
+    }
+}
+
+/// Nearest-rank percentile of sorted durations.
+fn percentile(sorted: &[i64], p: f64) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Samples invocation durations from the function's logs over `range` and
+/// renders them as a histogram split into warm and cold starts.
+pub async fn run_fn_latency(
+    name: &str,
+    range: &str,
+    samples: usize,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let window = crate::duration::parse(range)?;
+    if window.num_seconds() <= 0 {
+        return Err(OrbitError::Input("Range must be positive".into()));
+    }
+    let since = crate::duration::ago(window)?;
+
+    // Logs come newest first; page until the window or the sample cap is
+    // exhausted.
+    let mut durations: Vec<(i64, bool)> = Vec::new();
+    let mut offset = 0;
+    'pages: loop {
+        let page = client
+            .get(&format!(
+                "/functions/{name}/logs?tail={LATENCY_PAGE_SIZE}&offset={offset}"
+            ))
+            .await?;
+        let entries = page
+            .get("items")
+            .unwrap_or(&page)
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        for entry in &entries {
+            if crate::duration::timestamp(entry, "created_at").is_some_and(|t| t < since) {
+                break 'pages;
+            }
+            if let Some(ms) = entry["duration_ms"].as_i64() {
+                durations.push((ms, entry["cold_start"].as_bool().unwrap_or(false)));
+            }
+            if durations.len() >= samples {
+                break 'pages;
+            }
+        }
+        if entries.len() < LATENCY_PAGE_SIZE {
+            break;
+        }
+        offset += entries.len();
+    }
+    if durations.is_empty() {
+        return Err(OrbitError::Input(format!(
+            "No invocations of '{name}' in the last {range}"
+        )));
+    }
+
+    let max_ms = durations.iter().map(|(ms, _)| *ms).max().unwrap_or(0);
+    let edges = latency_edges(max_ms.max(1));
+    let mut counts = vec![(0u64, 0u64); edges.len()];
+    for (ms, cold) in &durations {
+        let i = edges.partition_point(|edge| edge < ms);
+        if *cold {
+            counts[i].1 += 1;
+        } else {
+            counts[i].0 += 1;
+        }
+    }
+    // Leading empty buckets carry no information.
+    let first = counts.iter().position(|(w, c)| w + c > 0).unwrap_or(0);
+    let peak = counts.iter().map(|(w, c)| w + c).max().unwrap_or(1).max(1);
+    let rows: Vec<Value> = (first..edges.len())
+        .map(|i| {
+            let (warm, cold) = counts[i];
+            let lower = if i == 0 { 0 } else { edges[i - 1] };
+            let scale =
+                |n: u64| (n as f64 / peak as f64 * LATENCY_BAR_WIDTH as f64).round() as usize;
+            json!({
+                "range": format!("{}–{}", format_ms(lower), format_ms(edges[i])),
+                "count": warm + cold,
+                "warm": warm,
+                "cold": cold,
+                "bar": format!("{}{}", "█".repeat(scale(warm)), "▒".repeat(scale(cold))),
+            })
+        })
+        .collect();
+    output::render(&Value::Array(rows), LATENCY_COLUMNS, output_format)?;
+
+    if !output::is_machine_readable(output_format) {
+        let summarize = |label: &str, mut ms: Vec<i64>| {
+            ms.sort_unstable();
+            let fmt = |p| {
+                percentile(&ms, p)
+                    .map(format_ms)
+                    .unwrap_or_else(|| "-".into())
+            };
+            output::print_line(&format!(
+                "{label:<5} {:>6} invocations   p50 {:>7}   p95 {:>7}   p99 {:>7}",
+                ms.len(),
+                fmt(50.0),
+                fmt(95.0),
+                fmt(99.0)
+            ));
+        };
+        let split = |cold: bool| -> Vec<i64> {
+            durations
+                .iter()
+                .filter(|(_, c)| *c == cold)
+                .map(|(ms, _)| *ms)
+                .collect()
+        };
+        output::print_line("");
+        summarize("all", durations.iter().map(|(ms, _)| *ms).collect());
+        summarize("warm", split(false));
+        summarize("cold", split(true));
+        output::print_line(&format!(
+            "█ warm  ▒ cold · {} sampled from the last {range}",
+            durations.len()
+        ));
+    }
+    Ok(())
+}
+
 pub async fn run_global(cmd: MetricsCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         MetricsCmd::Json => {
             let result = client.get("/metrics").await?;
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            output::print_line(&serde_json::to_string_pretty(&result)?);
         }
         MetricsCmd::Prometheus => {
             let result = client.get("/metrics/prometheus").await?;
             if let Some(s) = result.as_str() {
-                println!("{s}");
+                output::print_line(s);
             } else {
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                output::print_line(&serde_json::to_string_pretty(&result)?);
             }
         }
         MetricsCmd::Timeseries { range } => {
             let result = client
                 .get(&format!("/metrics/timeseries?range={range}"))
                 .await?;
-            output::render(&result, TIMESERIES_COLUMNS, output_format);
+            output::render(&result, TIMESERIES_COLUMNS, output_format)?;
         }
         MetricsCmd::Heatmap { weeks } => {
             let result = client
                 .get(&format!("/metrics/heatmap?weeks={weeks}"))
                 .await?;
-            output::render(&result, HEATMAP_COLUMNS, output_format);
+            output::render(&result, HEATMAP_COLUMNS, output_format)?;
         }
     }
     Ok(())
@@ -85,7 +296,156 @@ pub async fn run_fn_metrics(
             Column::new("Pool Size", "pool.size"),
         ],
         output_format,
-    );
+    )?;
+    Ok(())
+}
+
+/// Reports configured memory against per-instance RSS, OOM kills and GC
+/// activity, and suggests a size from the observed peak once instances have
+/// reported one.
+pub async fn run_fn_memory(name: &str, client: &NovaClient, output_format: &str) -> Result<()> {
+    let function = client.get(&format!("/functions/{name}")).await?;
+    let configured = function.get("memory_mb").and_then(|v| v.as_f64());
+    let instances = client
+        .get_all(&format!("/functions/{name}/instances"))
+        .await?;
+    let mut rows: Vec<Value> = instances.as_array().cloned().unwrap_or_default();
+    for row in &mut rows {
+        row["memory_mb"] = function.get("memory_mb").cloned().unwrap_or(Value::Null);
+        if let (Some(peak), Some(limit)) = (row["peak_rss_mb"].as_f64(), configured)
+            && limit > 0.0
+        {
+            row["peak_pct"] = json!(format!("{:.0}%", peak * 100.0 / limit));
+        }
+    }
+    output::render(&Value::Array(rows.clone()), MEMORY_COLUMNS, output_format)?;
+    if output::is_machine_readable(output_format) {
+        return Ok(());
+    }
+
+    let peak = rows
+        .iter()
+        .filter_map(|r| r["peak_rss_mb"].as_f64())
+        .filter(|&p| p > 0.0)
+        .reduce(f64::max);
+    let oom_kills: u64 = rows.iter().filter_map(|r| r["oom_kills"].as_u64()).sum();
+    output::print_line("");
+    match peak {
+        Some(peak) => output::print_line(&format!("Peak RSS across instances: {peak:.0} MB")),
+        None => output::print_line("Peak RSS across instances: (no samples)"),
+    }
+    match configured {
+        Some(limit) => output::print_line(&format!("Configured memory:         {limit:.0} MB")),
+        None => output::print_line("Configured memory:         (not set)"),
+    }
+    match peak {
+        Some(peak) => {
+            // Round the suggestion up to the next 64 MB step.
+            let suggested = ((peak * MEMORY_HEADROOM / 64.0).ceil() as i64 * 64).max(64);
+            output::print_line(&format!(
+                "Suggested memory:          {suggested} MB (peak + 25% headroom)"
+            ));
+        }
+        None => output::print_line(
+            "Suggested memory:          insufficient data (invoke the function first)",
+        ),
+    }
+    if oom_kills > 0 {
+        output::print_warning(&format!(
+            "{oom_kills} OOM kill(s) recorded; the function needs more memory than configured."
+        ));
+    }
+    Ok(())
+}
+
+/// Flags functions whose latest error rate or latency deviates from their
+/// baseline over `range` by at least `threshold` standard deviations.
+pub async fn run_fn_anomalies(
+    range: &str,
+    threshold: f64,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let functions = client.get_all("/functions").await?;
+    let names: Vec<String> = functions
+        .as_array()
+        .map(|fns| {
+            fns.iter()
+                .filter_map(|f| f["name"].as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    let results = client
+        .batch(names, DEFAULT_BATCH_CONCURRENCY, |c, name| async move {
+            c.get(&format!("/functions/{name}/metrics?range={range}"))
+                .await
+        })
+        .await;
+
+    let mut rows = Vec::new();
+    for (name, result) in results {
+        let metrics = match result {
+            Ok(m) => m,
+            Err(e) => {
+                output::print_warning(&format!("Skipping '{name}': {e}"));
+                continue;
+            }
+        };
+        // Idle buckets carry no signal for either metric.
+        let buckets: Vec<&Value> = metrics["timeseries"]
+            .as_array()
+            .map(|b| {
+                b.iter()
+                    .filter(|b| b["invocations"].as_f64().unwrap_or(0.0) > 0.0)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let error_rate: Vec<f64> = buckets
+            .iter()
+            .map(|b| {
+                b["errors"].as_f64().unwrap_or(0.0) * 100.0
+                    / b["invocations"].as_f64().unwrap_or(1.0)
+            })
+            .collect();
+        let latency: Vec<f64> = buckets
+            .iter()
+            .map(|b| b["avg_duration"].as_f64().unwrap_or(0.0))
+            .collect();
+
+        for (metric, series, unit) in [
+            ("error_rate", &error_rate, "%"),
+            ("latency", &latency, " ms"),
+        ] {
+            let Some((current, mean, stddev, z)) = z_score(series) else {
+                continue;
+            };
+            if z.abs() < threshold {
+                continue;
+            }
+            rows.push(json!({
+                "function": name,
+                "metric": metric,
+                "current": format!("{current:.1}{unit}"),
+                "baseline": format!("{mean:.1}{unit}"),
+                "stddev": format!("{stddev:.2}"),
+                "z_score": (z * 100.0).round() / 100.0,
+                "severity": if z.abs() >= threshold * 2.0 { "high" } else { "medium" },
+                "samples": series.len(),
+            }));
+        }
+    }
+
+    rows.sort_by(|a, b| {
+        let z = |r: &Value| r["z_score"].as_f64().unwrap_or(0.0).abs();
+        z(b).total_cmp(&z(a))
+    });
+    if rows.is_empty() && !output::is_machine_readable(output_format) {
+        output::print_success(&format!(
+            "No anomalies above z={threshold} in the last {range}."
+        ));
+        return Ok(());
+    }
+    output::render(&Value::Array(rows), ANOMALY_COLUMNS, output_format)?;
     Ok(())
 }
 
@@ -98,6 +458,6 @@ pub async fn run_fn_heatmap(
     let result = client
         .get(&format!("/functions/{name}/heatmap?weeks={weeks}"))
         .await?;
-    output::render(&result, HEATMAP_COLUMNS, output_format);
+    output::render(&result, HEATMAP_COLUMNS, output_format)?;
     Ok(())
 }
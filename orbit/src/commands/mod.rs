@@ -1,37 +1,59 @@
 pub mod ai;
+pub mod aliases;
 pub mod apikeys;
 pub mod async_invocations;
+pub mod audit;
+pub mod auth;
 pub mod backends;
+pub mod batch;
+pub mod canary;
 pub mod capacity;
+pub mod caps;
 pub mod cluster;
 pub mod code;
+pub mod compare;
 pub mod config_cmd;
 pub mod cost;
+pub mod deploy;
+pub mod dev;
 pub mod diagnostics;
 pub mod dlq;
 pub mod docs;
+pub mod doctor;
 pub mod events;
 pub mod functions;
 pub mod gateway;
 pub mod health;
 pub mod invoke;
 pub mod layers;
+pub mod locks;
 pub mod logs;
 pub mod metrics;
+pub mod monitor;
+pub mod namespace;
 pub mod notifications;
+pub mod operations;
 pub mod prewarm;
+pub mod profile;
 pub mod rate_limit;
 pub mod rbac;
+pub mod release;
+pub mod report;
 pub mod runtimes;
+pub mod scaffold;
 pub mod scaling;
 pub mod schedules;
 pub mod secrets;
+pub mod service_accounts;
+pub mod setup;
 pub mod slo;
 pub mod snapshots;
 pub mod state;
 pub mod tenant_perms;
 pub mod tenants;
+pub mod throttle;
 pub mod triggers;
 pub mod versions;
 pub mod volumes;
+pub mod whatif;
 pub mod workflows;
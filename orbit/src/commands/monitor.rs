@@ -0,0 +1,306 @@
+//! `orbit monitor run`: evaluates simple threshold rules on an interval and
+//! posts to a webhook or Slack when a rule starts or stops firing. A stopgap
+//! for deployments without alerting infrastructure.
+//!
+//! Rules file:
+//!
+//! ```yaml
+//! interval: 1m
+//! webhook: https://hooks.slack.com/services/T000/B000/XXXX
+//! rules:
+//!   - name: orders-lag
+//!     metric: subscription_lag
+//!     subscription: 3f2a...
+//!     threshold: 1000
+//!   - name: api-errors
+//!     metric: error_rate        # percent over `window`
+//!     function: api
+//!     threshold: 5
+//!     window: 15m
+//!   - name: async-backlog
+//!     metric: async_backlog
+//!     threshold: 500
+//!   - name: health
+//!     metric: health            # fires when /health is not "ok"
+//! ```
+
+use crate::client::NovaClient;
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use clap::Subcommand;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::path::Path;
+
+const RULE_COLUMNS: &[Column] = &[
+    Column::new("Rule", "rule"),
+    Column::new("Metric", "metric"),
+    Column::new("Value", "value"),
+    Column::new("Threshold", "threshold"),
+    Column::new("Status", "status"),
+];
+
+#[derive(Subcommand)]
+pub enum MonitorCmd {
+    /// Evaluate rules on an interval and notify a webhook when they fire
+    Run {
+        /// Rules file (YAML)
+        #[arg(long)]
+        rules: String,
+        /// Evaluate once, print the results and exit
+        #[arg(long)]
+        once: bool,
+    },
+}
+
+#[derive(Deserialize)]
+struct RulesFile {
+    #[serde(default = "default_interval")]
+    interval: String,
+    webhook: Option<String>,
+    /// slack or webhook (default: slack for hooks.slack.com URLs)
+    format: Option<String>,
+    rules: Vec<Rule>,
+}
+
+fn default_interval() -> String {
+    "1m".into()
+}
+
+#[derive(Deserialize)]
+struct Rule {
+    name: String,
+    metric: String,
+    #[serde(default)]
+    threshold: f64,
+    function: Option<String>,
+    subscription: Option<String>,
+    #[serde(default = "default_window")]
+    window: String,
+}
+
+fn default_window() -> String {
+    "5m".into()
+}
+
+impl RulesFile {
+    fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            OrbitError::Input(format!("Cannot read rules file {}: {e}", path.display()))
+        })?;
+        let file: Self = serde_yaml::from_str(&content).map_err(|e| {
+            OrbitError::Input(format!("Invalid rules file {}: {e}", path.display()))
+        })?;
+        for rule in &file.rules {
+            match rule.metric.as_str() {
+                "error_rate" | "async_backlog" | "health" => {}
+                "subscription_lag" if rule.subscription.is_some() => {}
+                "subscription_lag" => {
+                    return Err(OrbitError::Input(format!(
+                        "Rule '{}': subscription_lag requires 'subscription'",
+                        rule.name
+                    )));
+                }
+                other => {
+                    return Err(OrbitError::Input(format!(
+                        "Rule '{}': unknown metric '{other}' (expected error_rate, subscription_lag, async_backlog or health)",
+                        rule.name
+                    )));
+                }
+            }
+        }
+        if let Some(format) = &file.format
+            && format != "slack"
+            && format != "webhook"
+        {
+            return Err(OrbitError::Input(format!(
+                "Invalid format '{format}': expected slack or webhook"
+            )));
+        }
+        Ok(file)
+    }
+
+    fn slack(&self) -> bool {
+        match self.format.as_deref() {
+            Some(f) => f == "slack",
+            None => self
+                .webhook
+                .as_deref()
+                .is_some_and(|u| u.contains("hooks.slack.com")),
+        }
+    }
+}
+
+/// Current value of a rule's metric and whether it is over threshold.
+async fn evaluate(client: &NovaClient, rule: &Rule) -> Result<(String, bool)> {
+    let num = |v: &Value| v.as_f64().unwrap_or(0.0);
+    match rule.metric.as_str() {
+        "error_rate" => {
+            let buckets = match &rule.function {
+                Some(name) => client
+                    .get(&format!("/functions/{name}/metrics?range={}", rule.window))
+                    .await?["timeseries"]
+                    .clone(),
+                None => {
+                    client
+                        .get(&format!("/metrics/timeseries?range={}", rule.window))
+                        .await?
+                }
+            };
+            let buckets = buckets.as_array().cloned().unwrap_or_default();
+            let invocations: f64 = buckets.iter().map(|b| num(&b["invocations"])).sum();
+            let errors: f64 = buckets.iter().map(|b| num(&b["errors"])).sum();
+            let rate = if invocations > 0.0 {
+                errors * 100.0 / invocations
+            } else {
+                0.0
+            };
+            Ok((format!("{rate:.2}%"), rate > rule.threshold))
+        }
+        "subscription_lag" => {
+            let id = rule.subscription.as_deref().unwrap_or_default();
+            let lag = num(&client.get(&format!("/subscriptions/{id}")).await?["lag"]);
+            Ok((format!("{lag:.0}"), lag > rule.threshold))
+        }
+        "async_backlog" => {
+            let backlog = num(&client.get("/async-invocations/summary").await?["backlog"]);
+            Ok((format!("{backlog:.0}"), backlog > rule.threshold))
+        }
+        _ => match client.get("/health").await {
+            Ok(health) => {
+                let status = health["status"].as_str().unwrap_or("unknown").to_string();
+                let firing = status != "ok";
+                Ok((status, firing))
+            }
+            // An unreachable server is the most degraded state there is.
+            Err(e) => Ok((format!("unreachable: {e}"), true)),
+        },
+    }
+}
+
+fn threshold(rule: &Rule) -> String {
+    match rule.metric.as_str() {
+        "health" => "ok".into(),
+        "error_rate" => format!("{}%", rule.threshold),
+        _ => rule.threshold.to_string(),
+    }
+}
+
+fn notification(file: &RulesFile, rule: &Rule, value: &str, firing: bool, server: &str) -> Value {
+    let status = if firing { "firing" } else { "resolved" };
+    if file.slack() {
+        let icon = if firing {
+            ":rotating_light:"
+        } else {
+            ":white_check_mark:"
+        };
+        json!({
+            "text": format!(
+                "{icon} *{}* {status}: {} is {value} (threshold {}) on {server}",
+                rule.name,
+                rule.metric,
+                threshold(rule)
+            )
+        })
+    } else {
+        json!({
+            "rule": rule.name,
+            "metric": rule.metric,
+            "value": value,
+            "threshold": rule.threshold,
+            "status": status,
+            "server": server,
+            "at": chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+/// Evaluates every rule once, notifying on state changes. `states` holds
+/// whether each rule was firing after the previous pass.
+async fn tick(client: &NovaClient, file: &RulesFile, states: &mut [bool]) -> Vec<Value> {
+    let mut rows = Vec::new();
+    for (rule, was_firing) in file.rules.iter().zip(states.iter_mut()) {
+        let (value, firing) = match evaluate(client, rule).await {
+            Ok(result) => result,
+            Err(e) => {
+                output::print_warning(&format!("Rule '{}' could not be evaluated: {e}", rule.name));
+                rows.push(json!({
+                    "rule": rule.name,
+                    "metric": rule.metric,
+                    "value": null,
+                    "threshold": threshold(rule),
+                    "status": "error",
+                }));
+                continue;
+            }
+        };
+        rows.push(json!({
+            "rule": rule.name,
+            "metric": rule.metric,
+            "value": value,
+            "threshold": threshold(rule),
+            "status": if firing { "firing" } else { "ok" },
+        }));
+        if firing == *was_firing {
+            continue;
+        }
+        *was_firing = firing;
+
+        let message = format!(
+            "{} {}: {} is {value} (threshold {})",
+            chrono::Local::now().format("%H:%M:%S"),
+            rule.name,
+            rule.metric,
+            threshold(rule)
+        );
+        if firing {
+            output::print_warning(&format!("FIRING {message}"));
+        } else {
+            output::print_success(&format!("RESOLVED {message}"));
+        }
+        if let Some(url) = &file.webhook {
+            let payload = notification(file, rule, &value, firing, client.base_url());
+            if let Err(e) = client.post_webhook(url, &payload).await {
+                output::print_warning(&format!("Webhook delivery failed: {e}"));
+            }
+        }
+    }
+    rows
+}
+
+pub async fn run(cmd: MonitorCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    match cmd {
+        MonitorCmd::Run { rules, once } => {
+            let file = RulesFile::load(Path::new(&rules))?;
+            if file.rules.is_empty() {
+                return Err(OrbitError::Input(format!("No rules in {rules}")));
+            }
+            let interval = crate::duration::parse(&file.interval)?
+                .to_std()
+                .ok()
+                .filter(|d| !d.is_zero())
+                .ok_or_else(|| OrbitError::Input("Interval must be positive".into()))?;
+            let mut states = vec![false; file.rules.len()];
+
+            if once {
+                let rows = tick(client, &file, &mut states).await;
+                output::render(&Value::Array(rows), RULE_COLUMNS, output_format)?;
+                return Ok(());
+            }
+            output::print_status(&format!(
+                "Monitoring {} rule(s) every {}{}. Press Ctrl-C to stop.",
+                file.rules.len(),
+                file.interval,
+                if file.webhook.is_some() {
+                    ""
+                } else {
+                    " (no webhook configured)"
+                }
+            ));
+            loop {
+                tick(client, &file, &mut states).await;
+                tokio::time::sleep(interval).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! `orbit namespace` (`ns`): settings of the namespace selected with
+//! `--tenant`/`--namespace` or the config context.
+//!
+//! Defaults fill in the settings `functions create` is run without, so a
+//! team's conventions (runtime, memory, timeout, env) live in one place.
+
+use crate::client::NovaClient;
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use clap::Subcommand;
+use serde_json::{Value, json};
+
+const DEFAULTS_COLUMNS: &[Column] = &[
+    Column::new("Namespace", "namespace"),
+    Column::new("Runtime", "runtime"),
+    Column::new("Handler", "handler"),
+    Column::new("Memory", "memory_mb"),
+    Column::new("Timeout", "timeout_s"),
+    Column::new("Env", "env_vars"),
+    Column::wide("Updated", "updated_at"),
+];
+
+#[derive(Subcommand)]
+pub enum NamespaceCmd {
+    /// Manage default settings for functions created in the namespace
+    Defaults {
+        #[command(subcommand)]
+        cmd: DefaultsSubCmd,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DefaultsSubCmd {
+    /// Set defaults, replacing any existing ones
+    Set {
+        /// Runtime (python, go, rust, node, etc.)
+        #[arg(long)]
+        runtime: Option<String>,
+        /// Handler entry point
+        #[arg(long)]
+        handler: Option<String>,
+        /// Memory in MB
+        #[arg(long)]
+        memory: Option<i64>,
+        /// Timeout in seconds
+        #[arg(long)]
+        timeout: Option<i64>,
+        /// Environment variables (KEY=VAL)
+        #[arg(long = "env", value_name = "KEY=VAL")]
+        env_vars: Vec<String>,
+    },
+    /// Show the defaults
+    Get,
+    /// Remove the defaults
+    Clear,
+}
+
+fn defaults_path(client: &NovaClient) -> String {
+    format!(
+        "/tenants/{}/namespaces/{}/defaults",
+        client.tenant().unwrap_or("default"),
+        client.namespace().unwrap_or("default")
+    )
+}
+
+/// The current namespace's defaults, or null when none are set.
+pub async fn fetch(client: &NovaClient) -> Result<Value> {
+    match client.get(&defaults_path(client)).await {
+        Err(OrbitError::Api { status: 404, .. }) => Ok(Value::Null),
+        other => other,
+    }
+}
+
+pub async fn run(cmd: NamespaceCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    let NamespaceCmd::Defaults { cmd } = cmd;
+    let namespace = client.namespace().unwrap_or("default");
+    match cmd {
+        DefaultsSubCmd::Set {
+            runtime,
+            handler,
+            memory,
+            timeout,
+            env_vars,
+        } => {
+            let mut body = json!({});
+            if let Some(r) = runtime {
+                body["runtime"] = json!(r);
+            }
+            if let Some(h) = handler {
+                body["handler"] = json!(h);
+            }
+            if let Some(m) = memory {
+                body["memory_mb"] = json!(m);
+            }
+            if let Some(t) = timeout {
+                body["timeout_s"] = json!(t);
+            }
+            if !env_vars.is_empty() {
+                let mut env = serde_json::Map::new();
+                for item in &env_vars {
+                    let (k, v) = item.split_once('=').ok_or_else(|| {
+                        OrbitError::Input(format!("Invalid --env '{item}', expected KEY=VAL"))
+                    })?;
+                    env.insert(k.to_string(), json!(v));
+                }
+                body["env_vars"] = Value::Object(env);
+            }
+            if body.as_object().is_some_and(|b| b.is_empty()) {
+                return Err(OrbitError::Input(
+                    "Set at least one of --runtime, --handler, --memory, --timeout or --env".into(),
+                ));
+            }
+            let mut result = client.put(&defaults_path(client), &body).await?;
+            result["namespace"] = json!(namespace);
+            output::render_single(&result, DEFAULTS_COLUMNS, output_format)?;
+        }
+        DefaultsSubCmd::Get => {
+            let mut result = fetch(client).await?;
+            if result.is_null() {
+                if !output::is_machine_readable(output_format) {
+                    println!("No defaults set for namespace '{namespace}'.");
+                }
+                return Ok(());
+            }
+            result["namespace"] = json!(namespace);
+            output::render_single(&result, DEFAULTS_COLUMNS, output_format)?;
+        }
+        DefaultsSubCmd::Clear => {
+            client.delete(&defaults_path(client)).await?;
+            output::print_success(&format!("Defaults cleared for namespace '{namespace}'."));
+        }
+    }
+    Ok(())
+}
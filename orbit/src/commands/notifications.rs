@@ -26,19 +26,15 @@ const NOTIFICATION_COLUMNS: &[Column] = &[
     Column::new("Created At", "created_at"),
 ];
 
-pub async fn run(
-    cmd: NotificationsCmd,
-    client: &NovaClient,
-    output_format: &str,
-) -> Result<()> {
+pub async fn run(cmd: NotificationsCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         NotificationsCmd::List { status } => {
             let path = match status {
                 Some(s) => format!("/notifications?status={s}"),
                 None => "/notifications".to_string(),
             };
-            let result = client.get(&path).await?;
-            output::render(&result, NOTIFICATION_COLUMNS, output_format);
+            let result = client.get_paged(&path).await?;
+            output::render(&result, NOTIFICATION_COLUMNS, output_format)?;
         }
         NotificationsCmd::UnreadCount => {
             let result = client.get("/notifications/unread-count").await?;
@@ -46,13 +42,13 @@ pub async fn run(
                 &result,
                 &[Column::new("Unread Count", "unread_count")],
                 output_format,
-            );
+            )?;
         }
         NotificationsCmd::Read { id } => {
             let result = client
                 .post(&format!("/notifications/{id}/read"), &json!({}))
                 .await?;
-            output::render_single(&result, NOTIFICATION_COLUMNS, output_format);
+            output::render_single(&result, NOTIFICATION_COLUMNS, output_format)?;
         }
         NotificationsCmd::ReadAll => {
             client.post("/notifications/read-all", &json!({})).await?;
@@ -0,0 +1,106 @@
+//! `orbit operations`: the control plane's records of long-running work
+//! (snapshot creation, runtime imports, replays, ...), and `wait`, which
+//! follows an operation the server accepted with 202 and a `Location` the
+//! same way the global `--wait` does for the command that started it.
+
+use crate::client::NovaClient;
+use crate::error::Result;
+use crate::output::{self, Column};
+use clap::Subcommand;
+use serde_json::json;
+
+const OPERATION_COLUMNS: &[Column] = &[
+    Column::new("ID", "id"),
+    Column::new("Type", "type"),
+    Column::new("Target", "target"),
+    Column::new("Status", "status"),
+    Column::new("Progress %", "progress"),
+    Column::new("Created", "created_at"),
+    Column::wide("Updated", "updated_at"),
+    Column::wide("Error", "error").max_width(40),
+];
+
+#[derive(Subcommand)]
+pub enum OperationsCmd {
+    /// List operations, most recent first
+    List {
+        /// Only operations in this status (e.g. running, failed)
+        #[arg(long)]
+        status: Option<String>,
+        /// Only operations of this type (e.g. snapshot.create)
+        #[arg(long = "type")]
+        op_type: Option<String>,
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+    /// Get an operation's details
+    Get { id: String },
+    /// Cancel a pending or running operation
+    Cancel { id: String },
+    /// Wait for an operation to finish and print its final state
+    Wait {
+        /// Operation ID, or the `Location` path or URL from a 202 response
+        id: String,
+        /// Give up after this long (e.g. 30s, 10m); waits indefinitely by
+        /// default
+        #[arg(long)]
+        timeout: Option<String>,
+    },
+}
+
+fn operation_path(id: &str) -> String {
+    if id.starts_with('/') || id.starts_with("http://") || id.starts_with("https://") {
+        id.to_string()
+    } else {
+        format!("/operations/{id}")
+    }
+}
+
+pub async fn run(cmd: OperationsCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    match cmd {
+        OperationsCmd::List {
+            status,
+            op_type,
+            limit,
+        } => {
+            let mut path = "/operations".to_string();
+            let mut params = vec![];
+            if let Some(s) = status {
+                params.push(format!("status={s}"));
+            }
+            if let Some(t) = op_type {
+                params.push(format!("type={t}"));
+            }
+            if let Some(l) = limit {
+                params.push(format!("limit={l}"));
+            }
+            if !params.is_empty() {
+                path = format!("{}?{}", path, params.join("&"));
+            }
+            let result = client.get_paged(&path).await?;
+            output::render(&result, OPERATION_COLUMNS, output_format)?;
+        }
+        OperationsCmd::Get { id } => {
+            let result = client.get(&format!("/operations/{id}")).await?;
+            output::render_single(&result, OPERATION_COLUMNS, output_format)?;
+        }
+        OperationsCmd::Cancel { id } => {
+            let result = client
+                .post(&format!("/operations/{id}/cancel"), &json!({}))
+                .await?;
+            output::print_success(&format!("Operation '{id}' cancelled."));
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, OPERATION_COLUMNS, output_format)?;
+            }
+        }
+        OperationsCmd::Wait { id, timeout } => {
+            let timeout = match timeout {
+                Some(t) => Some(crate::duration::parse(&t)?.to_std().unwrap_or_default()),
+                None => None,
+            };
+            let result = client.wait_operation(&operation_path(&id), timeout).await?;
+            output::render_single(&result, OPERATION_COLUMNS, output_format)?;
+        }
+    }
+    Ok(())
+}
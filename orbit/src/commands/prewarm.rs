@@ -1,11 +1,11 @@
 use crate::client::NovaClient;
 use crate::error::Result;
 use crate::output;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressStyle;
 use std::time::Duration;
 
 pub async fn run(name: &str, client: &NovaClient) -> Result<()> {
-    let spinner = ProgressBar::new_spinner();
+    let spinner = output::progress_bar("prewarm", None);
     spinner.set_style(
         ProgressStyle::default_spinner()
             .template("{spinner:.cyan} Pre-warming {msg}...")
@@ -0,0 +1,165 @@
+use crate::client::NovaClient;
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use indicatif::ProgressStyle;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const HOTSPOT_COLUMNS: &[Column] = &[
+    Column::new("Function", "frame"),
+    Column::new("Self %", "self_pct"),
+    Column::new("Total %", "total_pct"),
+    Column::new("", "bar"),
+    Column::wide("Self Samples", "self"),
+    Column::wide("Total Samples", "total"),
+];
+
+/// Frames shown in the flamegraph summary.
+const TOP_FRAMES: usize = 20;
+const BAR_WIDTH: usize = 30;
+
+/// Summarizes collapsed stacks (`root;caller;leaf <count>` per line) into the
+/// frames with the most self time, including their inclusive share.
+fn hotspots(folded: &str) -> Vec<Value> {
+    let mut self_samples: HashMap<&str, u64> = HashMap::new();
+    let mut total_samples: HashMap<&str, u64> = HashMap::new();
+    let mut all = 0u64;
+    for line in folded.lines() {
+        let Some((stack, count)) = line.trim().rsplit_once(' ') else {
+            continue;
+        };
+        let Ok(count) = count.parse::<u64>() else {
+            continue;
+        };
+        all += count;
+        let frames: Vec<&str> = stack.split(';').collect();
+        if let Some(leaf) = frames.last() {
+            *self_samples.entry(leaf).or_default() += count;
+        }
+        // Count recursive frames once per stack for the inclusive total.
+        let mut seen = Vec::new();
+        for frame in frames {
+            if !seen.contains(&frame) {
+                seen.push(frame);
+                *total_samples.entry(frame).or_default() += count;
+            }
+        }
+    }
+    if all == 0 {
+        return Vec::new();
+    }
+
+    let mut frames: Vec<(&str, u64)> = self_samples.into_iter().collect();
+    frames.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let pct = |n: u64| n as f64 * 100.0 / all as f64;
+    frames
+        .into_iter()
+        .take(TOP_FRAMES)
+        .map(|(frame, samples)| {
+            let total = total_samples.get(frame).copied().unwrap_or(samples);
+            let width = (pct(samples) / 100.0 * BAR_WIDTH as f64).round() as usize;
+            json!({
+                "frame": frame,
+                "self": samples,
+                "total": total,
+                "self_pct": format!("{:.1}", pct(samples)),
+                "total_pct": format!("{:.1}", pct(total)),
+                "bar": "█".repeat(width.max(1)),
+            })
+        })
+        .collect()
+}
+
+/// Starts a profiling session, waits for it to finish (at most `max_wait`
+/// past the sampling duration), saves the pprof data, and optionally prints
+/// a flamegraph summary of the hottest frames.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_fn_profile(
+    name: &str,
+    duration: &str,
+    kind: &str,
+    out: Option<String>,
+    flamegraph: bool,
+    max_wait: &str,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let duration = crate::duration::parse(duration)?;
+    if duration.num_seconds() <= 0 {
+        return Err(OrbitError::Input("Duration must be positive".into()));
+    }
+    let wait = crate::duration::parse(max_wait)?
+        .to_std()
+        .map_err(|_| OrbitError::Input("--max-wait must be positive".into()))?;
+    let session = client
+        .post(
+            &format!("/functions/{name}/profile"),
+            &json!({ "duration_s": duration.num_seconds(), "type": kind }),
+        )
+        .await?;
+    let id = session
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| OrbitError::api(500, "Profile response missing 'id'"))?
+        .to_string();
+
+    let spinner = output::progress_bar("profile", None);
+    spinner.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.cyan} Profiling {msg} ({elapsed})...")
+            .unwrap(),
+    );
+    spinner.set_message(format!("'{name}' for {}s", duration.num_seconds()));
+    spinner.enable_steady_tick(Duration::from_millis(80));
+
+    let path = format!("/functions/{name}/profiles/{id}");
+    // Waits too long to represent never time out.
+    let deadline = Instant::now()
+        .checked_add(Duration::from_secs(duration.num_seconds().unsigned_abs()))
+        .and_then(|t| t.checked_add(wait));
+    loop {
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            spinner.finish_and_clear();
+            return Err(OrbitError::Timeout(format!(
+                "profile {id} of '{name}' not ready {max_wait} after sampling ended"
+            )));
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let profile = client.get(&path).await?;
+        match profile.get("status").and_then(|v| v.as_str()) {
+            Some("completed") => break,
+            Some("failed") => {
+                spinner.finish_and_clear();
+                let reason = profile
+                    .get("error")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown error");
+                return Err(OrbitError::api(500, format!("Profiling failed: {reason}")));
+            }
+            _ => {}
+        }
+    }
+    spinner.finish_and_clear();
+
+    let data = client.get_bytes(&format!("{path}/pprof")).await?;
+    let out = out.unwrap_or_else(|| format!("{name}-{kind}-{id}.pprof"));
+    std::fs::write(&out, &data)?;
+    output::print_success(&format!(
+        "Saved {kind} profile of '{name}' to {out} ({} bytes).",
+        data.len()
+    ));
+
+    if flamegraph {
+        let folded = client.get_bytes(&format!("{path}/folded")).await?;
+        let rows = hotspots(&String::from_utf8_lossy(&folded));
+        if rows.is_empty() {
+            output::print_warning("Profile contains no samples.");
+        } else {
+            output::render(&Value::Array(rows), HOTSPOT_COLUMNS, output_format)?;
+        }
+    } else {
+        output::print_status(&format!("Inspect it with: go tool pprof -http=:8080 {out}"));
+    }
+    Ok(())
+}
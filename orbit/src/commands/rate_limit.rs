@@ -26,7 +26,7 @@ pub async fn run(cmd: RateLimitCmd, client: &NovaClient, output_format: &str) ->
     match cmd {
         RateLimitCmd::Get => {
             let result = client.get("/gateway/rate-limit-template").await?;
-            output::render_single(&result, RATE_LIMIT_COLUMNS, output_format);
+            output::render_single(&result, RATE_LIMIT_COLUMNS, output_format)?;
         }
         RateLimitCmd::Set {
             requests_per_second,
@@ -39,10 +39,8 @@ pub async fn run(cmd: RateLimitCmd, client: &NovaClient, output_format: &str) ->
             if let Some(b) = burst_size {
                 body["burst_size"] = json!(b);
             }
-            let result = client
-                .put("/gateway/rate-limit-template", &body)
-                .await?;
-            output::render_single(&result, RATE_LIMIT_COLUMNS, output_format);
+            let result = client.put("/gateway/rate-limit-template", &body).await?;
+            output::render_single(&result, RATE_LIMIT_COLUMNS, output_format)?;
         }
     }
     Ok(())
@@ -101,7 +101,7 @@ pub async fn run(cmd: RbacCmd, client: &NovaClient, output_format: &str) -> Resu
         RbacCmd::Assignments { cmd } => run_assignments(cmd, client, output_format).await,
         RbacCmd::MyPermissions => {
             let result = client.get("/rbac/my-permissions").await?;
-            output::render(&result, PERM_COLUMNS, output_format);
+            output::render(&result, PERM_COLUMNS, output_format)?;
             Ok(())
         }
     }
@@ -115,15 +115,15 @@ async fn run_roles(cmd: RolesSubCmd, client: &NovaClient, output_format: &str) -
                 body["description"] = json!(d);
             }
             let result = client.post("/rbac/roles", &body).await?;
-            output::render_single(&result, ROLE_COLUMNS, output_format);
+            output::render_single(&result, ROLE_COLUMNS, output_format)?;
         }
         RolesSubCmd::List => {
-            let result = client.get("/rbac/roles").await?;
-            output::render(&result, ROLE_COLUMNS, output_format);
+            let result = client.get_paged("/rbac/roles").await?;
+            output::render(&result, ROLE_COLUMNS, output_format)?;
         }
         RolesSubCmd::Get { id } => {
             let result = client.get(&format!("/rbac/roles/{id}")).await?;
-            output::render_single(&result, ROLE_COLUMNS, output_format);
+            output::render_single(&result, ROLE_COLUMNS, output_format)?;
         }
         RolesSubCmd::Delete { id } => {
             client.delete(&format!("/rbac/roles/{id}")).await?;
@@ -133,11 +133,7 @@ async fn run_roles(cmd: RolesSubCmd, client: &NovaClient, output_format: &str) -
     Ok(())
 }
 
-async fn run_permissions(
-    cmd: PermsSubCmd,
-    client: &NovaClient,
-    output_format: &str,
-) -> Result<()> {
+async fn run_permissions(cmd: PermsSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         PermsSubCmd::Create {
             name,
@@ -152,11 +148,11 @@ async fn run_permissions(
                 body["action"] = json!(a);
             }
             let result = client.post("/rbac/permissions", &body).await?;
-            output::render_single(&result, PERM_COLUMNS, output_format);
+            output::render_single(&result, PERM_COLUMNS, output_format)?;
         }
         PermsSubCmd::List => {
-            let result = client.get("/rbac/permissions").await?;
-            output::render(&result, PERM_COLUMNS, output_format);
+            let result = client.get_paged("/rbac/permissions").await?;
+            output::render(&result, PERM_COLUMNS, output_format)?;
         }
     }
     Ok(())
@@ -179,11 +175,11 @@ async fn run_assignments(
                 "subject_id": subject_id,
             });
             let result = client.post("/rbac/assignments", &body).await?;
-            output::render_single(&result, ASSIGN_COLUMNS, output_format);
+            output::render_single(&result, ASSIGN_COLUMNS, output_format)?;
         }
         AssignSubCmd::List => {
-            let result = client.get("/rbac/assignments").await?;
-            output::render(&result, ASSIGN_COLUMNS, output_format);
+            let result = client.get_paged("/rbac/assignments").await?;
+            output::render(&result, ASSIGN_COLUMNS, output_format)?;
         }
         AssignSubCmd::Delete { id } => {
             client.delete(&format!("/rbac/assignments/{id}")).await?;
@@ -0,0 +1,370 @@
+use crate::client::NovaClient;
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use clap::Subcommand;
+use serde_json::{Value, json};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+/// Longest wait between attempts after failed polls or deploys.
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+const RELEASE_COLUMNS: &[Column] = &[
+    Column::new("ID", "id"),
+    Column::new("Function", "function_name"),
+    Column::new("Version", "version"),
+    Column::new("Commit", "git_commit"),
+    Column::new("Deployed By", "deployed_by"),
+    Column::new("Deployed At", "created_at"),
+    Column::new("Duration (ms)", "duration_ms"),
+    Column::wide("Notes", "notes"),
+];
+
+#[derive(Subcommand)]
+pub enum ReleaseCmd {
+    /// List deployments across the tenant, newest first
+    List {
+        /// Only show deployments of this function
+        #[arg(long)]
+        function: Option<String>,
+    },
+    /// Attach notes to a deployment
+    Annotate {
+        /// Release ID
+        id: String,
+        /// Notes text (replaces any existing notes)
+        #[arg(long)]
+        notes: String,
+    },
+    /// Watch a git ref or artifact URL and deploy new versions automatically
+    Watch {
+        /// Function to deploy to
+        function: String,
+        /// Git repository URL or path to watch
+        #[arg(
+            long,
+            required_unless_present = "artifact_url",
+            conflicts_with = "artifact_url"
+        )]
+        git: Option<String>,
+        /// Ref glob to follow in the repository; the newest match is deployed
+        /// (e.g. "refs/tags/v*", "main")
+        #[arg(long, default_value = "HEAD")]
+        pattern: String,
+        /// File in the repository holding the function code
+        #[arg(long, requires = "git")]
+        path: Option<String>,
+        /// Artifact URL polled for new content
+        #[arg(long)]
+        artifact_url: Option<String>,
+        /// Poll interval (e.g. 30s, 5m)
+        #[arg(long, default_value = "60s")]
+        interval: String,
+        /// Health-check invocations after each deploy (0 disables the gate)
+        #[arg(long, default_value_t = 3)]
+        health_checks: u32,
+        /// JSON payload for health-check invocations
+        #[arg(long)]
+        health_payload: Option<String>,
+        /// Exit after the first deploy instead of watching forever
+        #[arg(long)]
+        once: bool,
+    },
+}
+
+enum Source {
+    Git {
+        repo: String,
+        pattern: String,
+        path: String,
+    },
+    Artifact {
+        url: String,
+    },
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let out = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| OrbitError::Input(format!("Cannot run git: {e}")))?;
+    if !out.status.success() {
+        return Err(OrbitError::Input(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&out.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+impl Source {
+    fn describe(&self) -> String {
+        match self {
+            Source::Git { repo, pattern, .. } => format!("{repo} ({pattern})"),
+            Source::Artifact { url } => url.clone(),
+        }
+    }
+
+    /// Returns the newest revision available from the source.
+    async fn latest(&self, client: &NovaClient) -> Result<String> {
+        match self {
+            Source::Git { repo, pattern, .. } => {
+                let listing = git(&["ls-remote", "--sort=-v:refname", repo, pattern])?;
+                listing
+                    .lines()
+                    .filter_map(|line| line.split_once('\t'))
+                    .find(|(_, name)| !name.ends_with("^{}"))
+                    .map(|(sha, name)| format!("{name}@{sha}"))
+                    .ok_or_else(|| {
+                        OrbitError::Input(format!("No ref in {repo} matches '{pattern}'"))
+                    })
+            }
+            Source::Artifact { url } => {
+                let resp = client.send_external(reqwest::Method::HEAD, url).await?;
+                let header = |name| {
+                    resp.headers()
+                        .get(name)
+                        .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+                        .map(String::from)
+                };
+                match header(reqwest::header::ETAG).or(header(reqwest::header::LAST_MODIFIED)) {
+                    Some(rev) => Ok(rev),
+                    // Without validators, fall back to hashing the content.
+                    None => {
+                        let mut hasher = DefaultHasher::new();
+                        self.fetch(client, "").await?.hash(&mut hasher);
+                        Ok(format!("{:016x}", hasher.finish()))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Downloads the function code at `revision`.
+    async fn fetch(&self, client: &NovaClient, revision: &str) -> Result<String> {
+        match self {
+            Source::Git { repo, path, .. } => {
+                let (name, _) = revision.split_once('@').unwrap_or((revision, ""));
+                let branch = name
+                    .strip_prefix("refs/tags/")
+                    .or_else(|| name.strip_prefix("refs/heads/"));
+                let dir =
+                    std::env::temp_dir().join(format!("orbit-release-{}", std::process::id()));
+                let _ = std::fs::remove_dir_all(&dir);
+                let dir_str = dir.to_string_lossy().into_owned();
+                let mut args = vec!["clone", "--quiet", "--depth", "1"];
+                if let Some(b) = branch {
+                    args.extend(["--branch", b]);
+                }
+                args.extend([repo.as_str(), dir_str.as_str()]);
+                let cloned = git(&args);
+                let code = cloned.and_then(|_| {
+                    std::fs::read_to_string(PathBuf::from(&dir).join(path)).map_err(|e| {
+                        OrbitError::Input(format!("Cannot read {path} at {name}: {e}"))
+                    })
+                });
+                let _ = std::fs::remove_dir_all(&dir);
+                code
+            }
+            Source::Artifact { url } => {
+                let resp = client.send_external(reqwest::Method::GET, url).await?;
+                if !resp.status().is_success() {
+                    return Err(OrbitError::api(
+                        resp.status().as_u16(),
+                        format!("Cannot download {url}"),
+                    ));
+                }
+                Ok(resp.text().await?)
+            }
+        }
+    }
+}
+
+/// Invokes the function `checks` times; any error fails the gate.
+async fn health_check(
+    client: &NovaClient,
+    function: &str,
+    checks: u32,
+    payload: &Value,
+) -> std::result::Result<(), String> {
+    for i in 1..=checks {
+        match client
+            .post(&format!("/functions/{function}/invoke"), payload)
+            .await
+        {
+            Ok(r) => {
+                if let Some(err) = r.get("error").filter(|e| !e.is_null()) {
+                    return Err(format!("check {i}/{checks} returned error: {err}"));
+                }
+            }
+            Err(e) => return Err(format!("check {i}/{checks} failed: {e}")),
+        }
+    }
+    Ok(())
+}
+
+/// Deploys `code`, gates it on health checks, and rolls back to the
+/// previously active version on failure. Returns whether the deploy stuck.
+async fn deploy(
+    client: &NovaClient,
+    function: &str,
+    revision: &str,
+    code: String,
+    checks: u32,
+    payload: &Value,
+) -> Result<bool> {
+    let before = client.get(&format!("/functions/{function}")).await?;
+    let previous = before.get("version").and_then(|v| v.as_i64());
+
+    let result = client
+        .put(
+            &format!("/functions/{function}/code"),
+            &json!({ "code": code }),
+        )
+        .await?;
+    let version = result
+        .get("version")
+        .and_then(|v| v.as_i64())
+        .map(|v| format!("v{v}"))
+        .unwrap_or_else(|| "new version".into());
+    output::print_status(&format!(
+        "Deployed {revision} to '{function}' as {version}."
+    ));
+
+    match health_check(client, function, checks, payload).await {
+        Ok(()) => {
+            output::print_success(&format!("'{function}' healthy after {checks} checks."));
+            Ok(true)
+        }
+        Err(reason) => {
+            output::print_warning(&format!("Health check failed: {reason}"));
+            match previous {
+                Some(v) => {
+                    client
+                        .post(
+                            &format!("/functions/{function}/versions/{v}/activate"),
+                            &json!({}),
+                        )
+                        .await?;
+                    output::print_warning(&format!("Rolled '{function}' back to v{v}."));
+                }
+                None => output::print_warning("No previous version to roll back to."),
+            }
+            Ok(false)
+        }
+    }
+}
+
+/// The wait after another failure: twice `delay`, between `interval` and
+/// [`MAX_BACKOFF`].
+fn backoff(delay: Duration, interval: Duration) -> Duration {
+    (delay * 2).min(MAX_BACKOFF).max(interval)
+}
+
+pub async fn run(cmd: ReleaseCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    match cmd {
+        ReleaseCmd::List { function } => {
+            let path = match function {
+                Some(f) => format!("/releases?function={f}"),
+                None => "/releases".to_string(),
+            };
+            let result = client.get_paged(&path).await?;
+            output::render(&result, RELEASE_COLUMNS, output_format)?;
+        }
+        ReleaseCmd::Annotate { id, notes } => {
+            let result = client
+                .patch(&format!("/releases/{id}"), &json!({ "notes": notes }))
+                .await?;
+            output::render_single(&result, RELEASE_COLUMNS, output_format)?;
+        }
+        ReleaseCmd::Watch {
+            function,
+            git,
+            pattern,
+            path,
+            artifact_url,
+            interval,
+            health_checks,
+            health_payload,
+            once,
+        } => {
+            let source = match (git, artifact_url) {
+                (Some(repo), _) => Source::Git {
+                    repo,
+                    pattern,
+                    path: path
+                        .ok_or_else(|| OrbitError::Input("--path is required with --git".into()))?,
+                },
+                (_, Some(url)) => Source::Artifact { url },
+                _ => unreachable!("clap requires --git or --artifact-url"),
+            };
+            let interval = crate::duration::parse(&interval)?
+                .to_std()
+                .map_err(|_| OrbitError::Input("Interval must be positive".into()))?;
+            let payload = match health_payload {
+                Some(p) => serde_json::from_str(&p)
+                    .map_err(|e| OrbitError::Input(format!("Invalid JSON payload: {e}")))?,
+                None => json!({}),
+            };
+
+            let mut current = source.latest(client).await?;
+            output::print_status(&format!(
+                "Watching {} for '{function}' (current: {current}). Press Ctrl-C to stop.",
+                source.describe()
+            ));
+            // Doubles after each failure, up to MAX_BACKOFF, so a flaky
+            // network or server does not end the watch.
+            let mut delay = interval;
+            loop {
+                tokio::time::sleep(delay).await;
+                let latest = match source.latest(client).await {
+                    Ok(rev) => rev,
+                    Err(e) => {
+                        delay = backoff(delay, interval);
+                        output::print_warning(&format!(
+                            "Poll failed, retrying in {}s: {e}",
+                            delay.as_secs()
+                        ));
+                        continue;
+                    }
+                };
+                if latest == current {
+                    delay = interval;
+                    continue;
+                }
+                output::print_status(&format!("New revision {latest}."));
+                let deployed = match source.fetch(client, &latest).await {
+                    Ok(code) => {
+                        deploy(client, &function, &latest, code, health_checks, &payload).await
+                    }
+                    Err(e) => Err(e),
+                };
+                let healthy = match deployed {
+                    Ok(healthy) => healthy,
+                    Err(e) => {
+                        delay = backoff(delay, interval);
+                        output::print_warning(&format!(
+                            "Deploy of {latest} failed, retrying in {}s: {e}",
+                            delay.as_secs()
+                        ));
+                        continue;
+                    }
+                };
+                delay = interval;
+                current = latest;
+                if once {
+                    if !healthy {
+                        return Err(OrbitError::Input(format!(
+                            "Deploy of {current} failed health checks and was rolled back"
+                        )));
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,384 @@
+//! `orbit report generate`: compiles metrics, cost, errors, deploys and quota
+//! usage for a time window into a Markdown or HTML document.
+
+use crate::client::{DEFAULT_BATCH_CONCURRENCY, NovaClient};
+use crate::error::{OrbitError, Result};
+use crate::output;
+use clap::Subcommand;
+use serde_json::Value;
+
+#[derive(Subcommand)]
+pub enum ReportCmd {
+    /// Generate a report for a time window
+    Generate {
+        /// Report template: weekly-ops (everything) or cost (spend only)
+        #[arg(long, default_value = "weekly-ops", value_parser = ["weekly-ops", "cost"])]
+        template: String,
+        /// Time window (default: 7d for weekly-ops, 30d for cost)
+        #[arg(long)]
+        range: Option<String>,
+        /// Output file (default: stdout)
+        #[arg(long)]
+        out: Option<String>,
+        /// md or html (default: from the --out extension, else md)
+        #[arg(long, value_parser = ["md", "html"])]
+        format: Option<String>,
+    },
+}
+
+/// Rows listed in "top N" tables.
+const TOP_N: usize = 10;
+
+struct Section {
+    title: &'static str,
+    lines: Vec<String>,
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Section {
+    fn new(title: &'static str) -> Self {
+        Self {
+            title,
+            lines: Vec::new(),
+            headers: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn unavailable(title: &'static str, err: OrbitError) -> Self {
+        let mut section = Self::new(title);
+        section.lines.push(format!("Unavailable: {err}"));
+        section
+    }
+}
+
+fn num(v: &Value) -> f64 {
+    v.as_f64().unwrap_or(0.0)
+}
+
+fn text(v: &Value) -> String {
+    match v {
+        Value::Null => "-".into(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+async fn summary_section(client: &NovaClient, range: &str) -> Result<Section> {
+    let ts = client
+        .get(&format!("/metrics/timeseries?range={range}"))
+        .await?;
+    let buckets = ts.as_array().cloned().unwrap_or_default();
+    let invocations: f64 = buckets.iter().map(|b| num(&b["invocations"])).sum();
+    let errors: f64 = buckets.iter().map(|b| num(&b["errors"])).sum();
+    let weighted: f64 = buckets
+        .iter()
+        .map(|b| num(&b["avg_duration"]) * num(&b["invocations"]))
+        .sum();
+    let peak_vms = buckets
+        .iter()
+        .map(|b| num(&b["active_vms"]))
+        .fold(0.0, f64::max);
+
+    let mut section = Section::new("Summary");
+    section.headers = vec!["Metric", "Value"];
+    section.rows = vec![
+        vec!["Invocations".into(), format!("{invocations:.0}")],
+        vec!["Errors".into(), format!("{errors:.0}")],
+        vec![
+            "Error rate".into(),
+            if invocations > 0.0 {
+                format!("{:.2}%", errors * 100.0 / invocations)
+            } else {
+                "-".into()
+            },
+        ],
+        vec![
+            "Avg duration".into(),
+            if invocations > 0.0 {
+                format!("{:.1} ms", weighted / invocations)
+            } else {
+                "-".into()
+            },
+        ],
+        vec!["Peak active VMs".into(), format!("{peak_vms:.0}")],
+    ];
+    Ok(section)
+}
+
+async fn cost_section(client: &NovaClient, window_secs: i64) -> Result<Section> {
+    let cost = client
+        .get(&format!("/cost/summary?window={window_secs}"))
+        .await?;
+    let mut functions = cost["functions"].as_array().cloned().unwrap_or_default();
+    functions.sort_by(|a, b| num(&b["total_cost"]).total_cmp(&num(&a["total_cost"])));
+
+    let mut section = Section::new("Cost");
+    section
+        .lines
+        .push(format!("Total cost: {}", text(&cost["total_cost"])));
+    section.headers = vec!["Function", "Invocations", "Cold Starts", "Total Cost"];
+    section.rows = functions
+        .iter()
+        .take(TOP_N)
+        .map(|f| {
+            vec![
+                text(&f["function_name"]),
+                text(&f["invocations"]),
+                text(&f["cold_starts"]),
+                text(&f["total_cost"]),
+            ]
+        })
+        .collect();
+    Ok(section)
+}
+
+async fn errors_section(client: &NovaClient, range: &str) -> Result<Section> {
+    let functions = client.get_all("/functions").await?;
+    let names: Vec<String> = functions
+        .as_array()
+        .map(|fns| {
+            fns.iter()
+                .filter_map(|f| f["name"].as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    let results = client
+        .batch(names, DEFAULT_BATCH_CONCURRENCY, |c, name| async move {
+            c.get(&format!("/functions/{name}/metrics?range={range}"))
+                .await
+        })
+        .await;
+
+    let mut totals: Vec<(String, f64, f64)> = results
+        .into_iter()
+        .filter_map(|(name, result)| {
+            let buckets = result.ok()?["timeseries"].as_array()?.clone();
+            let inv = buckets.iter().map(|b| num(&b["invocations"])).sum();
+            let err = buckets.iter().map(|b| num(&b["errors"])).sum();
+            Some((name, inv, err))
+        })
+        .filter(|(_, _, err)| *err > 0.0)
+        .collect();
+    totals.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let mut section = Section::new("Top Errors");
+    if totals.is_empty() {
+        section.lines.push("No errors in this window.".into());
+        return Ok(section);
+    }
+    section.headers = vec!["Function", "Errors", "Invocations", "Error Rate"];
+    section.rows = totals
+        .iter()
+        .take(TOP_N)
+        .map(|(name, inv, err)| {
+            vec![
+                name.clone(),
+                format!("{err:.0}"),
+                format!("{inv:.0}"),
+                format!("{:.2}%", err * 100.0 / inv.max(1.0)),
+            ]
+        })
+        .collect();
+    Ok(section)
+}
+
+async fn deploys_section(
+    client: &NovaClient,
+    since: chrono::DateTime<chrono::Utc>,
+) -> Result<Section> {
+    let releases = client.get_all("/releases").await?;
+    let recent: Vec<&Value> = releases
+        .as_array()
+        .map(|r| {
+            r.iter()
+                .filter(|r| crate::duration::timestamp(r, "created_at").is_some_and(|t| t >= since))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut section = Section::new("Deploys");
+    section.lines.push(format!("{} deploy(s).", recent.len()));
+    if !recent.is_empty() {
+        section.headers = vec![
+            "Deployed At",
+            "Function",
+            "Version",
+            "Commit",
+            "Deployed By",
+        ];
+        section.rows = recent
+            .iter()
+            .map(|r| {
+                vec![
+                    text(&r["created_at"]),
+                    text(&r["function_name"]),
+                    text(&r["version"]),
+                    text(&r["git_commit"]),
+                    text(&r["deployed_by"]),
+                ]
+            })
+            .collect();
+    }
+    Ok(section)
+}
+
+async fn quotas_section(client: &NovaClient) -> Result<Section> {
+    let tenant = client.tenant().unwrap_or("default");
+    let usage = client.get(&format!("/tenants/{tenant}/usage")).await?;
+    let quotas = client.get(&format!("/tenants/{tenant}/quotas")).await?;
+
+    let mut section = Section::new("Quota Usage");
+    section.lines.push(format!("Tenant: {tenant}"));
+    section.headers = vec!["Dimension", "Limit", "Window", "Used", "Used %"];
+    section.rows = quotas
+        .as_array()
+        .map(|q| {
+            q.iter()
+                .map(|q| {
+                    let dim = text(&q["dimension"]);
+                    let used = &usage[dim.as_str()];
+                    let pct = match (used.as_f64(), q["limit"].as_f64()) {
+                        (Some(u), Some(l)) if l > 0.0 => format!("{:.0}%", u * 100.0 / l),
+                        _ => "-".into(),
+                    };
+                    vec![
+                        dim.clone(),
+                        text(&q["limit"]),
+                        text(&q["window"]),
+                        text(used),
+                        pct,
+                    ]
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(section)
+}
+
+fn render_markdown(title: &str, subtitle: &str, sections: &[Section]) -> String {
+    let cell = |s: &str| s.replace('|', "\\|").replace('\n', " ");
+    let mut out = format!("# {title}\n\n_{subtitle}_\n");
+    for section in sections {
+        out.push_str(&format!("\n## {}\n\n", section.title));
+        for line in &section.lines {
+            out.push_str(&format!("{line}\n\n"));
+        }
+        if !section.headers.is_empty() && !section.rows.is_empty() {
+            out.push_str(&format!("| {} |\n", section.headers.join(" | ")));
+            out.push_str(&format!("|{}\n", "---|".repeat(section.headers.len())));
+            for row in &section.rows {
+                let cells: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+        }
+    }
+    out
+}
+
+fn render_html(title: &str, subtitle: &str, sections: &[Section]) -> String {
+    let esc = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+         <style>body{{font-family:sans-serif;max-width:960px;margin:2em auto}}\
+         table{{border-collapse:collapse}}td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left}}</style>\n\
+         </head>\n<body>\n<h1>{0}</h1>\n<p><em>{1}</em></p>\n",
+        esc(title),
+        esc(subtitle)
+    );
+    for section in sections {
+        out.push_str(&format!("<h2>{}</h2>\n", esc(section.title)));
+        for line in &section.lines {
+            out.push_str(&format!("<p>{}</p>\n", esc(line)));
+        }
+        if !section.headers.is_empty() && !section.rows.is_empty() {
+            out.push_str("<table>\n<tr>");
+            for h in &section.headers {
+                out.push_str(&format!("<th>{}</th>", esc(h)));
+            }
+            out.push_str("</tr>\n");
+            for row in &section.rows {
+                out.push_str("<tr>");
+                for c in row {
+                    out.push_str(&format!("<td>{}</td>", esc(c)));
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str("</table>\n");
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+pub async fn run(cmd: ReportCmd, client: &NovaClient) -> Result<()> {
+    match cmd {
+        ReportCmd::Generate {
+            template,
+            range,
+            out,
+            format,
+        } => {
+            let range =
+                range.unwrap_or_else(|| if template == "cost" { "30d" } else { "7d" }.to_string());
+            let window = crate::duration::parse(&range)?;
+            if window.num_seconds() <= 0 {
+                return Err(OrbitError::Input("Range must be positive".into()));
+            }
+            let format = format.unwrap_or_else(|| {
+                match out.as_deref() {
+                    Some(p) if p.ends_with(".html") || p.ends_with(".htm") => "html",
+                    _ => "md",
+                }
+                .to_string()
+            });
+            let now = chrono::Utc::now();
+            let since = crate::duration::ago(window)?;
+
+            // A failing section is reported inline rather than failing the
+            // whole report.
+            let or_note = |title, result: Result<Section>| {
+                result.unwrap_or_else(|e| Section::unavailable(title, e))
+            };
+            let mut sections = vec![
+                or_note("Summary", summary_section(client, &range).await),
+                or_note("Cost", cost_section(client, window.num_seconds()).await),
+            ];
+            if template == "weekly-ops" {
+                sections.push(or_note("Top Errors", errors_section(client, &range).await));
+                sections.push(or_note("Deploys", deploys_section(client, since).await));
+                sections.push(or_note("Quota Usage", quotas_section(client).await));
+            }
+
+            let title = match template.as_str() {
+                "cost" => "Cost Report",
+                _ => "Weekly Ops Report",
+            };
+            let subtitle = format!(
+                "{} to {} ({range}) · {}",
+                since.format("%Y-%m-%d %H:%M UTC"),
+                now.format("%Y-%m-%d %H:%M UTC"),
+                client.base_url()
+            );
+            let doc = if format == "html" {
+                render_html(title, &subtitle, &sections)
+            } else {
+                render_markdown(title, &subtitle, &sections)
+            };
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, doc)?;
+                    output::print_success(&format!("Wrote {title} to {path}."));
+                }
+                None => print!("{doc}"),
+            }
+        }
+    }
+    Ok(())
+}
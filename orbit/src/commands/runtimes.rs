@@ -1,13 +1,23 @@
-use crate::client::NovaClient;
-use crate::error::Result;
+use crate::client::{DEFAULT_BATCH_CONCURRENCY, NovaClient};
+use crate::error::{OrbitError, Result};
 use crate::output::{self, Column};
 use clap::Subcommand;
-use serde_json::json;
+use indicatif::ProgressStyle;
+use reqwest::multipart::{Form, Part};
+use serde_json::{Value, json};
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+use tokio::task::JoinSet;
 
 #[derive(Subcommand)]
 pub enum RuntimesCmd {
     /// List available runtimes
     List,
+    /// Show runtime details, image layers, and the functions using it
+    Get {
+        /// Runtime ID
+        id: String,
+    },
     /// Create a custom runtime
     Create {
         /// Runtime name
@@ -24,29 +34,178 @@ pub enum RuntimesCmd {
     Upload {
         /// Runtime ID
         id: String,
-        /// Path to rootfs image
+        /// Path to rootfs image (.ext4)
         #[arg(long)]
         image: String,
+        /// Display name (defaults to the ID)
+        #[arg(long)]
+        name: Option<String>,
+        /// Command used to start functions, comma-separated (e.g. python3,-u)
+        #[arg(long, value_delimiter = ',', required = true)]
+        entrypoint: Vec<String>,
+        /// File extension of function code (e.g. .py)
+        #[arg(long)]
+        file_extension: String,
+        /// Runtime version label
+        #[arg(long)]
+        version: Option<String>,
+        /// Environment variables (KEY=VAL)
+        #[arg(long = "env", value_name = "KEY=VAL")]
+        env_vars: Vec<String>,
     },
     /// Delete a runtime
     Delete {
         /// Runtime ID
         id: String,
     },
+    /// Run the runtime smoke test on each architecture and report boot times
+    Verify {
+        /// Runtime ID
+        id: String,
+        /// Architectures to test, comma-separated
+        #[arg(long, value_delimiter = ',', default_value = "x86_64,aarch64")]
+        arch: Vec<String>,
+    },
+    /// Delete custom runtimes that no function uses
+    Prune {
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
 }
 
+const UPLOAD_CHUNK_SIZE: usize = 1 << 20;
+
 const RUNTIME_COLUMNS: &[Column] = &[
     Column::new("Name", "name"),
     Column::new("Rootfs", "rootfs"),
     Column::new("Command", "command"),
+    Column::new("Image Size", "image_size"),
+    Column::new("Functions", "functions_count"),
+    Column::wide("Layers", "layers"),
     Column::wide("Description", "description"),
 ];
 
+const RUNTIME_DETAIL_COLUMNS: &[Column] = &[
+    Column::new("ID", "id"),
+    Column::new("Name", "name"),
+    Column::new("Version", "version"),
+    Column::new("Status", "status"),
+    Column::new("Image", "image_name"),
+    Column::new("Image Size", "image_size"),
+    Column::new("Layers", "layers"),
+    Column::new("Functions", "functions_count"),
+    Column::new("Created", "created_at"),
+];
+
+const DEPENDENT_COLUMNS: &[Column] = &[
+    Column::new("Name", "name"),
+    Column::new("Version", "version"),
+    Column::new("Updated", "updated_at"),
+];
+
+const VERIFY_COLUMNS: &[Column] = &[
+    Column::new("Arch", "arch"),
+    Column::new("Result", "result"),
+    Column::new("Boot Time (ms)", "boot_time_ms"),
+    Column::new("Node", "node"),
+    Column::wide("Error", "error").max_width(40),
+];
+
+const PRUNE_COLUMNS: &[Column] = &[
+    Column::new("ID", "id"),
+    Column::new("Name", "name"),
+    Column::new("Image Size", "image_size"),
+    Column::new("Functions", "functions_count"),
+    Column::new("Action", "action"),
+];
+
+/// Streams the file at `path` as a multipart part, advancing `progress` as
+/// chunks are sent so multi-GB images never sit in memory.
+async fn file_part(path: &str, progress: output::Progress) -> Result<Part> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| OrbitError::Input(format!("Cannot read file {path}: {e}")))?;
+    let len = file.metadata().await?.len();
+    progress.set_length(len);
+    let chunks =
+        futures_util::stream::unfold((file, progress), |(mut file, progress)| async move {
+            let mut buf = vec![0u8; UPLOAD_CHUNK_SIZE];
+            match file.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    progress.inc(n as u64);
+                    Some((Ok(buf), (file, progress)))
+                }
+                Err(e) => Some((Err::<Vec<u8>, _>(e), (file, progress))),
+            }
+        });
+    let file_name = Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string());
+    Ok(Part::stream_with_length(reqwest::Body::wrap_stream(chunks), len).file_name(file_name))
+}
+
+/// Runtimes the server seeds itself; every other one was added through
+/// `runtimes create` or `runtimes upload`.
+const BUILTIN_RUNTIMES: &[&str] = &[
+    "python", "node", "go", "rust", "java", "kotlin", "scala", "c", "cpp", "ruby", "php", "deno",
+    "bun", "graalvm", "elixir", "swift", "zig", "lua", "perl", "r", "julia", "wasm",
+];
+
+/// Whether `runtime` was added by a user, going by `custom` when the server
+/// reports it.
+fn is_custom(runtime: &Value) -> bool {
+    runtime
+        .get("custom")
+        .and_then(|v| v.as_bool())
+        .unwrap_or_else(|| {
+            runtime["id"]
+                .as_str()
+                .is_some_and(|id| !BUILTIN_RUNTIMES.contains(&id))
+        })
+}
+
+fn functions_count(runtime: &Value) -> Option<u64> {
+    runtime.get("functions_count").and_then(|v| v.as_u64())
+}
+
+async fn find_runtime(client: &NovaClient, id: &str) -> Result<Value> {
+    let runtimes = client.get_all("/runtimes").await?;
+    runtimes
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .find(|r| r["id"].as_str() == Some(id) || r["name"].as_str() == Some(id))
+        })
+        .cloned()
+        .ok_or_else(|| OrbitError::api(404, format!("runtime '{id}' not found")))
+}
+
+async fn dependents(client: &NovaClient, id: &str) -> Result<Value> {
+    client.get_all(&format!("/functions?runtime={id}")).await
+}
+
 pub async fn run(cmd: RuntimesCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         RuntimesCmd::List => {
-            let result = client.get("/runtimes").await?;
-            output::render(&result, RUNTIME_COLUMNS, output_format);
+            let result = client.get_paged("/runtimes").await?;
+            output::render(&result, RUNTIME_COLUMNS, output_format)?;
+        }
+        RuntimesCmd::Get { id } => {
+            let mut runtime = find_runtime(client, &id).await?;
+            let functions = dependents(client, &id).await?;
+            if output::is_machine_readable(output_format) {
+                runtime["functions"] = functions;
+                output::render_single(&runtime, &[], output_format)?;
+            } else {
+                output::render_single(&runtime, RUNTIME_DETAIL_COLUMNS, output_format)?;
+                output::print_line("\nFunctions using this runtime:");
+                output::render(&functions, DEPENDENT_COLUMNS, output_format)?;
+            }
         }
         RuntimesCmd::Create {
             name,
@@ -61,29 +220,162 @@ pub async fn run(cmd: RuntimesCmd, client: &NovaClient, output_format: &str) ->
                 body["command"] = json!(c);
             }
             let result = client.post("/runtimes", &body).await?;
-            output::render_single(&result, RUNTIME_COLUMNS, output_format);
+            output::render_single(&result, RUNTIME_COLUMNS, output_format)?;
         }
-        RuntimesCmd::Upload { id, image } => {
-            let content = std::fs::read(&image).map_err(|e| {
-                crate::error::OrbitError::Input(format!("Cannot read file {image}: {e}"))
-            })?;
-            let form = reqwest::multipart::Form::new().part(
-                "file",
-                reqwest::multipart::Part::bytes(content).file_name(image.clone()),
+        RuntimesCmd::Upload {
+            id,
+            image,
+            name,
+            entrypoint,
+            file_extension,
+            version,
+            env_vars,
+        } => {
+            if !image.to_ascii_lowercase().ends_with(".ext4") {
+                return Err(OrbitError::Input(format!(
+                    "Runtime image {image} must be an .ext4 file"
+                )));
+            }
+            let mut env = serde_json::Map::new();
+            for kv in env_vars {
+                let (k, v) = kv.split_once('=').ok_or_else(|| {
+                    OrbitError::Input(format!("Invalid env var '{kv}': expected KEY=VAL"))
+                })?;
+                env.insert(k.to_string(), json!(v));
+            }
+            let mut metadata = json!({
+                "id": id,
+                "name": name.unwrap_or_else(|| id.clone()),
+                "entrypoint": entrypoint,
+                "file_extension": file_extension,
+                "env_vars": env,
+            });
+            if let Some(v) = version {
+                metadata["version"] = json!(v);
+            }
+
+            let progress = output::progress_bar("upload", Some(0));
+            progress.set_style(
+                ProgressStyle::default_bar()
+                    .template(
+                        "{spinner:.cyan} Uploading [{bar:30.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+                    )
+                    .unwrap(),
             );
-            // Use raw request for multipart
-            let _ = form; // Multipart upload needs direct reqwest usage
-            let body = json!({ "image_path": image });
-            let result = client.post(&format!("/runtimes/upload"), &body).await?;
+            let form = Form::new()
+                .text("metadata", metadata.to_string())
+                .part("file", file_part(&image, progress.clone()).await?);
+            let result = client.post_multipart("/runtimes/upload", form).await;
+            progress.finish_and_clear();
+            let result = result?;
             output::print_success(&format!("Runtime image uploaded for '{id}'."));
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&result, RUNTIME_COLUMNS, output_format);
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, RUNTIME_COLUMNS, output_format)?;
             }
         }
         RuntimesCmd::Delete { id } => {
             client.delete(&format!("/runtimes/{id}")).await?;
             output::print_success(&format!("Runtime '{id}' deleted."));
         }
+        RuntimesCmd::Verify { id, arch } => {
+            let mut tasks = JoinSet::new();
+            for a in arch.clone() {
+                let client = client.clone();
+                let path = format!("/runtimes/{id}/verify");
+                tasks.spawn(async move {
+                    let result = client.post(&path, &json!({ "arch": a })).await;
+                    (a, result)
+                });
+            }
+            let mut results = Vec::new();
+            while let Some(joined) = tasks.join_next().await {
+                if let Ok((a, result)) = joined {
+                    let row = match result {
+                        Ok(r) => {
+                            let ok = r.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+                            json!({
+                                "arch": a,
+                                "result": if ok { "passed" } else { "failed" },
+                                "boot_time_ms": r.get("boot_time_ms").cloned().unwrap_or(Value::Null),
+                                "node": r.get("node").cloned().unwrap_or(Value::Null),
+                                "error": r.get("error").cloned().unwrap_or(Value::Null),
+                            })
+                        }
+                        Err(e) => json!({ "arch": a, "result": "failed", "error": e.to_string() }),
+                    };
+                    results.push(row);
+                }
+            }
+            results.sort_by_key(|r| arch.iter().position(|a| r["arch"] == a.as_str()));
+            let failed = results.iter().filter(|r| r["result"] == "failed").count();
+            output::render(&Value::Array(results), VERIFY_COLUMNS, output_format)?;
+            if failed > 0 {
+                return Err(OrbitError::Input(format!(
+                    "Runtime '{id}' failed verification on {failed} of {} architectures",
+                    arch.len()
+                )));
+            }
+            output::print_success(&format!("Runtime '{id}' verified on {}.", arch.join(", ")));
+        }
+        RuntimesCmd::Prune { yes } => {
+            let runtimes = client.get_all("/runtimes").await?;
+            let custom: Vec<&Value> = runtimes
+                .as_array()
+                .map(|items| items.iter().filter(|r| is_custom(r)).collect())
+                .unwrap_or_default();
+            let mut rows = Vec::new();
+            let mut users = Vec::new();
+            let mut unused: Vec<&str> = Vec::new();
+            for r in custom {
+                let id = r["id"].as_str().unwrap_or_default();
+                // A count the listing leaves out is looked up, not taken as 0.
+                let names: Vec<String> = match functions_count(r) {
+                    Some(0) => Vec::new(),
+                    _ => dependents(client, id)
+                        .await?
+                        .as_array()
+                        .map(|fns| {
+                            fns.iter()
+                                .filter_map(|f| f["name"].as_str().map(String::from))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                };
+                let in_use = functions_count(r).is_some_and(|n| n > 0) || !names.is_empty();
+                let mut row = r.clone();
+                row["action"] = json!(if in_use { "keep (in use)" } else { "delete" });
+                rows.push(row);
+                if in_use {
+                    users.push(format!("{id} is used by: {}", names.join(", ")));
+                } else {
+                    unused.push(id);
+                }
+            }
+            output::render(&Value::Array(rows), PRUNE_COLUMNS, output_format)?;
+            for line in users {
+                eprintln!("{line}");
+            }
+
+            if unused.is_empty() {
+                output::print_success("No unused custom runtimes.");
+                return Ok(());
+            }
+            if !yes && !output::confirm(&format!("Delete {} unused runtime(s)?", unused.len())) {
+                output::print_status("Aborted.");
+                return Ok(());
+            }
+            let results = client
+                .batch(unused, DEFAULT_BATCH_CONCURRENCY, |c, id| async move {
+                    c.delete(&format!("/runtimes/{id}")).await
+                })
+                .await;
+            let failed = output::print_batch_summary(&results, "Deleted", "runtime(s)");
+            if failed > 0 {
+                return Err(OrbitError::Input(format!(
+                    "{failed} runtime(s) could not be deleted"
+                )));
+            }
+        }
     }
     Ok(())
 }
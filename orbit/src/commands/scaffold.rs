@@ -0,0 +1,190 @@
+//! `orbit functions init`: a starter project that `orbit functions deploy`
+//! can ship as is.
+
+use crate::commands::functions::{RuntimeFamily, detect_runtime_family, source_file_rel_path};
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use serde_json::json;
+use std::path::Path;
+
+const INIT_COLUMNS: &[Column] = &[
+    Column::new("Name", "name"),
+    Column::new("Runtime", "runtime"),
+    Column::new("Handler", "handler"),
+    Column::new("Directory", "directory"),
+    Column::new("Files", "files"),
+];
+
+const PYTHON_HANDLER: &str = r#"def handler(event, context):
+    name = event.get("name", "World")
+    return {"message": f"Hello, {name}!"}
+"#;
+
+const NODE_HANDLER: &str = r#"async function handler(event, context) {
+  const name = typeof event?.name === "string" && event.name ? event.name : "World"
+  return { message: `Hello, ${name}!` }
+}
+
+module.exports = { handler }
+"#;
+
+const GO_HANDLER: &str = r#"package main
+
+import (
+	"encoding/json"
+	"fmt"
+)
+
+type Event struct {
+	Name string `json:"name"`
+}
+
+func Handler(event json.RawMessage, ctx Context) (interface{}, error) {
+	var e Event
+	if err := json.Unmarshal(event, &e); err != nil {
+		return nil, err
+	}
+	if e.Name == "" {
+		e.Name = "World"
+	}
+	return map[string]string{"message": fmt.Sprintf("Hello, %s!", e.Name)}, nil
+}
+"#;
+
+const RUST_HANDLER: &str = r#"use serde_json::{Value, json};
+
+pub fn handler(event: Value, _ctx: crate::context::Context) -> Result<Value, String> {
+    let name = event["name"].as_str().unwrap_or("World");
+    Ok(json!({ "message": format!("Hello, {name}!") }))
+}
+"#;
+
+struct Template {
+    handler: &'static str,
+    source: &'static str,
+    /// Dependency files written next to the source
+    files: Vec<(&'static str, String)>,
+}
+
+/// The starter project for a runtime family, if there is one.
+fn template(name: &str, family: RuntimeFamily) -> Option<Template> {
+    let (handler, source, files) = match family {
+        RuntimeFamily::Python => (
+            "main.handler",
+            PYTHON_HANDLER,
+            vec![("requirements.txt", String::new())],
+        ),
+        RuntimeFamily::Node => (
+            "index.handler",
+            NODE_HANDLER,
+            vec![(
+                "package.json",
+                format!("{{\n  \"name\": \"{name}\",\n  \"private\": true\n}}\n"),
+            )],
+        ),
+        RuntimeFamily::Go => (
+            "Handler",
+            GO_HANDLER,
+            vec![("go.mod", format!("module {name}\n\ngo 1.22\n"))],
+        ),
+        RuntimeFamily::Rust => (
+            "handler",
+            RUST_HANDLER,
+            vec![(
+                "Cargo.toml",
+                format!(
+                    "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+                     [dependencies]\nserde = {{ version = \"1\", features = [\"derive\"] }}\n\
+                     serde_json = \"1\"\n"
+                ),
+            )],
+        ),
+        RuntimeFamily::Java | RuntimeFamily::Unknown => return None,
+    };
+    Some(Template {
+        handler,
+        source,
+        files,
+    })
+}
+
+fn ignore_lines(family: RuntimeFamily) -> &'static str {
+    match family {
+        RuntimeFamily::Python => "__pycache__/\n*.pyc\n",
+        RuntimeFamily::Node => "node_modules/\n",
+        RuntimeFamily::Rust => "target/\n",
+        _ => "",
+    }
+}
+
+pub fn run(
+    name: String,
+    runtime: String,
+    dir: Option<String>,
+    force: bool,
+    output_format: &str,
+) -> Result<()> {
+    let family = detect_runtime_family(&runtime);
+    let Template {
+        handler,
+        source,
+        mut files,
+    } = template(&name, family).ok_or_else(|| {
+        OrbitError::Input(format!(
+            "No starter template for runtime '{runtime}'. Use python, node, go or rust."
+        ))
+    })?;
+    let dir = dir.unwrap_or_else(|| name.clone());
+    let root = Path::new(&dir);
+    let occupied = root
+        .read_dir()
+        .is_ok_and(|mut entries| entries.next().is_some());
+    if occupied && !force {
+        return Err(OrbitError::Input(format!(
+            "{dir} is not empty. Use --force to write the starter files anyway."
+        )));
+    }
+
+    let entry = source_file_rel_path(&runtime, handler);
+    let manifest = format!(
+        "name = \"{name}\"\nruntime = \"{runtime}\"\nhandler = \"{handler}\"\ncode = \"{entry}\"\n\
+         memory_mb = 128\ntimeout_s = 30\n\n[env]\n"
+    );
+    files.push(("function.toml", manifest));
+    files.push(("payload.json", "{\n  \"name\": \"World\"\n}\n".into()));
+    files.push((
+        crate::package::IGNORE_FILE,
+        format!(
+            "# Left out of `orbit functions deploy` and --code-dir uploads\npayload.json\n{}",
+            ignore_lines(family)
+        ),
+    ));
+
+    let write = |relative: &str, content: &str| -> Result<()> {
+        let path = root.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content)
+            .map_err(|e| OrbitError::Input(format!("Cannot write {}: {e}", path.display())))
+    };
+    write(&entry, source)?;
+    for (relative, content) in &files {
+        write(relative, content)?;
+    }
+
+    let mut written: Vec<&str> = vec![&entry];
+    written.extend(files.iter().map(|(f, _)| *f));
+    let summary = json!({
+        "name": name,
+        "runtime": runtime,
+        "handler": handler,
+        "directory": dir,
+        "files": written,
+    });
+    output::render_single(&summary, INIT_COLUMNS, output_format)?;
+    if !output::is_machine_readable(output_format) {
+        output::print_status(&format!("\nNext: orbit functions deploy {dir}"));
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use crate::client::NovaClient;
-use crate::commands::functions::ScalingSubCmd;
-use crate::error::Result;
+use crate::commands::functions::{ScalingScheduleSubCmd, ScalingSubCmd};
+use crate::error::{OrbitError, Result};
 use crate::output::{self, Column};
 use serde_json::json;
 
@@ -13,11 +13,21 @@ const SCALING_COLUMNS: &[Column] = &[
     Column::new("Cooldown Down (s)", "cooldown_scale_down_s"),
 ];
 
+const SCALING_SCHEDULE_COLUMNS: &[Column] = &[
+    Column::new("ID", "id"),
+    Column::new("Cron", "cron_expression"),
+    Column::new("Min Replicas", "min_replicas"),
+    Column::new("Max Replicas", "max_replicas"),
+    Column::new("Duration (s)", "duration_s"),
+    Column::wide("Timezone", "timezone"),
+    Column::new("Created", "created_at"),
+];
+
 pub async fn run(cmd: ScalingSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         ScalingSubCmd::Get { name } => {
             let result = client.get(&format!("/functions/{name}/scaling")).await?;
-            output::render_single(&result, SCALING_COLUMNS, output_format);
+            output::render_single(&result, SCALING_COLUMNS, output_format)?;
         }
         ScalingSubCmd::Set {
             name,
@@ -46,12 +56,61 @@ pub async fn run(cmd: ScalingSubCmd, client: &NovaClient, output_format: &str) -
             let result = client
                 .put(&format!("/functions/{name}/scaling"), &body)
                 .await?;
-            output::render_single(&result, SCALING_COLUMNS, output_format);
+            output::render_single(&result, SCALING_COLUMNS, output_format)?;
         }
         ScalingSubCmd::Delete { name } => {
             client.delete(&format!("/functions/{name}/scaling")).await?;
             output::print_success(&format!("Scaling policy deleted for '{name}'."));
         }
+        ScalingSubCmd::Schedule { cmd } => run_schedule(cmd, client, output_format).await?,
+    }
+    Ok(())
+}
+
+async fn run_schedule(
+    cmd: ScalingScheduleSubCmd,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    match cmd {
+        ScalingScheduleSubCmd::Add {
+            name,
+            cron,
+            min,
+            max,
+            duration,
+            timezone,
+        } => {
+            if max.is_some_and(|max| max < min) {
+                return Err(OrbitError::Input("--max must be at least --min".into()));
+            }
+            let mut body = json!({ "cron_expression": cron, "min_replicas": min });
+            if let Some(v) = max {
+                body["max_replicas"] = json!(v);
+            }
+            if let Some(d) = duration {
+                body["duration_s"] = json!(crate::duration::parse(&d)?.num_seconds());
+            }
+            if let Some(tz) = timezone {
+                body["timezone"] = json!(tz);
+            }
+            let result = client
+                .post(&format!("/functions/{name}/scaling/schedules"), &body)
+                .await?;
+            output::render_single(&result, SCALING_SCHEDULE_COLUMNS, output_format)?;
+        }
+        ScalingScheduleSubCmd::List { name } => {
+            let result = client
+                .get(&format!("/functions/{name}/scaling/schedules"))
+                .await?;
+            output::render(&result, SCALING_SCHEDULE_COLUMNS, output_format)?;
+        }
+        ScalingScheduleSubCmd::Remove { name, id } => {
+            client
+                .delete(&format!("/functions/{name}/scaling/schedules/{id}"))
+                .await?;
+            output::print_success(&format!("Replica profile '{id}' removed."));
+        }
     }
     Ok(())
 }
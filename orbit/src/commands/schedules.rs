@@ -25,11 +25,11 @@ pub async fn run(cmd: SchedulesSubCmd, client: &NovaClient, output_format: &str)
             let result = client
                 .post(&format!("/functions/{name}/schedules"), &body)
                 .await?;
-            output::render_single(&result, SCHEDULE_COLUMNS, output_format);
+            output::render_single(&result, SCHEDULE_COLUMNS, output_format)?;
         }
         SchedulesSubCmd::List { name } => {
             let result = client.get(&format!("/functions/{name}/schedules")).await?;
-            output::render(&result, SCHEDULE_COLUMNS, output_format);
+            output::render(&result, SCHEDULE_COLUMNS, output_format)?;
         }
         SchedulesSubCmd::Delete { name, id } => {
             client
@@ -45,7 +45,7 @@ pub async fn run(cmd: SchedulesSubCmd, client: &NovaClient, output_format: &str)
             let result = client
                 .patch(&format!("/functions/{name}/schedules/{id}"), &body)
                 .await?;
-            output::render_single(&result, SCHEDULE_COLUMNS, output_format);
+            output::render_single(&result, SCHEDULE_COLUMNS, output_format)?;
         }
     }
     Ok(())
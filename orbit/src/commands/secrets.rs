@@ -29,11 +29,11 @@ pub async fn run(cmd: SecretsCmd, client: &NovaClient, output_format: &str) -> R
         SecretsCmd::Create { name, value } => {
             let body = json!({ "name": name, "value": value });
             let result = client.post("/secrets", &body).await?;
-            output::render_single(&result, SECRET_COLUMNS, output_format);
+            output::render_single(&result, SECRET_COLUMNS, output_format)?;
         }
         SecretsCmd::List => {
-            let result = client.get("/secrets").await?;
-            output::render(&result, SECRET_COLUMNS, output_format);
+            let result = client.get_paged("/secrets").await?;
+            output::render(&result, SECRET_COLUMNS, output_format)?;
         }
         SecretsCmd::Delete { name } => {
             client.delete(&format!("/secrets/{name}")).await?;
@@ -0,0 +1,99 @@
+use crate::client::NovaClient;
+use crate::error::Result;
+use crate::output::{self, Column};
+use clap::Subcommand;
+use serde_json::json;
+
+#[derive(Subcommand)]
+pub enum ServiceAccountsCmd {
+    /// Create a service account
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        description: Option<String>,
+        /// Permission scopes (repeatable)
+        #[arg(long = "scope")]
+        scopes: Vec<String>,
+        /// Rotate the credential automatically after this period (e.g. 30d)
+        #[arg(long)]
+        rotate_every: Option<String>,
+    },
+    /// List service accounts
+    List,
+    /// Issue a new credential and revoke the current one
+    Rotate {
+        id: String,
+        /// Keep the previous credential valid for this long (e.g. 1h)
+        #[arg(long)]
+        grace_period: Option<String>,
+    },
+    /// Delete a service account
+    Delete { id: String },
+}
+
+const SA_COLUMNS: &[Column] = &[
+    Column::new("ID", "id"),
+    Column::new("Name", "name"),
+    Column::new("Scopes", "scopes"),
+    Column::new("Last Rotated", "last_rotated_at"),
+    Column::new("Next Rotation", "next_rotation_at"),
+    Column::wide("Rotation Period", "rotation_period"),
+    Column::wide("Description", "description"),
+    Column::wide("Created", "created_at"),
+];
+
+const SA_CREDENTIAL_COLUMNS: &[Column] = &[
+    Column::new("ID", "id"),
+    Column::new("Name", "name"),
+    Column::new("Key", "key"),
+    Column::new("Scopes", "scopes"),
+    Column::new("Next Rotation", "next_rotation_at"),
+];
+
+pub async fn run(cmd: ServiceAccountsCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    match cmd {
+        ServiceAccountsCmd::Create {
+            name,
+            description,
+            scopes,
+            rotate_every,
+        } => {
+            let mut body = json!({ "name": name });
+            if let Some(d) = description {
+                body["description"] = json!(d);
+            }
+            if !scopes.is_empty() {
+                body["scopes"] = json!(scopes);
+            }
+            if let Some(r) = rotate_every {
+                let period = crate::duration::parse(&r)?;
+                body["rotation_period_s"] = json!(period.num_seconds());
+            }
+            let result = client.post("/service-accounts", &body).await?;
+            output::render_single(&result, SA_CREDENTIAL_COLUMNS, output_format)?;
+            output::print_success("Store the key now; it cannot be retrieved again.");
+        }
+        ServiceAccountsCmd::List => {
+            let result = client.get_paged("/service-accounts").await?;
+            output::render(&result, SA_COLUMNS, output_format)?;
+        }
+        ServiceAccountsCmd::Rotate { id, grace_period } => {
+            let mut body = json!({});
+            if let Some(g) = grace_period {
+                let grace = crate::duration::parse(&g)?;
+                body["grace_period_s"] = json!(grace.num_seconds());
+            }
+            let result = client
+                .post(&format!("/service-accounts/{id}/rotate"), &body)
+                .await?;
+            output::render_single(&result, SA_CREDENTIAL_COLUMNS, output_format)?;
+            output::print_success(&format!("Credential for '{id}' rotated."));
+        }
+        ServiceAccountsCmd::Delete { id } => {
+            client.delete(&format!("/service-accounts/{id}")).await?;
+            output::print_success(&format!("Service account '{id}' deleted."));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+//! Interactive first-run setup (`orbit init` / `--setup`).
+
+use crate::client::{ClientOptions, NovaClient};
+use crate::config::OrbitConfig;
+use crate::error::{OrbitError, Result};
+use crate::output;
+use std::io::{BufRead, IsTerminal, Write};
+use std::time::Duration;
+
+const OUTPUT_FORMATS: &[&str] = &["table", "wide", "json", "yaml"];
+
+/// Asks for a value on stderr, returning `default` on an empty answer.
+fn prompt(label: &str, default: Option<&str>) -> Result<String> {
+    match default {
+        Some(d) => eprint!("{label} [{d}]: "),
+        None => eprint!("{label}: "),
+    }
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(if answer.is_empty() {
+        default.unwrap_or_default().to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+/// Confirms the settings reach the server (and are accepted, when a key
+/// was given) before they are saved.
+async fn verify(
+    server: &str,
+    api_key: Option<String>,
+    tenant: &str,
+    namespace: &str,
+) -> Result<()> {
+    let options = ClientOptions {
+        timeout: Some(Duration::from_secs(10)),
+        ..Default::default()
+    };
+    let has_key = api_key.is_some();
+    let client = NovaClient::new(
+        server.to_string(),
+        api_key,
+        Some(tenant.to_string()),
+        Some(namespace.to_string()),
+        options,
+    )?;
+    client.get("/health/live").await?;
+    if has_key {
+        match client.get("/auth/whoami").await {
+            Err(OrbitError::Api { status: 404, .. }) => {}
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Prompts for the basic settings of `context` (or the top level), checks
+/// them against the server and saves them.
+pub async fn run(context: Option<&str>) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        return Err(OrbitError::Input(
+            "The setup wizard needs an interactive terminal; use 'orbit config set' instead".into(),
+        ));
+    }
+    let mut config = OrbitConfig::load();
+    let path = OrbitConfig::config_path();
+    let target = context.map_or("the default context".into(), |c| format!("context '{c}'"));
+    if config.section_mut(context).server.is_some()
+        && !output::confirm(&format!(
+            "{} already configures {target}. Overwrite it?",
+            path.display()
+        ))
+    {
+        output::print_status("Aborted.");
+        return Ok(());
+    }
+    eprintln!("Setting up {target}. Press Enter to accept the default in brackets.");
+
+    let section = config.section_mut(context);
+    let server = prompt(
+        "Server URL",
+        Some(section.server.as_deref().unwrap_or("http://localhost:9000")),
+    )?;
+    let api_key = rpassword::prompt_password("API key (input hidden, empty for none): ")?;
+    let api_key = Some(api_key.trim().to_string()).filter(|k| !k.is_empty());
+    let tenant = prompt(
+        "Tenant",
+        Some(section.tenant.as_deref().unwrap_or("default")),
+    )?;
+    let namespace = prompt(
+        "Namespace",
+        Some(section.namespace.as_deref().unwrap_or("default")),
+    )?;
+    let output_format = loop {
+        let answer = prompt(
+            "Default output format (table, wide, json, yaml)",
+            Some(section.output.as_deref().unwrap_or("table")),
+        )?;
+        if OUTPUT_FORMATS.contains(&answer.as_str()) {
+            break answer;
+        }
+        output::print_error(&format!("Unknown output format '{answer}'"));
+    };
+    let keyring = api_key.is_some() && output::confirm("Store the API key in the OS keyring?");
+
+    eprintln!("Checking {server}...");
+    match verify(&server, api_key.clone(), &tenant, &namespace).await {
+        Ok(()) => output::print_success("Connected."),
+        Err(e) => {
+            output::print_error(&format!("Connection check failed: {e}"));
+            if !output::confirm("Save these settings anyway?") {
+                return Err(OrbitError::Input("Setup aborted; nothing saved".into()));
+            }
+        }
+    }
+
+    if keyring && let Some(key) = &api_key {
+        crate::config::store_keyring_api_key(context, key)?;
+    }
+    let section = config.section_mut(context);
+    section.server = Some(server);
+    section.api_key_keyring = keyring;
+    section.api_key = api_key;
+    section.tenant = Some(tenant);
+    section.namespace = Some(namespace);
+    section.output = Some(output_format);
+    if let Some(name) = context
+        && config.current_context.is_none()
+    {
+        config.current_context = Some(name.to_string());
+    }
+    config.save()?;
+    output::print_success(&format!("Saved {target} to {}.", path.display()));
+    Ok(())
+}
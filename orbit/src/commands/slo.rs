@@ -36,7 +36,7 @@ pub async fn run(cmd: SloCmd, client: &NovaClient, output_format: &str) -> Resul
     match cmd {
         SloCmd::Get { name } => {
             let result = client.get(&format!("/functions/{name}/slo")).await?;
-            output::render_single(&result, SLO_COLUMNS, output_format);
+            output::render_single(&result, SLO_COLUMNS, output_format)?;
         }
         SloCmd::Set {
             name,
@@ -54,10 +54,8 @@ pub async fn run(cmd: SloCmd, client: &NovaClient, output_format: &str) -> Resul
             if let Some(v) = evaluation_window {
                 body["evaluation_window"] = json!(v);
             }
-            let result = client
-                .put(&format!("/functions/{name}/slo"), &body)
-                .await?;
-            output::render_single(&result, SLO_COLUMNS, output_format);
+            let result = client.put(&format!("/functions/{name}/slo"), &body).await?;
+            output::render_single(&result, SLO_COLUMNS, output_format)?;
         }
         SloCmd::Delete { name } => {
             client.delete(&format!("/functions/{name}/slo")).await?;
@@ -1,9 +1,10 @@
-use crate::client::NovaClient;
+use crate::client::{DEFAULT_BATCH_CONCURRENCY, NovaClient};
 use crate::commands::functions::SnapshotSubCmd;
-use crate::error::Result;
+use crate::error::{OrbitError, Result};
 use crate::output::{self, Column};
 use clap::Subcommand;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressStyle;
+use serde_json::{Value, json};
 use std::time::Duration;
 
 const SNAPSHOT_COLUMNS: &[Column] = &[
@@ -14,22 +15,340 @@ const SNAPSHOT_COLUMNS: &[Column] = &[
     Column::new("Created", "created_at"),
 ];
 
+const SNAPSHOT_LIST_COLUMNS: &[Column] = &[
+    Column::new("Function", "function_name"),
+    Column::new("Snapshot Size", "snap_size"),
+    Column::new("Memory Size", "mem_size"),
+    Column::new("Total Size", "total_size"),
+    Column::new("Age", "age"),
+    Column::new("Code Hash", "code_hash"),
+    Column::new("Stale", "stale"),
+    Column::wide("Current Hash", "current_code_hash"),
+    Column::wide("Created", "created_at"),
+];
+
 #[derive(Subcommand)]
 pub enum SnapshotsCmd {
     /// List all snapshots
-    List,
+    List {
+        /// Only show snapshots taken of code that has since changed
+        #[arg(long)]
+        stale: bool,
+    },
+    /// Delete stale or old snapshots
+    Prune {
+        /// Delete snapshots whose function code has since changed
+        #[arg(long)]
+        stale: bool,
+        /// Delete snapshots older than this (e.g. 30d)
+        #[arg(long)]
+        older_than: Option<String>,
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
 }
 
-pub async fn run_list(client: &NovaClient, output_format: &str) -> Result<()> {
-    let result = client.get("/snapshots").await?;
-    output::render(&result, SNAPSHOT_COLUMNS, output_format);
+/// Fetches all snapshots annotated with `age`, the function's current code
+/// hash, and `stale`. A stale snapshot no longer matches the deployed code,
+/// so invocations fall back to a cold boot.
+async fn annotated_snapshots(client: &NovaClient) -> Result<Vec<Value>> {
+    let snapshots = client.get_all("/snapshots").await?;
+    let functions = client.get_all("/functions").await?;
+    let current_hash = |name: &str| {
+        functions
+            .as_array()
+            .and_then(|fns| fns.iter().find(|f| f["name"].as_str() == Some(name)))
+            .and_then(|f| f["code_hash"].as_str())
+    };
+    let now = chrono::Utc::now();
+
+    let mut rows = snapshots.as_array().cloned().unwrap_or_default();
+    for row in &mut rows {
+        if let Some(created) = crate::duration::timestamp(row, "created_at") {
+            row["age"] = json!(crate::duration::format_age(now - created));
+        }
+        let name = row["function_name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let current = current_hash(&name).map(String::from);
+        let stale = match (row["code_hash"].as_str(), &current) {
+            (Some(taken), Some(current)) => taken != current,
+            _ => false,
+        };
+        row["current_code_hash"] = json!(current);
+        row["stale"] = json!(stale);
+    }
+    Ok(rows)
+}
+
+pub async fn run(
+    cmd: Option<SnapshotsCmd>,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    match cmd.unwrap_or(SnapshotsCmd::List { stale: false }) {
+        SnapshotsCmd::List { stale } => {
+            let mut rows = annotated_snapshots(client).await?;
+            if stale {
+                rows.retain(|r| r["stale"] == true);
+            }
+            output::render(&Value::Array(rows), SNAPSHOT_LIST_COLUMNS, output_format)?;
+        }
+        SnapshotsCmd::Prune {
+            stale,
+            older_than,
+            yes,
+        } => {
+            if !stale && older_than.is_none() {
+                return Err(OrbitError::Input(
+                    "Specify --stale and/or --older-than to choose snapshots to prune".into(),
+                ));
+            }
+            let cutoff = match &older_than {
+                Some(age) => Some(crate::duration::ago(crate::duration::parse(age)?)?),
+                None => None,
+            };
+            let mut rows = annotated_snapshots(client).await?;
+            rows.retain(|r| {
+                let is_stale = stale && r["stale"] == true;
+                let is_old = cutoff.is_some_and(|cutoff| {
+                    crate::duration::timestamp(r, "created_at").is_some_and(|t| t < cutoff)
+                });
+                is_stale || is_old
+            });
+            if rows.is_empty() {
+                output::print_success("No snapshots to prune.");
+                return Ok(());
+            }
+            output::render(
+                &Value::Array(rows.clone()),
+                SNAPSHOT_LIST_COLUMNS,
+                output_format,
+            )?;
+            if !yes && !output::confirm(&format!("Delete {} snapshot(s)?", rows.len())) {
+                output::print_status("Aborted.");
+                return Ok(());
+            }
+            let names = rows
+                .iter()
+                .map(|row| row["function_name"].as_str().unwrap_or_default());
+            let results = client
+                .batch(names, DEFAULT_BATCH_CONCURRENCY, |c, name| async move {
+                    c.delete(&format!("/functions/{name}/snapshot")).await
+                })
+                .await;
+            let failed = output::print_batch_summary(&results, "Deleted", "snapshot(s)");
+            if failed > 0 {
+                return Err(OrbitError::Input(format!(
+                    "{failed} snapshot(s) could not be deleted"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+const BENCH_COLUMNS: &[Column] = &[
+    Column::new("Boot", "mode"),
+    Column::new("Runs", "runs"),
+    Column::new("Errors", "errors"),
+    Column::new("Mismatched", "mismatched"),
+    Column::new("Min (ms)", "min_ms"),
+    Column::new("P50 (ms)", "p50_ms"),
+    Column::new("P90 (ms)", "p90_ms"),
+    Column::new("P99 (ms)", "p99_ms"),
+    Column::new("Max (ms)", "max_ms"),
+    Column::new("Mean (ms)", "mean_ms"),
+];
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Latencies of one boot mode. Invocations that booted another way than
+/// the mode forces (by their `cold_start`) are counted but not sampled.
+#[derive(Default)]
+struct BenchRuns {
+    samples: Vec<f64>,
+    errors: u32,
+    mismatched: u32,
+}
+
+impl BenchRuns {
+    fn summary(mut self, mode: &str) -> Value {
+        self.samples.sort_by(f64::total_cmp);
+        let samples = &self.samples;
+        let mean = if samples.is_empty() {
+            0.0
+        } else {
+            samples.iter().sum::<f64>() / samples.len() as f64
+        };
+        let round = |v: f64| (v * 10.0).round() / 10.0;
+        json!({
+            "mode": mode,
+            "runs": samples.len() as u32 + self.errors + self.mismatched,
+            "errors": self.errors,
+            "mismatched": self.mismatched,
+            "min_ms": round(samples.first().copied().unwrap_or_default()),
+            "p50_ms": round(percentile(samples, 50.0)),
+            "p90_ms": round(percentile(samples, 90.0)),
+            "p99_ms": round(percentile(samples, 99.0)),
+            "max_ms": round(samples.last().copied().unwrap_or_default()),
+            "mean_ms": round(mean),
+        })
+    }
+}
+
+/// Stops every instance of the function, so the next invocation boots.
+async fn evict(client: &NovaClient, function_id: &str) -> Result<()> {
+    client
+        .post(
+            "/internal/pool/evict",
+            &json!({ "function_id": function_id }),
+        )
+        .await?;
+    Ok(())
+}
+
+async fn delete_snapshot(client: &NovaClient, name: &str) -> Result<()> {
+    match client.delete(&format!("/functions/{name}/snapshot")).await {
+        Ok(_) | Err(OrbitError::Api { status: 404, .. }) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Invokes once, recording the latency as observed by the client if the
+/// invocation succeeded and `cold_start` is `cold`.
+async fn sample(client: &NovaClient, name: &str, body: &Value, cold: bool, runs: &mut BenchRuns) {
+    let start = std::time::Instant::now();
+    let result = client
+        .post(&format!("/functions/{name}/invoke"), body)
+        .await;
+    let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+    match result {
+        Ok(r) if r.get("error").is_some_and(|e| !e.is_null()) => runs.errors += 1,
+        Ok(r) if r["cold_start"].as_bool() != Some(cold) => runs.mismatched += 1,
+        Ok(_) => runs.samples.push(elapsed),
+        Err(_) => runs.errors += 1,
+    }
+}
+
+/// Runs `iterations` rounds of a cold boot (snapshot deleted, instances
+/// evicted), a snapshot restore (snapshot created, instances evicted) and a
+/// warm invocation of the instance just restored.
+async fn bench_rounds(
+    name: &str,
+    function_id: &str,
+    iterations: u32,
+    body: &Value,
+    client: &NovaClient,
+    runs: &mut [BenchRuns; 3],
+) -> Result<()> {
+    let progress = output::progress_bar("benchmark", Some(u64::from(iterations) * 3));
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.cyan} Benchmarking [{bar:30.cyan/blue}] {pos}/{len}")
+            .unwrap(),
+    );
+    let [warm, snapshot, cold] = runs;
+    for _ in 0..iterations {
+        delete_snapshot(client, name).await?;
+        evict(client, function_id).await?;
+        sample(client, name, body, true, cold).await;
+        progress.inc(1);
+
+        client
+            .post(&format!("/functions/{name}/snapshot"), &json!({}))
+            .await?;
+        evict(client, function_id).await?;
+        sample(client, name, body, true, snapshot).await;
+        progress.inc(1);
+
+        sample(client, name, body, false, warm).await;
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+    Ok(())
+}
+
+/// Compares warm, snapshot-restored and cold invocations, forcing each
+/// boot mode by evicting instances and deleting or creating the snapshot.
+/// Latency is end-to-end as observed by the client.
+async fn run_bench(
+    name: &str,
+    iterations: u32,
+    payload: Option<String>,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let body = match payload {
+        Some(p) => serde_json::from_str(&p)
+            .map_err(|e| OrbitError::Input(format!("Invalid JSON payload: {e}")))?,
+        None => json!({}),
+    };
+    let function = client.get(&format!("/functions/{name}")).await?;
+    let function_id = function["id"].as_str().unwrap_or(name).to_string();
+    let had_snapshot = client
+        .get_all("/snapshots")
+        .await?
+        .as_array()
+        .is_some_and(|s| s.iter().any(|s| s["function_name"].as_str() == Some(name)));
+
+    let mut runs: [BenchRuns; 3] = Default::default();
+    let result = bench_rounds(name, &function_id, iterations, &body, client, &mut runs).await;
+    // Leave the snapshot as it was found.
+    let restored = if had_snapshot {
+        client
+            .post(&format!("/functions/{name}/snapshot"), &json!({}))
+            .await
+            .map(|_| ())
+    } else {
+        delete_snapshot(client, name).await
+    };
+    result?;
+    if let Err(e) = restored {
+        output::print_warning(&format!("Could not restore the snapshot of '{name}': {e}"));
+    }
+
+    let [warm, snapshot, cold] = runs;
+    let mismatched = warm.mismatched + snapshot.mismatched + cold.mismatched;
+    let rows = vec![
+        warm.summary("warm"),
+        snapshot.summary("snapshot"),
+        cold.summary("cold"),
+    ];
+    let (snapshot_p50, cold_p50) = (rows[1]["p50_ms"].as_f64(), rows[2]["p50_ms"].as_f64());
+    output::render(&Value::Array(rows), BENCH_COLUMNS, output_format)?;
+    if mismatched > 0 {
+        output::print_warning(&format!(
+            "{mismatched} invocation(s) did not boot as intended (by their cold_start) and were left out."
+        ));
+    }
+    if !output::is_machine_readable(output_format)
+        && let (Some(snapshot), Some(cold)) = (snapshot_p50, cold_p50)
+        && snapshot > 0.0
+        && cold > 0.0
+    {
+        output::print_line(&format!(
+            "Snapshot restore p50 is {:.1}x faster than a cold boot ({:.1} ms saved).",
+            cold / snapshot,
+            cold - snapshot
+        ));
+    }
     Ok(())
 }
 
 pub async fn run_fn(cmd: SnapshotSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         SnapshotSubCmd::Create { name } => {
-            let spinner = ProgressBar::new_spinner();
+            let spinner = output::progress_bar("snapshot", None);
             spinner.set_style(
                 ProgressStyle::default_spinner()
                     .template("{spinner:.cyan} Creating snapshot for {msg}...")
@@ -46,8 +365,8 @@ pub async fn run_fn(cmd: SnapshotSubCmd, client: &NovaClient, output_format: &st
                 .await?;
             spinner.finish_and_clear();
             output::print_success(&format!("Snapshot created for '{name}'."));
-            if output_format == "json" || output_format == "yaml" {
-                output::render_single(&result, SNAPSHOT_COLUMNS, output_format);
+            if output::is_machine_readable(output_format) {
+                output::render_single(&result, SNAPSHOT_COLUMNS, output_format)?;
             }
         }
         SnapshotSubCmd::Delete { name } => {
@@ -56,6 +375,11 @@ pub async fn run_fn(cmd: SnapshotSubCmd, client: &NovaClient, output_format: &st
                 .await?;
             output::print_success(&format!("Snapshot deleted for '{name}'."));
         }
+        SnapshotSubCmd::Bench {
+            name,
+            iterations,
+            payload,
+        } => run_bench(&name, iterations, payload, client, output_format).await?,
     }
     Ok(())
 }
@@ -28,16 +28,15 @@ pub async fn run(cmd: StateCmd, client: &NovaClient, output_format: &str) -> Res
     match cmd {
         StateCmd::Get { name } => {
             let result = client.get(&format!("/functions/{name}/state")).await?;
-            output::render_single(&result, STATE_COLUMNS, output_format);
+            output::render_single(&result, STATE_COLUMNS, output_format)?;
         }
         StateCmd::Put { name, data } => {
-            let parsed: serde_json::Value = serde_json::from_str(&data).map_err(|e| {
-                crate::error::OrbitError::Input(format!("Invalid JSON data: {e}"))
-            })?;
+            let parsed: serde_json::Value = serde_json::from_str(&data)
+                .map_err(|e| crate::error::OrbitError::Input(format!("Invalid JSON data: {e}")))?;
             let result = client
                 .put(&format!("/functions/{name}/state"), &parsed)
                 .await?;
-            output::render_single(&result, STATE_COLUMNS, output_format);
+            output::render_single(&result, STATE_COLUMNS, output_format)?;
         }
         StateCmd::Delete { name } => {
             client.delete(&format!("/functions/{name}/state")).await?;
@@ -16,10 +16,7 @@ pub enum MenuPermsCmd {
         visible: bool,
     },
     /// Delete a menu permission
-    Delete {
-        tenant_id: String,
-        menu_key: String,
-    },
+    Delete { tenant_id: String, menu_key: String },
 }
 
 #[derive(Subcommand)]
@@ -56,7 +53,7 @@ pub async fn run_menu(cmd: MenuPermsCmd, client: &NovaClient, output_format: &st
             let result = client
                 .get(&format!("/tenants/{tenant_id}/menu-permissions"))
                 .await?;
-            output::render(&result, MENU_PERM_COLUMNS, output_format);
+            output::render(&result, MENU_PERM_COLUMNS, output_format)?;
         }
         MenuPermsCmd::Set {
             tenant_id,
@@ -70,16 +67,14 @@ pub async fn run_menu(cmd: MenuPermsCmd, client: &NovaClient, output_format: &st
                     &body,
                 )
                 .await?;
-            output::render_single(&result, MENU_PERM_COLUMNS, output_format);
+            output::render_single(&result, MENU_PERM_COLUMNS, output_format)?;
         }
         MenuPermsCmd::Delete {
             tenant_id,
             menu_key,
         } => {
             client
-                .delete(&format!(
-                    "/tenants/{tenant_id}/menu-permissions/{menu_key}"
-                ))
+                .delete(&format!("/tenants/{tenant_id}/menu-permissions/{menu_key}"))
                 .await?;
             output::print_success(&format!("Menu permission '{menu_key}' deleted."));
         }
@@ -97,7 +92,7 @@ pub async fn run_button(
             let result = client
                 .get(&format!("/tenants/{tenant_id}/button-permissions"))
                 .await?;
-            output::render(&result, BUTTON_PERM_COLUMNS, output_format);
+            output::render(&result, BUTTON_PERM_COLUMNS, output_format)?;
         }
         ButtonPermsCmd::Set {
             tenant_id,
@@ -111,7 +106,7 @@ pub async fn run_button(
                     &body,
                 )
                 .await?;
-            output::render_single(&result, BUTTON_PERM_COLUMNS, output_format);
+            output::render_single(&result, BUTTON_PERM_COLUMNS, output_format)?;
         }
         ButtonPermsCmd::Delete {
             tenant_id,
@@ -122,9 +117,7 @@ pub async fn run_button(
                     "/tenants/{tenant_id}/button-permissions/{permission_key}"
                 ))
                 .await?;
-            output::print_success(&format!(
-                "Button permission '{permission_key}' deleted."
-            ));
+            output::print_success(&format!("Button permission '{permission_key}' deleted."));
         }
     }
     Ok(())
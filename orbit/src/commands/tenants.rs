@@ -1,8 +1,8 @@
 use crate::client::NovaClient;
-use crate::error::Result;
+use crate::error::{OrbitError, Result};
 use crate::output::{self, Column};
 use clap::Subcommand;
-use serde_json::json;
+use serde_json::{Value, json};
 
 #[derive(Subcommand)]
 pub enum TenantsCmd {
@@ -39,6 +39,40 @@ pub enum TenantsCmd {
     },
     /// Get tenant usage
     Usage { id: String },
+    /// Show concurrent executions against the quota, queue depth and recent
+    /// throttling, for noisy-neighbor investigations
+    Concurrency {
+        id: String,
+        /// Keep sampling, printing a line per sample
+        #[arg(long)]
+        watch: bool,
+        /// Sampling interval for --watch (e.g. 5s, 1m)
+        #[arg(long, default_value = "5s")]
+        interval: String,
+    },
+    /// Manage the encryption keys for secrets and payload captures
+    Keys {
+        #[command(subcommand)]
+        cmd: KeysSubCmd,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum KeysSubCmd {
+    /// List encryption keys with their rotation age
+    List { tenant_id: String },
+    /// Rotate encryption keys and re-encrypt existing data
+    Rotate {
+        tenant_id: String,
+        /// Only rotate the key for this purpose (secrets, payloads)
+        #[arg(long)]
+        purpose: Option<String>,
+        /// Skip the confirmation prompt
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Show re-encryption progress of the latest rotation
+    Status { tenant_id: String },
 }
 
 #[derive(Subcommand)]
@@ -85,6 +119,103 @@ pub enum QuotasSubCmd {
     },
 }
 
+const CONCURRENCY_COLUMNS: &[Column] = &[
+    Column::new("Tenant", "tenant_id"),
+    Column::new("Concurrent", "concurrent"),
+    Column::new("Quota", "concurrency_quota"),
+    Column::new("Utilization", "utilization"),
+    Column::new("Queued", "queue_depth"),
+    Column::new("Throttled (5m)", "throttled_5m"),
+];
+
+const THROTTLE_EVENT_COLUMNS: &[Column] = &[
+    Column::new("Time", "at"),
+    Column::new("Function", "function"),
+    Column::new("Rejected", "rejected"),
+    Column::new("Reason", "reason").max_width(40),
+];
+
+const BUSIEST_COLUMNS: &[Column] = &[
+    Column::new("Function", "function"),
+    Column::new("Concurrent", "concurrent"),
+    Column::new("Queued", "queue_depth"),
+];
+
+/// Samples kept for the `--watch` sparkline.
+const WATCH_HISTORY: usize = 40;
+
+fn utilization(sample: &Value) -> Option<f64> {
+    let quota = sample["concurrency_quota"].as_f64().filter(|q| *q > 0.0)?;
+    Some(sample["concurrent"].as_f64()? * 100.0 / quota)
+}
+
+async fn run_concurrency(
+    client: &NovaClient,
+    id: &str,
+    watch: bool,
+    interval: &str,
+    output_format: &str,
+) -> Result<()> {
+    let path = format!("/tenants/{id}/concurrency");
+    if !watch {
+        let mut result = client.get(&path).await?;
+        if let Some(pct) = utilization(&result) {
+            result["utilization"] = json!(format!("{pct:.0}%"));
+        }
+        output::render_single(&result, CONCURRENCY_COLUMNS, output_format)?;
+        if output::is_machine_readable(output_format) {
+            return Ok(());
+        }
+        for (title, field, columns) in [
+            ("Busiest functions:", "by_function", BUSIEST_COLUMNS),
+            (
+                "Recent throttling:",
+                "throttle_events",
+                THROTTLE_EVENT_COLUMNS,
+            ),
+        ] {
+            if let Some(rows) = result[field].as_array().filter(|r| !r.is_empty()) {
+                output::print_line(&format!("\n{title}"));
+                output::render(&Value::Array(rows.clone()), columns, output_format)?;
+            }
+        }
+        return Ok(());
+    }
+
+    let every = crate::duration::parse(interval)?
+        .to_std()
+        .ok()
+        .filter(|d| !d.is_zero())
+        .ok_or_else(|| OrbitError::Input("Interval must be positive".into()))?;
+    let mut history: Vec<f64> = Vec::new();
+    output::print_status(&format!("Sampling every {interval}. Press Ctrl-C to stop."));
+    loop {
+        match client.get(&path).await {
+            Ok(sample) => {
+                let concurrent = sample["concurrent"].as_f64().unwrap_or(0.0);
+                history.push(concurrent);
+                if history.len() > WATCH_HISTORY {
+                    history.remove(0);
+                }
+                let quota = match (sample["concurrency_quota"].as_i64(), utilization(&sample)) {
+                    (Some(quota), Some(pct)) => format!("/{quota} ({pct:>3.0}%)"),
+                    _ => String::new(),
+                };
+                println!(
+                    "{}  concurrent {:>5}{quota}  queued {:>5}  throttled {:>5}  {}",
+                    chrono::Local::now().format("%H:%M:%S"),
+                    concurrent,
+                    sample["queue_depth"].as_i64().unwrap_or(0),
+                    sample["throttled_5m"].as_i64().unwrap_or(0),
+                    output::sparkline(&history)
+                );
+            }
+            Err(e) => output::print_warning(&format!("Sample failed: {e}")),
+        }
+        tokio::time::sleep(every).await;
+    }
+}
+
 const TENANT_COLUMNS: &[Column] = &[
     Column::new("ID", "id"),
     Column::new("Name", "name"),
@@ -104,11 +235,66 @@ const QUOTA_COLUMNS: &[Column] = &[
     Column::new("Window", "window"),
 ];
 
+const KEY_COLUMNS: &[Column] = &[
+    Column::new("Key ID", "id"),
+    Column::new("Purpose", "purpose"),
+    Column::new("State", "state"),
+    Column::new("Algorithm", "algorithm"),
+    Column::new("Age", "age"),
+    Column::wide("Created", "created_at"),
+    Column::wide("Rotated", "rotated_at"),
+];
+
+const ROTATION_COLUMNS: &[Column] = &[
+    Column::new("Purpose", "purpose"),
+    Column::new("State", "state"),
+    Column::new("Progress", "progress"),
+    Column::new("Re-encrypted", "reencrypted"),
+    Column::new("Total", "total"),
+    Column::new("Failed", "failed"),
+    Column::new("Started", "started_at"),
+    Column::wide("From Key", "from_key_id"),
+    Column::wide("To Key", "to_key_id"),
+    Column::wide("Finished", "finished_at"),
+];
+
+/// Adds the time since each key was last rotated (or created).
+fn with_key_age(keys: Value) -> Value {
+    let now = chrono::Utc::now();
+    let mut rows = keys.as_array().cloned().unwrap_or_default();
+    for row in &mut rows {
+        let since = crate::duration::timestamp(row, "rotated_at")
+            .or_else(|| crate::duration::timestamp(row, "created_at"));
+        if let Some(since) = since {
+            row["age"] = json!(crate::duration::format_age(now - since));
+        }
+    }
+    Value::Array(rows)
+}
+
+/// Adds a `progress` percentage to each rotation.
+fn with_progress(rotations: Value) -> Value {
+    let mut rows = match rotations {
+        Value::Array(rows) => rows,
+        Value::Object(_) => vec![rotations],
+        other => return other,
+    };
+    for row in &mut rows {
+        let done = row["reencrypted"].as_u64().unwrap_or(0);
+        row["progress"] = match row["total"].as_u64() {
+            Some(0) => json!("100%"),
+            Some(total) => json!(format!("{:.0}%", done as f64 * 100.0 / total as f64)),
+            None => Value::Null,
+        };
+    }
+    Value::Array(rows)
+}
+
 pub async fn run(cmd: TenantsCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         TenantsCmd::List => {
-            let result = client.get("/tenants").await?;
-            output::render(&result, TENANT_COLUMNS, output_format);
+            let result = client.get_paged("/tenants").await?;
+            output::render(&result, TENANT_COLUMNS, output_format)?;
         }
         TenantsCmd::Create { name, tier } => {
             let mut body = json!({ "name": name });
@@ -116,7 +302,7 @@ pub async fn run(cmd: TenantsCmd, client: &NovaClient, output_format: &str) -> R
                 body["tier"] = json!(t);
             }
             let result = client.post("/tenants", &body).await?;
-            output::render_single(&result, TENANT_COLUMNS, output_format);
+            output::render_single(&result, TENANT_COLUMNS, output_format)?;
         }
         TenantsCmd::Update {
             id,
@@ -135,7 +321,7 @@ pub async fn run(cmd: TenantsCmd, client: &NovaClient, output_format: &str) -> R
                 body["tier"] = json!(t);
             }
             let result = client.patch(&format!("/tenants/{id}"), &body).await?;
-            output::render_single(&result, TENANT_COLUMNS, output_format);
+            output::render_single(&result, TENANT_COLUMNS, output_format)?;
         }
         TenantsCmd::Delete { id } => {
             client.delete(&format!("/tenants/{id}")).await?;
@@ -146,14 +332,14 @@ pub async fn run(cmd: TenantsCmd, client: &NovaClient, output_format: &str) -> R
                 let result = client
                     .get(&format!("/tenants/{tenant_id}/namespaces"))
                     .await?;
-                output::render(&result, NS_COLUMNS, output_format);
+                output::render(&result, NS_COLUMNS, output_format)?;
             }
             NamespacesSubCmd::Create { tenant_id, name } => {
                 let body = json!({ "name": name });
                 let result = client
                     .post(&format!("/tenants/{tenant_id}/namespaces"), &body)
                     .await?;
-                output::render_single(&result, NS_COLUMNS, output_format);
+                output::render_single(&result, NS_COLUMNS, output_format)?;
             }
             NamespacesSubCmd::Update {
                 tenant_id,
@@ -170,7 +356,7 @@ pub async fn run(cmd: TenantsCmd, client: &NovaClient, output_format: &str) -> R
                         &body,
                     )
                     .await?;
-                output::render_single(&result, NS_COLUMNS, output_format);
+                output::render_single(&result, NS_COLUMNS, output_format)?;
             }
             NamespacesSubCmd::Delete {
                 tenant_id,
@@ -185,7 +371,7 @@ pub async fn run(cmd: TenantsCmd, client: &NovaClient, output_format: &str) -> R
         TenantsCmd::Quotas { cmd } => match cmd {
             QuotasSubCmd::List { tenant_id } => {
                 let result = client.get(&format!("/tenants/{tenant_id}/quotas")).await?;
-                output::render(&result, QUOTA_COLUMNS, output_format);
+                output::render(&result, QUOTA_COLUMNS, output_format)?;
             }
             QuotasSubCmd::Set {
                 tenant_id,
@@ -200,7 +386,7 @@ pub async fn run(cmd: TenantsCmd, client: &NovaClient, output_format: &str) -> R
                 let result = client
                     .put(&format!("/tenants/{tenant_id}/quotas/{dimension}"), &body)
                     .await?;
-                output::render_single(&result, QUOTA_COLUMNS, output_format);
+                output::render_single(&result, QUOTA_COLUMNS, output_format)?;
             }
             QuotasSubCmd::Delete {
                 tenant_id,
@@ -212,6 +398,44 @@ pub async fn run(cmd: TenantsCmd, client: &NovaClient, output_format: &str) -> R
                 output::print_success(&format!("Quota '{dimension}' deleted."));
             }
         },
+        TenantsCmd::Keys { cmd } => match cmd {
+            KeysSubCmd::List { tenant_id } => {
+                let result = client.get(&format!("/tenants/{tenant_id}/keys")).await?;
+                output::render(&with_key_age(result), KEY_COLUMNS, output_format)?;
+            }
+            KeysSubCmd::Rotate {
+                tenant_id,
+                purpose,
+                yes,
+            } => {
+                let what = purpose.as_deref().unwrap_or("all");
+                if !yes
+                    && !output::confirm(&format!(
+                        "Rotate {what} encryption key(s) of tenant '{tenant_id}' and re-encrypt existing data?"
+                    ))
+                {
+                    output::print_status("Aborted.");
+                    return Ok(());
+                }
+                let mut body = json!({});
+                if let Some(p) = purpose {
+                    body["purpose"] = json!(p);
+                }
+                let result = client
+                    .post(&format!("/tenants/{tenant_id}/keys/rotate"), &body)
+                    .await?;
+                output::render(&with_progress(result), ROTATION_COLUMNS, output_format)?;
+                output::print_status(&format!(
+                    "Track re-encryption with: orbit tenants keys status {tenant_id}"
+                ));
+            }
+            KeysSubCmd::Status { tenant_id } => {
+                let result = client
+                    .get(&format!("/tenants/{tenant_id}/keys/status"))
+                    .await?;
+                output::render(&with_progress(result), ROTATION_COLUMNS, output_format)?;
+            }
+        },
         TenantsCmd::Usage { id } => {
             let result = client.get(&format!("/tenants/{id}/usage")).await?;
             output::render_single(
@@ -223,8 +447,13 @@ pub async fn run(cmd: TenantsCmd, client: &NovaClient, output_format: &str) -> R
                     Column::new("Async Queue", "async_queue_depth"),
                 ],
                 output_format,
-            );
+            )?;
         }
+        TenantsCmd::Concurrency {
+            id,
+            watch,
+            interval,
+        } => run_concurrency(client, &id, watch, &interval, output_format).await?,
     }
     Ok(())
 }
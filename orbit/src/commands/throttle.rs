@@ -0,0 +1,79 @@
+//! `orbit functions throttle`: a temporary invocation rate cap for
+//! incidents. Unlike the capacity policy it carries an expiry, after which
+//! the server lifts it on its own.
+
+use crate::client::NovaClient;
+use crate::commands::functions::ThrottleSubCmd;
+use crate::error::{OrbitError, Result};
+use crate::output::{self, Column};
+use chrono::Utc;
+use serde_json::{Value, json};
+
+const THROTTLE_COLUMNS: &[Column] = &[
+    Column::new("RPS", "rps"),
+    Column::new("Expires", "expires_at"),
+    Column::new("Remaining", "remaining"),
+    Column::new("Reason", "reason"),
+];
+
+/// Time left on an active throttle, or `None` once it has expired.
+fn remaining(throttle: &Value) -> Option<String> {
+    let left = crate::duration::timestamp(throttle, "expires_at")? - Utc::now();
+    (left.num_seconds() > 0).then(|| crate::duration::format_age(left))
+}
+
+/// One-line summary for `functions get`, e.g. `50 rps, 1h 59m left`.
+pub fn summary(throttle: &Value) -> Option<String> {
+    let rps = throttle.get("rps").filter(|v| !v.is_null())?;
+    Some(format!("{rps} rps, {} left", remaining(throttle)?))
+}
+
+pub async fn run(cmd: ThrottleSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
+    match cmd {
+        ThrottleSubCmd::Set {
+            name,
+            rps,
+            until,
+            reason,
+        } => {
+            if rps == 0 {
+                return Err(OrbitError::Input("--rps must be positive".into()));
+            }
+            let until = crate::duration::parse(&until)?;
+            if until.num_seconds() <= 0 {
+                return Err(OrbitError::Input("--until must be positive".into()));
+            }
+            let mut body = json!({
+                "rps": rps,
+                "expires_at": crate::duration::from_now(until)?.to_rfc3339(),
+            });
+            if let Some(r) = reason {
+                body["reason"] = json!(r);
+            }
+            let mut result = client
+                .put(&format!("/functions/{name}/throttle"), &body)
+                .await?;
+            result["remaining"] = json!(remaining(&result));
+            output::render_single(&result, THROTTLE_COLUMNS, output_format)?;
+        }
+        ThrottleSubCmd::Clear { name } => {
+            client
+                .delete(&format!("/functions/{name}/throttle"))
+                .await?;
+            output::print_success(&format!("Throttle cleared for '{name}'."));
+        }
+        ThrottleSubCmd::Status { name } => {
+            let mut result = match client.get(&format!("/functions/{name}/throttle")).await {
+                Err(OrbitError::Api { status: 404, .. }) => Value::Null,
+                other => other?,
+            };
+            if summary(&result).is_none() && !output::is_machine_readable(output_format) {
+                println!("No active throttle on '{name}'.");
+                return Ok(());
+            }
+            result["remaining"] = json!(remaining(&result));
+            output::render_single(&result, THROTTLE_COLUMNS, output_format)?;
+        }
+    }
+    Ok(())
+}
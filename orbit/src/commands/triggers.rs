@@ -67,29 +67,27 @@ pub async fn run(cmd: TriggersCmd, client: &NovaClient, output_format: &str) ->
                 body["config"] = parsed;
             }
             let result = client.post("/triggers", &body).await?;
-            output::render_single(&result, TRIGGER_COLUMNS, output_format);
+            output::render_single(&result, TRIGGER_COLUMNS, output_format)?;
         }
         TriggersCmd::List { function } => {
             let mut path = "/triggers".to_string();
             if let Some(f) = function {
                 path = format!("{path}?function={f}");
             }
-            let result = client.get(&path).await?;
-            output::render(&result, TRIGGER_COLUMNS, output_format);
+            let result = client.get_paged(&path).await?;
+            output::render(&result, TRIGGER_COLUMNS, output_format)?;
         }
         TriggersCmd::Get { id } => {
             let result = client.get(&format!("/triggers/{id}")).await?;
-            output::render_single(&result, TRIGGER_COLUMNS, output_format);
+            output::render_single(&result, TRIGGER_COLUMNS, output_format)?;
         }
         TriggersCmd::Update { id, enabled } => {
             let mut body = json!({});
             if let Some(v) = enabled {
                 body["enabled"] = json!(v);
             }
-            let result = client
-                .put(&format!("/triggers/{id}"), &body)
-                .await?;
-            output::render_single(&result, TRIGGER_COLUMNS, output_format);
+            let result = client.put(&format!("/triggers/{id}"), &body).await?;
+            output::render_single(&result, TRIGGER_COLUMNS, output_format)?;
         }
         TriggersCmd::Delete { id } => {
             client.delete(&format!("/triggers/{id}")).await?;
@@ -1,7 +1,16 @@
 use crate::client::NovaClient;
 use crate::commands::functions::VersionsSubCmd;
-use crate::error::Result;
+use crate::duration;
+use crate::error::{OrbitError, Result};
 use crate::output::{self, Column};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde_json::{Value, json};
+
+/// Version fields that describe the release rather than its configuration.
+const VERSION_ONLY_FIELDS: &[&str] = &["function_id", "code", "created_at"];
+
+/// Request fields of an audited update that are not configuration.
+const NON_CONFIG_FIELDS: &[&str] = &["name", "code", "dependency_files", "merge_env_vars"];
 
 const VERSION_COLUMNS: &[Column] = &[
     Column::new("Version", "version"),
@@ -14,17 +23,262 @@ const VERSION_COLUMNS: &[Column] = &[
     Column::new("Created", "created_at"),
 ];
 
+/// Fields activating a version copies onto the function.
+const ACTIVATED_FIELDS: &[&str] = &[
+    "code_hash",
+    "handler",
+    "memory_mb",
+    "timeout_s",
+    "mode",
+    "limits",
+    "env_vars",
+];
+
+const ROLLBACK_COLUMNS: &[Column] = &[
+    Column::new("Field", "field"),
+    Column::new("Active", "from"),
+    Column::new("After Rollback", "to"),
+];
+
+/// What activating `target` would change on `function`, one row per field.
+/// Environment variables are listed by name only, since values may be
+/// secrets.
+fn rollback_changes(function: &Value, target: &Value) -> Vec<Value> {
+    let mut rows = Vec::new();
+    for field in ACTIVATED_FIELDS {
+        let (from, to) = (&function[field], &target[field]);
+        if *field != "env_vars" {
+            if from != to {
+                rows.push(json!({ "field": field, "from": from, "to": to }));
+            }
+            continue;
+        }
+        let keys: std::collections::BTreeSet<&String> = from
+            .as_object()
+            .into_iter()
+            .chain(to.as_object())
+            .flat_map(|m| m.keys())
+            .collect();
+        for key in keys {
+            let (a, b) = (from.get(key), to.get(key));
+            if a == b {
+                continue;
+            }
+            let to = match (a, b) {
+                (_, None) => json!("removed"),
+                (Some(_), Some(_)) => json!("changed"),
+                (None, Some(_)) => json!("set"),
+            };
+            rows.push(json!({
+                "field": format!("env_vars.{key}"),
+                "from": a.map(|_| "set"),
+                "to": to,
+            }));
+        }
+    }
+    rows
+}
+
+/// Restores version `to` (by default the newest one before the active
+/// version) after confirming the changes it makes.
+async fn rollback(
+    name: &str,
+    to: Option<u32>,
+    yes: bool,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let function = client.get(&format!("/functions/{name}")).await?;
+    let active = function["version"].as_i64();
+    let target = match to {
+        Some(v) => i64::from(v),
+        None => {
+            let versions = client
+                .get_all(&format!("/functions/{name}/versions"))
+                .await?;
+            let mut numbers: Vec<i64> = versions
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v["version"].as_i64())
+                .collect();
+            numbers.sort_unstable();
+            let previous = match active {
+                Some(a) => numbers.into_iter().rfind(|v| *v < a),
+                None => numbers.iter().rev().nth(1).copied(),
+            };
+            previous.ok_or_else(|| {
+                OrbitError::Input(format!("'{name}' has no earlier version to roll back to"))
+            })?
+        }
+    };
+    let version = client
+        .get(&format!("/functions/{name}/versions/{target}"))
+        .await?;
+    let changes = rollback_changes(&function, &version);
+    let from = active.map_or("the active version".to_string(), |a| format!("v{a}"));
+    if changes.is_empty() && active == Some(target) {
+        output::print_success(&format!("'{name}' is already at v{target}."));
+        return Ok(());
+    }
+
+    if !output::is_machine_readable(output_format) {
+        if changes.is_empty() {
+            output::print_line(&format!(
+                "v{target} has the same code and config as {from}."
+            ));
+        } else {
+            output::render(&json!(changes), ROLLBACK_COLUMNS, output_format)?;
+        }
+    }
+    if !yes && !output::confirm(&format!("Roll '{name}' back from {from} to v{target}?")) {
+        output::print_status("Aborted.");
+        return Ok(());
+    }
+    client
+        .post(
+            &format!("/functions/{name}/versions/{target}/activate"),
+            &json!({}),
+        )
+        .await?;
+    if output::is_machine_readable(output_format) {
+        let result = json!({
+            "name": name,
+            "from_version": active,
+            "to_version": target,
+            "changes": changes,
+        });
+        output::render_single(&result, &[], output_format)?;
+    } else {
+        output::print_success(&format!("Rolled '{name}' back from {from} to v{target}."));
+    }
+    Ok(())
+}
+
+/// Parses `--at`: an RFC 3339 timestamp, a date (midnight UTC), or a
+/// duration ago such as `6h`.
+pub fn parse_at(input: &str) -> Result<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(input) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    duration::parse(input).and_then(duration::ago).map_err(|_| {
+        OrbitError::Input(format!(
+            "Invalid --at '{input}'. Use a timestamp, YYYY-MM-DD, or a duration ago like 6h"
+        ))
+    })
+}
+
+/// Reconstructs the configuration `name` had at `at`: the newest version
+/// published by then, with the function's audited updates since replayed
+/// over it. The audit log keeps only the start of each request body, so an
+/// update whose body cannot be read is counted in `skipped_changes`.
+pub async fn config_at(client: &NovaClient, name: &str, at: DateTime<Utc>) -> Result<Value> {
+    let at_param = at.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let versions = client
+        .get_all(&format!("/functions/{name}/versions"))
+        .await?;
+    let base = versions
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|v| duration::timestamp(v, "created_at").is_some_and(|t| t <= at))
+        .max_by_key(|v| v["version"].as_i64());
+
+    let mut config = json!({ "name": name });
+    let mut since = None;
+    if let Some(version) = base {
+        for (key, value) in version.as_object().into_iter().flatten() {
+            if !VERSION_ONLY_FIELDS.contains(&key.as_str()) {
+                config[key] = value.clone();
+            }
+        }
+        since = duration::timestamp(version, "created_at");
+    }
+
+    let audit = client
+        .get_all(&format!(
+            "/audit-logs?resource_type=function&resource_name={name}&until={at_param}"
+        ))
+        .await?;
+    let function_path = format!("/functions/{name}");
+    let mut changes: Vec<&Value> = audit
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|e| e["http_path"].as_str() == Some(function_path.as_str()))
+        .filter(|e| e["status_code"].as_i64().is_some_and(|s| s < 300))
+        .filter(|e| {
+            duration::timestamp(e, "created_at")
+                .is_some_and(|t| t <= at && since.is_none_or(|s| t > s))
+        })
+        .collect();
+    changes.sort_by_key(|e| duration::timestamp(e, "created_at"));
+
+    let (mut applied, mut skipped) = (0, 0);
+    for change in changes {
+        if change["action"] == "delete" {
+            return Err(OrbitError::Input(format!(
+                "Function '{name}' was deleted at {}, before {at_param}",
+                change["created_at"].as_str().unwrap_or("?")
+            )));
+        }
+        let body = change["request_body"]
+            .as_str()
+            .and_then(|b| serde_json::from_str::<Value>(b).ok());
+        let Some(Value::Object(fields)) = body else {
+            skipped += 1;
+            continue;
+        };
+        let merge_env = fields.get("merge_env_vars") == Some(&json!(true));
+        for (key, value) in fields {
+            if NON_CONFIG_FIELDS.contains(&key.as_str()) {
+                continue;
+            }
+            match (&mut config[&key], &value) {
+                (Value::Object(current), Value::Object(new)) if key == "env_vars" && merge_env => {
+                    current.extend(new.clone());
+                }
+                (current, _) => *current = value,
+            }
+        }
+        applied += 1;
+    }
+
+    if base.is_none() && applied == 0 {
+        return Err(OrbitError::Input(format!(
+            "No version or audited change of '{name}' at or before {at_param}"
+        )));
+    }
+    let source = match (&base, applied) {
+        (Some(v), 0) => format!("v{}", v["version"]),
+        (Some(v), n) => format!("v{} + {n} audited change(s)", v["version"]),
+        (None, n) => format!("{n} audited change(s)"),
+    };
+    config["as_of"] = json!(at_param);
+    config["reconstructed_from"] = json!(source);
+    config["skipped_changes"] = json!(skipped);
+    Ok(config)
+}
+
 pub async fn run(cmd: VersionsSubCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         VersionsSubCmd::List { name } => {
-            let result = client.get(&format!("/functions/{name}/versions")).await?;
-            output::render(&result, VERSION_COLUMNS, output_format);
+            let result = client
+                .get_paged(&format!("/functions/{name}/versions"))
+                .await?;
+            output::render(&result, VERSION_COLUMNS, output_format)?;
         }
         VersionsSubCmd::Get { name, version } => {
             let result = client
                 .get(&format!("/functions/{name}/versions/{version}"))
                 .await?;
-            output::render_single(&result, VERSION_COLUMNS, output_format);
+            output::render_single(&result, VERSION_COLUMNS, output_format)?;
+        }
+        VersionsSubCmd::Rollback { name, to, yes } => {
+            rollback(&name, to, yes, client, output_format).await?;
         }
     }
     Ok(())
@@ -67,15 +67,15 @@ pub async fn run(cmd: VolumesCmd, client: &NovaClient, output_format: &str) -> R
                 body["description"] = json!(v);
             }
             let result = client.post("/volumes", &body).await?;
-            output::render_single(&result, VOLUME_COLUMNS, output_format);
+            output::render_single(&result, VOLUME_COLUMNS, output_format)?;
         }
         VolumesCmd::List => {
-            let result = client.get("/volumes").await?;
-            output::render(&result, VOLUME_COLUMNS, output_format);
+            let result = client.get_paged("/volumes").await?;
+            output::render(&result, VOLUME_COLUMNS, output_format)?;
         }
         VolumesCmd::Get { name } => {
             let result = client.get(&format!("/volumes/{name}")).await?;
-            output::render_single(&result, VOLUME_COLUMNS, output_format);
+            output::render_single(&result, VOLUME_COLUMNS, output_format)?;
         }
         VolumesCmd::Delete { name } => {
             client.delete(&format!("/volumes/{name}")).await?;
@@ -89,7 +89,7 @@ pub async fn run_mounts(cmd: MountsCmd, client: &NovaClient, output_format: &str
     match cmd {
         MountsCmd::Get { name } => {
             let result = client.get(&format!("/functions/{name}/mounts")).await?;
-            output::render(&result, MOUNT_COLUMNS, output_format);
+            output::render(&result, MOUNT_COLUMNS, output_format)?;
         }
         MountsCmd::Set {
             name,
@@ -100,7 +100,7 @@ pub async fn run_mounts(cmd: MountsCmd, client: &NovaClient, output_format: &str
             let result = client
                 .put(&format!("/functions/{name}/mounts"), &body)
                 .await?;
-            output::render_single(&result, MOUNT_COLUMNS, output_format);
+            output::render_single(&result, MOUNT_COLUMNS, output_format)?;
         }
     }
     Ok(())
@@ -0,0 +1,265 @@
+//! `orbit whatif`: replays recent traffic against proposed function settings
+//! to estimate the impact of a change before it is applied.
+//!
+//! Each metrics bucket is reduced to an average concurrency (Little's law:
+//! invocations × average duration ÷ bucket length) and compared against the
+//! capacity the current and proposed settings would provide.
+
+use crate::client::NovaClient;
+use crate::error::{OrbitError, Result};
+use crate::manifest::{FunctionSpec, ProjectManifest};
+use crate::output::{self, Column};
+use serde_json::{Value, json};
+use std::path::Path;
+
+const WHATIF_COLUMNS: &[Column] = &[
+    Column::new("Function", "function"),
+    Column::new("Change", "change"),
+    Column::new("Shed Requests", "shed"),
+    Column::new("Shed %", "shed_pct"),
+    Column::new("Cold Starts Δ", "cold_starts_delta"),
+    Column::new("Timeouts", "timeouts"),
+    Column::new("Cost Δ", "cost_delta"),
+    Column::wide("Invocations", "invocations"),
+    Column::wide("Peak Concurrency", "peak_concurrency"),
+];
+
+/// Settings that influence capacity and cost. `None` keeps the deployed
+/// value.
+#[derive(Default)]
+pub struct Proposal {
+    pub memory_mb: Option<i64>,
+    pub timeout_s: Option<i64>,
+    pub min_replicas: Option<i64>,
+    pub max_replicas: Option<i64>,
+    pub concurrency: Option<i64>,
+}
+
+impl From<&FunctionSpec> for Proposal {
+    fn from(spec: &FunctionSpec) -> Self {
+        Self {
+            memory_mb: spec.memory_mb,
+            timeout_s: spec.timeout_s,
+            min_replicas: spec.min_replicas,
+            max_replicas: spec.max_replicas,
+            concurrency: None,
+        }
+    }
+}
+
+/// Resolved settings of one side of the comparison.
+#[derive(Clone, Copy)]
+struct Settings {
+    memory_mb: i64,
+    timeout_s: i64,
+    min_replicas: i64,
+    /// 0 means unlimited
+    max_replicas: i64,
+    concurrency: i64,
+}
+
+impl Settings {
+    fn current(function: &Value) -> Self {
+        let int = |key: &str| function[key].as_i64().unwrap_or(0);
+        Self {
+            memory_mb: int("memory_mb"),
+            timeout_s: int("timeout_s"),
+            min_replicas: int("min_replicas"),
+            max_replicas: int("max_replicas"),
+            concurrency: int("instance_concurrency").max(1),
+        }
+    }
+
+    fn apply(self, p: &Proposal) -> Self {
+        Self {
+            memory_mb: p.memory_mb.unwrap_or(self.memory_mb),
+            timeout_s: p.timeout_s.unwrap_or(self.timeout_s),
+            min_replicas: p.min_replicas.unwrap_or(self.min_replicas),
+            max_replicas: p.max_replicas.unwrap_or(self.max_replicas),
+            concurrency: p.concurrency.unwrap_or(self.concurrency).max(1),
+        }
+    }
+
+    /// Requests that can be in flight at once, if bounded.
+    fn capacity(&self) -> Option<f64> {
+        (self.max_replicas > 0).then(|| (self.max_replicas * self.concurrency) as f64)
+    }
+
+    /// Invocations in a bucket that exceed capacity.
+    fn shed(&self, invocations: f64, concurrency: f64) -> f64 {
+        match self.capacity() {
+            Some(cap) if concurrency > cap => invocations * (1.0 - cap / concurrency),
+            _ => 0.0,
+        }
+    }
+
+    /// Instances beyond the warm pool a bucket needs, each a cold start.
+    fn cold_starts(&self, concurrency: f64) -> f64 {
+        let mut needed = (concurrency / self.concurrency as f64).ceil();
+        if self.max_replicas > 0 {
+            needed = needed.min(self.max_replicas as f64);
+        }
+        (needed - self.min_replicas as f64).max(0.0)
+    }
+}
+
+/// Describes the settings that differ, e.g. `max_replicas 10→4`.
+fn describe_change(cur: &Settings, new: &Settings) -> String {
+    let fields = [
+        ("memory_mb", cur.memory_mb, new.memory_mb),
+        ("timeout_s", cur.timeout_s, new.timeout_s),
+        ("min_replicas", cur.min_replicas, new.min_replicas),
+        ("max_replicas", cur.max_replicas, new.max_replicas),
+        ("concurrency", cur.concurrency, new.concurrency),
+    ];
+    let changes: Vec<String> = fields
+        .iter()
+        .filter(|(_, a, b)| a != b)
+        .map(|(name, a, b)| format!("{name} {a}→{b}"))
+        .collect();
+    if changes.is_empty() {
+        "none".into()
+    } else {
+        changes.join(", ")
+    }
+}
+
+/// Length of one metrics bucket in seconds, from the first two timestamps.
+fn bucket_seconds(buckets: &[Value], range_secs: f64) -> f64 {
+    match (
+        buckets
+            .first()
+            .and_then(|b| crate::duration::timestamp(b, "timestamp")),
+        buckets
+            .get(1)
+            .and_then(|b| crate::duration::timestamp(b, "timestamp")),
+    ) {
+        (Some(a), Some(b)) if b > a => (b - a).num_seconds() as f64,
+        _ => range_secs / buckets.len().max(1) as f64,
+    }
+}
+
+async fn analyze(
+    client: &NovaClient,
+    name: &str,
+    proposal: &Proposal,
+    range: &str,
+    range_secs: i64,
+) -> Result<Value> {
+    let function = match client.get(&format!("/functions/{name}")).await {
+        Ok(f) => f,
+        Err(OrbitError::Api { status: 404, .. }) => {
+            return Ok(json!({ "function": name, "change": "new function" }));
+        }
+        Err(e) => return Err(e),
+    };
+    let cur = Settings::current(&function);
+    let new = cur.apply(proposal);
+
+    let metrics = client
+        .get(&format!("/functions/{name}/metrics?range={range}"))
+        .await?;
+    let buckets = metrics["timeseries"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let bucket_secs = bucket_seconds(&buckets, range_secs as f64);
+
+    let (mut invocations, mut shed, mut cold_delta, mut timeouts, mut peak) =
+        (0.0, 0.0, 0.0, 0.0, 0.0f64);
+    for bucket in &buckets {
+        let inv = bucket["invocations"].as_f64().unwrap_or(0.0);
+        let avg_ms = bucket["avg_duration"].as_f64().unwrap_or(0.0);
+        let concurrency = inv * avg_ms / 1000.0 / bucket_secs;
+        invocations += inv;
+        peak = peak.max(concurrency);
+        shed += (new.shed(inv, concurrency) - cur.shed(inv, concurrency)).max(0.0);
+        cold_delta += new.cold_starts(concurrency) - cur.cold_starts(concurrency);
+        if new.timeout_s > 0
+            && avg_ms > (new.timeout_s * 1000) as f64
+            && new.timeout_s < cur.timeout_s
+        {
+            timeouts += inv;
+        }
+    }
+
+    // Cost is optional: older servers have no cost endpoint.
+    let cost_delta = match client
+        .get(&format!("/functions/{name}/cost?window={range_secs}"))
+        .await
+    {
+        Ok(cost) => {
+            let num = |key: &str| cost[key].as_f64().unwrap_or(0.0);
+            let mut delta = 0.0;
+            if cur.memory_mb > 0 {
+                delta += num("compute_cost") * (new.memory_mb as f64 / cur.memory_mb as f64 - 1.0);
+            }
+            if num("invocations") > 0.0 {
+                delta -= num("total_cost") / num("invocations") * shed;
+            }
+            if num("cold_starts") > 0.0 {
+                delta += num("cold_start_cost") / num("cold_starts") * cold_delta;
+            }
+            Some(delta)
+        }
+        Err(_) => None,
+    };
+
+    let shed_pct = if invocations > 0.0 {
+        shed * 100.0 / invocations
+    } else {
+        0.0
+    };
+    Ok(json!({
+        "function": name,
+        "change": describe_change(&cur, &new),
+        "invocations": invocations.round() as i64,
+        "peak_concurrency": format!("{peak:.1}"),
+        "shed": shed.round() as i64,
+        "shed_pct": format!("{shed_pct:.1}%"),
+        "cold_starts_delta": format!("{:+}", cold_delta.round() as i64),
+        "timeouts": timeouts.round() as i64,
+        "cost_delta": cost_delta.map(|d| format!("{d:+.4}")),
+    }))
+}
+
+/// Estimates the impact of `proposal` on function `name`, or of every
+/// function in the manifest at `file`.
+pub async fn run(
+    name: Option<String>,
+    file: Option<String>,
+    proposal: Proposal,
+    range: &str,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let range_secs = crate::duration::parse(range)?.num_seconds();
+    if range_secs <= 0 {
+        return Err(OrbitError::Input("Range must be positive".into()));
+    }
+    let targets: Vec<(String, Proposal)> = match (name, file) {
+        (Some(name), None) => vec![(name, proposal)],
+        (None, Some(file)) => ProjectManifest::load(Path::new(&file))?
+            .functions
+            .iter()
+            .map(|spec| (spec.name.clone(), Proposal::from(spec)))
+            .collect(),
+        _ => {
+            return Err(OrbitError::Input(
+                "Specify a function name or a manifest with -f".into(),
+            ));
+        }
+    };
+
+    let mut rows = Vec::new();
+    for (name, proposal) in &targets {
+        rows.push(analyze(client, name, proposal, range, range_secs).await?);
+    }
+    output::render(&Value::Array(rows), WHATIF_COLUMNS, output_format)?;
+    if !output::is_machine_readable(output_format) {
+        output::print_line(&format!(
+            "Estimated from the last {range} of traffic; actual impact depends on burstiness within each bucket."
+        ));
+    }
+    Ok(())
+}
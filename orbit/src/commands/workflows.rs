@@ -2,7 +2,11 @@ use crate::client::NovaClient;
 use crate::error::Result;
 use crate::output::{self, Column};
 use clap::Subcommand;
-use serde_json::json;
+use indicatif::ProgressStyle;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
 #[derive(Subcommand)]
 pub enum WorkflowsCmd {
@@ -59,6 +63,26 @@ pub enum WorkflowsCmd {
         #[command(subcommand)]
         cmd: WfRunsCmd,
     },
+    /// Generate a workflow definition from a common orchestration pattern
+    New {
+        /// Orchestration pattern
+        #[arg(long, value_parser = ["fanout", "saga", "retry-pipeline"])]
+        pattern: String,
+        /// Functions to wire into the pattern, in order
+        #[arg(long, value_delimiter = ',', required = true)]
+        functions: Vec<String>,
+        /// Output file for the definition
+        #[arg(long, default_value = "workflow.json")]
+        out: String,
+        /// Overwrite an existing output file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Manage pending manual approval steps
+    Tasks {
+        #[command(subcommand)]
+        cmd: WfTasksCmd,
+    },
 }
 
 #[derive(Subcommand)]
@@ -85,6 +109,61 @@ pub enum WfRunsCmd {
     Get { name: String, id: String },
     /// Cancel a run
     Cancel { name: String, id: String },
+    /// Stream live progress events for a run
+    Watch { name: String, id: String },
+    /// Export a run with every step record for offline debugging
+    Export {
+        name: String,
+        id: String,
+        /// Output file (defaults to stdout)
+        #[arg(long)]
+        out: Option<String>,
+        /// Keep step input/output payloads in the export
+        #[arg(long)]
+        include_payloads: bool,
+    },
+    /// Cancel every run matching the given filters
+    CancelAll {
+        name: String,
+        /// Only cancel runs in this status
+        #[arg(long, default_value = "running")]
+        status: String,
+        /// Only cancel runs started longer ago than this (e.g. 30m, 2h, 1d)
+        #[arg(long)]
+        older_than: Option<String>,
+        /// Maximum number of concurrent cancel requests
+        #[arg(long, default_value = "8")]
+        concurrency: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WfTasksCmd {
+    /// List pending manual tasks
+    List {
+        name: String,
+        /// Only show tasks for this run
+        #[arg(long)]
+        run: Option<String>,
+    },
+    /// Approve a pending task
+    Approve {
+        name: String,
+        /// Task ID
+        id: String,
+        /// Comment recorded on the run history
+        #[arg(long)]
+        comment: Option<String>,
+    },
+    /// Reject a pending task
+    Reject {
+        name: String,
+        /// Task ID
+        id: String,
+        /// Comment recorded on the run history
+        #[arg(long)]
+        comment: Option<String>,
+    },
 }
 
 const WF_COLUMNS: &[Column] = &[
@@ -99,16 +178,227 @@ const RUN_COLUMNS: &[Column] = &[
     Column::new("ID", "id"),
     Column::new("Status", "status"),
     Column::new("Trigger", "trigger_type"),
-    Column::wide("Error", "error_message"),
+    Column::wide("Error", "error_message").max_width(40),
     Column::new("Started", "started_at"),
     Column::wide("Finished", "finished_at"),
 ];
 
+const TASK_COLUMNS: &[Column] = &[
+    Column::new("ID", "id"),
+    Column::new("Run", "run_id"),
+    Column::new("Step", "node_key"),
+    Column::new("Status", "status"),
+    Column::wide("Assignee", "assignee"),
+    Column::wide("Comment", "comment"),
+    Column::new("Requested", "created_at"),
+];
+
 const WF_VERSION_COLUMNS: &[Column] = &[
     Column::new("Version", "version"),
     Column::new("Created", "created_at"),
 ];
 
+/// Builds a definition skeleton for `pattern`.
+///
+/// - `fanout`: the first function runs, then every remaining function runs in
+///   parallel on its output.
+/// - `saga`: functions run in sequence and fail fast (no retries), leaving
+///   compensation to the steps themselves.
+/// - `retry-pipeline`: functions run in sequence, each with exponential
+///   backoff retries.
+fn generate_definition(pattern: &str, functions: &[String]) -> Result<Value> {
+    if functions.len() < 2 {
+        return Err(crate::error::OrbitError::Input(
+            "At least two functions are required to build a workflow pattern".into(),
+        ));
+    }
+    for (i, f) in functions.iter().enumerate() {
+        if functions[..i].contains(f) {
+            return Err(crate::error::OrbitError::Input(format!(
+                "Function '{f}' is listed more than once"
+            )));
+        }
+    }
+
+    let retry_policy = match pattern {
+        "saga" => json!({ "max_attempts": 1, "base_ms": 0, "max_backoff_ms": 0 }),
+        "retry-pipeline" => json!({ "max_attempts": 3, "base_ms": 500, "max_backoff_ms": 10000 }),
+        _ => Value::Null,
+    };
+    let nodes: Vec<Value> = functions
+        .iter()
+        .map(|f| {
+            let mut node = json!({
+                "node_key": f,
+                "node_type": "function",
+                "function_name": f,
+            });
+            if !retry_policy.is_null() {
+                node["retry_policy"] = retry_policy.clone();
+            }
+            node
+        })
+        .collect();
+    let edges: Vec<Value> = match pattern {
+        "fanout" => functions[1..]
+            .iter()
+            .map(|to| json!({ "from": functions[0], "to": to }))
+            .collect(),
+        _ => functions
+            .windows(2)
+            .map(|pair| json!({ "from": pair[0], "to": pair[1] }))
+            .collect(),
+    };
+    Ok(json!({ "nodes": nodes, "edges": edges }))
+}
+
+fn print_run_event(event: &Value, output_format: &str) {
+    if output::is_machine_readable(output_format) {
+        println!("{event}");
+        return;
+    }
+    let field = |key: &str| event.get(key).and_then(|v| v.as_str()).unwrap_or("");
+    let step = match field("node_key") {
+        "" => "run",
+        key => key,
+    };
+    let error = field("error_message");
+    if error.is_empty() {
+        println!("{:<24} {}", step, field("status"));
+    } else {
+        println!("{:<24} {}  {}", step, field("status"), error);
+    }
+}
+
+fn strip_payloads(record: &mut Value) {
+    if let Some(map) = record.as_object_mut() {
+        map.remove("input");
+        map.remove("output");
+    }
+}
+
+async fn run_export(
+    name: String,
+    id: String,
+    out: Option<String>,
+    include_payloads: bool,
+    client: &NovaClient,
+) -> Result<()> {
+    let mut run = client.get(&format!("/workflows/{name}/runs/{id}")).await?;
+    if !include_payloads {
+        strip_payloads(&mut run);
+        if let Some(nodes) = run.get_mut("nodes").and_then(|n| n.as_array_mut()) {
+            for node in nodes.iter_mut() {
+                strip_payloads(node);
+                if let Some(attempts) = node.get_mut("attempts").and_then(|a| a.as_array_mut()) {
+                    attempts.iter_mut().for_each(strip_payloads);
+                }
+            }
+        }
+    }
+
+    let content = serde_json::to_string_pretty(&run)?;
+    match out {
+        Some(path) => {
+            std::fs::write(&path, content)?;
+            let steps = run
+                .get("nodes")
+                .and_then(|n| n.as_array())
+                .map_or(0, |n| n.len());
+            output::print_success(&format!("Exported run '{id}' ({steps} steps) to {path}"));
+        }
+        None => println!("{content}"),
+    }
+    Ok(())
+}
+
+async fn run_cancel_all(
+    name: String,
+    status: String,
+    older_than: Option<String>,
+    concurrency: usize,
+    client: &NovaClient,
+    output_format: &str,
+) -> Result<()> {
+    let cutoff = match older_than {
+        Some(age) => Some(crate::duration::ago(crate::duration::parse(&age)?)?),
+        None => None,
+    };
+
+    let runs = client
+        .get_all(&format!("/workflows/{name}/runs?status={status}"))
+        .await?;
+    let ids: Vec<String> = runs
+        .as_array()
+        .map(|items| items.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.get("status").and_then(|v| v.as_str()) == Some(status.as_str()))
+        .filter(|r| {
+            cutoff
+                .is_none_or(|c| crate::duration::timestamp(r, "started_at").is_some_and(|t| t < c))
+        })
+        .filter_map(|r| r.get("id").and_then(|v| v.as_str()).map(String::from))
+        .collect();
+
+    if ids.is_empty() {
+        output::print_success("No matching runs to cancel.");
+        return Ok(());
+    }
+
+    let progress = output::progress_bar("cancel-runs", Some(ids.len() as u64));
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.cyan} Cancelling runs [{bar:30.cyan/blue}] {pos}/{len}")
+            .unwrap(),
+    );
+
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+    for id in ids {
+        let client = client.clone();
+        let semaphore = semaphore.clone();
+        let path = format!("/workflows/{name}/runs/{id}/cancel");
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = client.post(&path, &json!({})).await;
+            (id, result)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((id, result)) = joined {
+            let (state, error) = match result {
+                Ok(_) => ("cancelled", Value::Null),
+                Err(e) => ("failed", json!(e.to_string())),
+            };
+            results.push(json!({ "id": id, "result": state, "error": error }));
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    let total = results.len();
+    let failed = results.iter().filter(|r| r["result"] == "failed").count();
+    output::render(
+        &Value::Array(results),
+        &[
+            Column::new("ID", "id"),
+            Column::new("Result", "result"),
+            Column::wide("Error", "error").max_width(40),
+        ],
+        output_format,
+    )?;
+    if failed > 0 {
+        return Err(crate::error::OrbitError::Input(format!(
+            "{failed} of {total} runs could not be cancelled"
+        )));
+    }
+    output::print_success(&format!("Cancelled {total} runs."));
+    Ok(())
+}
+
 pub async fn run(cmd: WorkflowsCmd, client: &NovaClient, output_format: &str) -> Result<()> {
     match cmd {
         WorkflowsCmd::Create {
@@ -135,15 +425,15 @@ pub async fn run(cmd: WorkflowsCmd, client: &NovaClient, output_format: &str) ->
                 body["definition"] = parsed;
             }
             let result = client.post("/workflows", &body).await?;
-            output::render_single(&result, WF_COLUMNS, output_format);
+            output::render_single(&result, WF_COLUMNS, output_format)?;
         }
         WorkflowsCmd::List => {
-            let result = client.get("/workflows").await?;
-            output::render(&result, WF_COLUMNS, output_format);
+            let result = client.get_paged("/workflows").await?;
+            output::render(&result, WF_COLUMNS, output_format)?;
         }
         WorkflowsCmd::Get { name } => {
             let result = client.get(&format!("/workflows/{name}")).await?;
-            output::render_single(&result, WF_COLUMNS, output_format);
+            output::render_single(&result, WF_COLUMNS, output_format)?;
         }
         WorkflowsCmd::Update {
             name,
@@ -169,7 +459,7 @@ pub async fn run(cmd: WorkflowsCmd, client: &NovaClient, output_format: &str) ->
                 body["definition"] = parsed;
             }
             let result = client.put(&format!("/workflows/{name}"), &body).await?;
-            output::render_single(&result, WF_COLUMNS, output_format);
+            output::render_single(&result, WF_COLUMNS, output_format)?;
         }
         WorkflowsCmd::Delete { name } => {
             client.delete(&format!("/workflows/{name}")).await?;
@@ -200,17 +490,19 @@ pub async fn run(cmd: WorkflowsCmd, client: &NovaClient, output_format: &str) ->
                 let result = client
                     .post(&format!("/workflows/{name}/versions"), &body)
                     .await?;
-                output::render_single(&result, WF_VERSION_COLUMNS, output_format);
+                output::render_single(&result, WF_VERSION_COLUMNS, output_format)?;
             }
             WfVersionsCmd::List { name } => {
-                let result = client.get(&format!("/workflows/{name}/versions")).await?;
-                output::render(&result, WF_VERSION_COLUMNS, output_format);
+                let result = client
+                    .get_paged(&format!("/workflows/{name}/versions"))
+                    .await?;
+                output::render(&result, WF_VERSION_COLUMNS, output_format)?;
             }
             WfVersionsCmd::Get { name, version } => {
                 let result = client
                     .get(&format!("/workflows/{name}/versions/{version}"))
                     .await?;
-                output::render_single(&result, WF_VERSION_COLUMNS, output_format);
+                output::render_single(&result, WF_VERSION_COLUMNS, output_format)?;
             }
         },
         WorkflowsCmd::Run { name, input } => {
@@ -223,7 +515,7 @@ pub async fn run(cmd: WorkflowsCmd, client: &NovaClient, output_format: &str) ->
             let result = client
                 .post(&format!("/workflows/{name}/run"), &body)
                 .await?;
-            output::render_single(&result, RUN_COLUMNS, output_format);
+            output::render_single(&result, RUN_COLUMNS, output_format)?;
         }
         WorkflowsCmd::InvokeAsync { name, input } => {
             let mut body = json!({});
@@ -236,24 +528,102 @@ pub async fn run(cmd: WorkflowsCmd, client: &NovaClient, output_format: &str) ->
                 .post(&format!("/workflows/{name}/invoke-async"), &body)
                 .await?;
             output::print_success("Workflow invoked asynchronously.");
-            output::render_single(&result, RUN_COLUMNS, output_format);
+            output::render_single(&result, RUN_COLUMNS, output_format)?;
         }
         WorkflowsCmd::Runs { cmd } => match cmd {
             WfRunsCmd::List { name } => {
-                let result = client.get(&format!("/workflows/{name}/runs")).await?;
-                output::render(&result, RUN_COLUMNS, output_format);
+                let result = client.get_paged(&format!("/workflows/{name}/runs")).await?;
+                output::render(&result, RUN_COLUMNS, output_format)?;
             }
             WfRunsCmd::Get { name, id } => {
                 let result = client.get(&format!("/workflows/{name}/runs/{id}")).await?;
-                output::render_single(&result, RUN_COLUMNS, output_format);
+                output::render_single(&result, RUN_COLUMNS, output_format)?;
             }
             WfRunsCmd::Cancel { name, id } => {
                 let result = client
                     .post(&format!("/workflows/{name}/runs/{id}/cancel"), &json!({}))
                     .await?;
                 output::print_success(&format!("Run '{id}' cancelled."));
-                if output_format == "json" || output_format == "yaml" {
-                    output::render_single(&result, RUN_COLUMNS, output_format);
+                if output::is_machine_readable(output_format) {
+                    output::render_single(&result, RUN_COLUMNS, output_format)?;
+                }
+            }
+            WfRunsCmd::Watch { name, id } => {
+                let mut events = client
+                    .stream(&format!("/workflows/{name}/runs/{id}/events"))
+                    .await?;
+                while let Some(event) = events.next().await {
+                    print_run_event(&event?, output_format);
+                }
+            }
+            WfRunsCmd::Export {
+                name,
+                id,
+                out,
+                include_payloads,
+            } => {
+                run_export(name, id, out, include_payloads, client).await?;
+            }
+            WfRunsCmd::CancelAll {
+                name,
+                status,
+                older_than,
+                concurrency,
+            } => {
+                run_cancel_all(name, status, older_than, concurrency, client, output_format)
+                    .await?;
+            }
+        },
+        WorkflowsCmd::New {
+            pattern,
+            functions,
+            out,
+            force,
+        } => {
+            let definition = generate_definition(&pattern, &functions)?;
+            if std::path::Path::new(&out).exists() && !force {
+                return Err(crate::error::OrbitError::Input(format!(
+                    "File '{out}' already exists. Use --force to overwrite."
+                )));
+            }
+            std::fs::write(&out, serde_json::to_string_pretty(&definition)?)?;
+            output::print_success(&format!(
+                "Wrote {pattern} definition to {out}. Create it with: orbit wf create --name <name> --definition-file {out}"
+            ));
+        }
+        WorkflowsCmd::Tasks { cmd } => match cmd {
+            WfTasksCmd::List { name, run } => {
+                let mut path = format!("/workflows/{name}/tasks");
+                if let Some(r) = run {
+                    path = format!("{path}?run_id={r}");
+                }
+                let result = client.get_paged(&path).await?;
+                output::render(&result, TASK_COLUMNS, output_format)?;
+            }
+            WfTasksCmd::Approve { name, id, comment } => {
+                let mut body = json!({});
+                if let Some(c) = comment {
+                    body["comment"] = json!(c);
+                }
+                let result = client
+                    .post(&format!("/workflows/{name}/tasks/{id}/approve"), &body)
+                    .await?;
+                output::print_success(&format!("Task '{id}' approved."));
+                if output::is_machine_readable(output_format) {
+                    output::render_single(&result, TASK_COLUMNS, output_format)?;
+                }
+            }
+            WfTasksCmd::Reject { name, id, comment } => {
+                let mut body = json!({});
+                if let Some(c) = comment {
+                    body["comment"] = json!(c);
+                }
+                let result = client
+                    .post(&format!("/workflows/{name}/tasks/{id}/reject"), &body)
+                    .await?;
+                output::print_success(&format!("Task '{id}' rejected."));
+                if output::is_machine_readable(output_format) {
+                    output::render_single(&result, TASK_COLUMNS, output_format)?;
                 }
             }
         },
@@ -1,13 +1,212 @@
+use crate::passphrase;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct OrbitConfig {
+    /// Context used when `--profile` is not given
+    pub current_context: Option<String>,
     pub server: Option<String>,
     pub api_key: Option<String>,
+    /// The API key lives in the OS keyring rather than in this file
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub api_key_keyring: bool,
     pub tenant: Option<String>,
     pub namespace: Option<String>,
     pub output: Option<String>,
+    /// Request timeout in seconds
+    pub timeout: Option<u64>,
+    /// HTTP(S) proxy URL, optionally with `user:pass@` credentials
+    pub proxy: Option<String>,
+    /// Gzip large request bodies; only for servers that accept
+    /// `Content-Encoding: gzip`
+    pub compress: Option<bool>,
+    /// Client certificate (PEM) for mutual TLS
+    pub client_cert: Option<String>,
+    /// Client private key (PKCS#8 PEM) for mutual TLS
+    pub client_key: Option<String>,
+    /// Extra CA certificate (PEM) trusted for the server
+    pub ca_cert: Option<String>,
+    /// `api-key` (default) or `hmac` to sign requests
+    pub auth_mode: Option<String>,
+    /// Shared secret for `auth_mode = "hmac"`
+    pub hmac_secret: Option<String>,
+    /// Extra `KEY=VALUE` headers sent on every request
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<String>,
+    /// OAuth token obtained by `orbit login`
+    pub token: Option<StoredToken>,
+    /// Change freeze set by `orbit locks set`
+    pub lock: Option<DeployLock>,
+    /// Passphrase encryption of the credentials in this file, set up by
+    /// `orbit config encrypt`
+    pub encryption: Option<ConfigEncryption>,
+    /// `.orbit.toml` files, by path, whose `server` may receive the stored
+    /// credentials, each with the server it was trusted for
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub trusted_projects: BTreeMap<String, String>,
+    /// Named contexts (`[profiles.<name>]`) with the same settings as the
+    /// top level
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, OrbitConfig>,
+}
+
+/// Project-local settings from the nearest `.orbit.toml`, found by walking
+/// up from the working directory. The file is meant to be committed, so it
+/// cannot hold credentials.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Context to use when `--profile` is not given
+    pub context: Option<String>,
+    pub server: Option<String>,
+    pub tenant: Option<String>,
+    pub namespace: Option<String>,
+    pub output: Option<String>,
+    /// Request timeout in seconds
+    pub timeout: Option<u64>,
+}
+
+pub const PROJECT_CONFIG_FILE: &str = ".orbit.toml";
+
+/// Key of a project file in `trusted_projects`: its canonical path.
+pub fn project_key(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+impl ProjectConfig {
+    /// Finds and parses the nearest `.orbit.toml`, returning its path too.
+    pub fn discover() -> crate::error::Result<Option<(PathBuf, Self)>> {
+        let Ok(cwd) = std::env::current_dir() else {
+            return Ok(None);
+        };
+        let Some(path) = cwd
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+            .find(|p| p.is_file())
+        else {
+            return Ok(None);
+        };
+        let content = std::fs::read_to_string(&path)?;
+        let project = toml::from_str(&content).map_err(|e| {
+            crate::error::OrbitError::Config(format!("Invalid {}: {e}", path.display()))
+        })?;
+        Ok(Some((path, project)))
+    }
+}
+
+/// A change freeze: mutating requests are refused until `until` unless the
+/// user overrides the lock with a reason.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployLock {
+    pub until: DateTime<Utc>,
+    pub reason: String,
+    pub set_by: Option<String>,
+    pub set_at: DateTime<Utc>,
+}
+
+impl DeployLock {
+    pub fn is_active(&self) -> bool {
+        self.until > Utc::now()
+    }
+}
+
+/// Parameters of the passphrase encryption of a config file; see
+/// [`crate::passphrase`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigEncryption {
+    /// Argon2id salt (base64)
+    pub salt: String,
+    /// A known value encrypted under the key, to tell a wrong passphrase
+    /// apart before anything is decrypted or written
+    pub check: String,
+}
+
+const ENCRYPTION_CHECK: &str = "orbit";
+
+/// Key derived for the config in this process, with the salt it belongs to.
+static CONFIG_KEY: Mutex<Option<(String, passphrase::Key)>> = Mutex::new(None);
+
+/// Why the config file could not be parsed, if it could not; saving is
+/// refused then, since it would replace the file with defaults.
+static PARSE_ERROR: OnceLock<String> = OnceLock::new();
+
+impl ConfigEncryption {
+    /// Sets up encryption under a new salt and `passphrase`.
+    pub fn new(passphrase: &str) -> crate::error::Result<Self> {
+        let salt = passphrase::new_salt();
+        let key = passphrase::derive_key(passphrase, &salt)?;
+        let check = passphrase::encrypt(&key, ENCRYPTION_CHECK)?;
+        *CONFIG_KEY.lock().unwrap_or_else(|e| e.into_inner()) = Some((salt.clone(), key));
+        Ok(Self { salt, check })
+    }
+
+    /// The key for this file, asking for the passphrase at most once per
+    /// process.
+    fn key(&self) -> crate::error::Result<passphrase::Key> {
+        let mut cached = CONFIG_KEY.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((salt, key)) = cached.as_ref()
+            && *salt == self.salt
+        {
+            return Ok(*key);
+        }
+        let key = passphrase::derive_key(&passphrase::read_passphrase(false)?, &self.salt)?;
+        passphrase::decrypt(&key, &self.check)?;
+        *cached = Some((self.salt.clone(), key));
+        Ok(key)
+    }
+}
+
+/// An OAuth access token together with what is needed to refresh it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredToken {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub token_endpoint: String,
+    pub client_id: String,
+}
+
+impl StoredToken {
+    /// Builds a token from an OAuth token response. A refresh token missing
+    /// from the response falls back to `previous_refresh`.
+    pub fn from_response(
+        resp: &Value,
+        token_endpoint: &str,
+        client_id: &str,
+        previous_refresh: Option<String>,
+    ) -> Option<Self> {
+        let access_token = resp.get("access_token")?.as_str()?.to_string();
+        let refresh_token = resp
+            .get("refresh_token")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or(previous_refresh);
+        let expires_at = resp
+            .get("expires_in")
+            .and_then(|v| v.as_i64())
+            .map(|secs| Utc::now() + chrono::Duration::seconds(secs));
+        Some(Self {
+            access_token,
+            refresh_token,
+            expires_at,
+            token_endpoint: token_endpoint.to_string(),
+            client_id: client_id.to_string(),
+        })
+    }
+
+    /// True when the token expires within the next 30 seconds.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|t| t - chrono::Duration::seconds(30) <= Utc::now())
+    }
 }
 
 impl OrbitConfig {
@@ -15,27 +214,429 @@ impl OrbitConfig {
         let path = Self::config_path();
         if path.exists() {
             let content = std::fs::read_to_string(&path).unwrap_or_default();
-            toml::from_str(&content).unwrap_or_default()
+            let mut config = match Self::parse(&content) {
+                Ok(config) => config,
+                Err(e) => {
+                    let reason = match e {
+                        crate::error::OrbitError::Config(reason) => reason,
+                        e => e.to_string(),
+                    };
+                    if PARSE_ERROR.set(reason.clone()).is_ok() {
+                        crate::output::print_warning(&format!(
+                            "Ignoring {}, fix it with 'orbit config edit': {}",
+                            path.display(),
+                            reason.trim_end()
+                        ));
+                    }
+                    return Self::default();
+                }
+            };
+            // Carrying on without the credentials would let the next save
+            // drop them from the file.
+            if let Err(e) = config.decrypt_credentials() {
+                crate::output::print_error(&e.to_string());
+                std::process::exit(1);
+            }
+            config
         } else {
             Self::default()
         }
     }
 
+    /// Credentials that `config encrypt` protects, in this section and every
+    /// context.
+    pub fn credential_fields(&mut self) -> Vec<&mut String> {
+        let mut fields: Vec<&mut String> = self
+            .api_key
+            .iter_mut()
+            .chain(self.hmac_secret.iter_mut())
+            .collect();
+        if let Some(token) = &mut self.token {
+            fields.push(&mut token.access_token);
+            fields.extend(token.refresh_token.as_mut());
+        }
+        for ctx in self.profiles.values_mut() {
+            fields.extend(ctx.credential_fields());
+        }
+        fields
+    }
+
+    fn decrypt_credentials(&mut self) -> crate::error::Result<()> {
+        let Some(encryption) = self.encryption.clone() else {
+            return Ok(());
+        };
+        let mut fields = self.credential_fields();
+        fields.retain(|v| passphrase::is_encrypted(v));
+        if fields.is_empty() {
+            return Ok(());
+        }
+        let key = encryption.key()?;
+        for value in fields {
+            *value = passphrase::decrypt(&key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Fills in the API key of context `name` (or the top level) if it is
+    /// kept in the OS keyring.
+    fn load_keyring_api_key(&mut self, name: Option<&str>) {
+        if !self.api_key_keyring || self.api_key.is_some() {
+            return;
+        }
+        let key = keyring::Entry::new(KEYRING_SERVICE, &keyring_user(name))
+            .and_then(|e| e.get_password());
+        match key {
+            Ok(key) => self.api_key = Some(key),
+            Err(e) => crate::output::print_warning(&format!(
+                "Cannot read the API key for {} from the OS keyring: {e}",
+                name.map_or("the default context".into(), |n| format!("context '{n}'"))
+            )),
+        }
+    }
+
+    /// Parses a config file, rejecting values orbit would fail on later.
+    pub fn parse(content: &str) -> crate::error::Result<Self> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| crate::error::OrbitError::Config(e.to_string()))?;
+        let sections = std::iter::once(("(top level)", &config))
+            .chain(config.profiles.iter().map(|(n, c)| (n.as_str(), c)));
+        for (name, section) in sections {
+            if let Some(mode) = section.auth_mode.as_deref()
+                && mode != "api-key"
+                && mode != "hmac"
+            {
+                return Err(crate::error::OrbitError::Config(format!(
+                    "{name}: invalid auth_mode '{mode}': expected api-key or hmac"
+                )));
+            }
+        }
+        if let Some(name) = config
+            .profiles
+            .iter()
+            .find_map(|(n, c)| (!c.profiles.is_empty()).then_some(n))
+        {
+            return Err(crate::error::OrbitError::Config(format!(
+                "{name}: contexts cannot be nested"
+            )));
+        }
+        if let Some(current) = &config.current_context
+            && !config.profiles.contains_key(current)
+        {
+            return Err(crate::error::OrbitError::Config(format!(
+                "current_context '{current}' does not exist"
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Name of the context in use: `profile` if given, else the current one.
+    pub fn context_name(&self, profile: Option<&str>) -> Option<String> {
+        profile
+            .map(String::from)
+            .or_else(|| self.current_context.clone())
+    }
+
+    /// Layers the settings of context `name` over the top-level ones.
+    /// Credentials are never inherited, since they belong to one server.
+    /// Only the resolved API key is read from the OS keyring, so contexts
+    /// not in use never trigger a keyring prompt.
+    pub fn resolve(mut self, name: Option<&str>) -> crate::error::Result<Self> {
+        let Some(name) = name else {
+            self.load_keyring_api_key(None);
+            return Ok(self);
+        };
+        let ctx = self.profiles.remove(name).ok_or_else(|| {
+            crate::error::OrbitError::Config(format!(
+                "Unknown context '{name}'. Run 'orbit config get-contexts' to list them."
+            ))
+        })?;
+        self.server = ctx.server.or(self.server);
+        self.tenant = ctx.tenant.or(self.tenant);
+        self.namespace = ctx.namespace.or(self.namespace);
+        self.output = ctx.output.or(self.output);
+        self.timeout = ctx.timeout.or(self.timeout);
+        self.proxy = ctx.proxy.or(self.proxy);
+        self.compress = ctx.compress.or(self.compress);
+        self.client_cert = ctx.client_cert.or(self.client_cert);
+        self.client_key = ctx.client_key.or(self.client_key);
+        self.ca_cert = ctx.ca_cert.or(self.ca_cert);
+        self.auth_mode = ctx.auth_mode.or(self.auth_mode);
+        self.headers.extend(ctx.headers);
+        self.api_key = ctx.api_key;
+        self.api_key_keyring = ctx.api_key_keyring;
+        self.hmac_secret = ctx.hmac_secret;
+        self.token = ctx.token;
+        self.load_keyring_api_key(Some(name));
+        Ok(self)
+    }
+
+    /// Drops credentials and local state so the config can be shared:
+    /// API keys, HMAC secrets, login tokens, locks, proxy passwords and
+    /// credential-bearing headers.
+    pub fn redact(&mut self) {
+        self.api_key = None;
+        self.api_key_keyring = false;
+        self.hmac_secret = None;
+        self.token = None;
+        self.lock = None;
+        if let Some(proxy) = &self.proxy
+            && let Ok(mut url) = reqwest::Url::parse(proxy)
+            && url.password().is_some()
+        {
+            let _ = url.set_password(None);
+            self.proxy = Some(url.to_string());
+        }
+        self.headers.retain(|h| {
+            h.split_once('=')
+                .is_some_and(|(name, _)| !crate::trace::is_sensitive(name.trim()))
+        });
+        for ctx in self.profiles.values_mut() {
+            ctx.redact();
+        }
+    }
+
+    /// Merges an imported config into this one. Settings present in `other`
+    /// win, everything else (including contexts missing from `other`) is
+    /// kept. The current context is only taken over if none is set, and
+    /// tokens and locks are never imported.
+    pub fn merge(&mut self, other: OrbitConfig) {
+        fn take<T>(dst: &mut Option<T>, src: Option<T>) {
+            if src.is_some() {
+                *dst = src;
+            }
+        }
+        if self.current_context.is_none() {
+            self.current_context = other.current_context;
+        }
+        take(&mut self.server, other.server);
+        if other.api_key.is_some() {
+            self.api_key = other.api_key;
+            self.api_key_keyring = false;
+        }
+        take(&mut self.tenant, other.tenant);
+        take(&mut self.namespace, other.namespace);
+        take(&mut self.output, other.output);
+        take(&mut self.timeout, other.timeout);
+        take(&mut self.proxy, other.proxy);
+        take(&mut self.compress, other.compress);
+        take(&mut self.client_cert, other.client_cert);
+        take(&mut self.client_key, other.client_key);
+        take(&mut self.ca_cert, other.ca_cert);
+        take(&mut self.auth_mode, other.auth_mode);
+        take(&mut self.hmac_secret, other.hmac_secret);
+        for header in other.headers {
+            let name = header.split_once('=').map_or(header.as_str(), |(n, _)| n);
+            let name = name.trim().to_ascii_lowercase();
+            self.headers.retain(|h| {
+                h.split_once('=')
+                    .is_none_or(|(n, _)| n.trim().to_ascii_lowercase() != name)
+            });
+            self.headers.push(header);
+        }
+        for (name, ctx) in other.profiles {
+            self.profiles.entry(name).or_default().merge(ctx);
+        }
+    }
+
+    /// Whether the stored credentials may go to the server set by the
+    /// project file at `path`: it is the configured server, or the user
+    /// trusted the file for it with `config trust-project`.
+    pub fn trusts_project(&self, project: &ProjectConfig, path: Option<&Path>) -> bool {
+        let Some(server) = &project.server else {
+            return true;
+        };
+        let same = |s: &str| s.trim_end_matches('/') == server.trim_end_matches('/');
+        self.server.as_deref().is_some_and(same)
+            || path
+                .and_then(|p| self.trusted_projects.get(&project_key(p)))
+                .is_some_and(|s| same(s))
+    }
+
+    /// True when an API key, HMAC secret or login token is configured.
+    pub fn has_credentials(&self) -> bool {
+        self.api_key.is_some() || self.hmac_secret.is_some() || self.token.is_some()
+    }
+
+    /// Layers project-local settings over the global ones. Like contexts,
+    /// an untrusted project file pointing at another server does not
+    /// inherit the credentials.
+    pub fn apply_project(mut self, project: ProjectConfig, path: Option<&Path>) -> Self {
+        if !self.trusts_project(&project, path) {
+            self.api_key = None;
+            self.api_key_keyring = false;
+            self.hmac_secret = None;
+            self.token = None;
+        }
+        self.server = project.server.or(self.server);
+        self.tenant = project.tenant.or(self.tenant);
+        self.namespace = project.namespace.or(self.namespace);
+        self.output = project.output.or(self.output);
+        self.timeout = project.timeout.or(self.timeout);
+        self
+    }
+
+    /// The section writes for context `name` go to: that context, created
+    /// if missing, or the top level.
+    pub fn section_mut(&mut self, name: Option<&str>) -> &mut OrbitConfig {
+        match name {
+            Some(n) => self.profiles.entry(n.to_string()).or_default(),
+            None => self,
+        }
+    }
+
     pub fn save(&self) -> crate::error::Result<()> {
         let path = Self::config_path();
+        if PARSE_ERROR.get().is_some() {
+            return Err(crate::error::OrbitError::Config(format!(
+                "Not overwriting {}, which cannot be parsed; fix it with 'orbit config edit' first",
+                path.display()
+            )));
+        }
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        let content = toml::to_string_pretty(self)
+        // Keys resolved from the keyring must not end up in the file.
+        let mut table = toml::Table::try_from(self)
+            .map_err(|e| crate::error::OrbitError::Config(e.to_string()))?;
+        strip_keyring_secrets(&mut table);
+        if let Some(encryption) = &self.encryption {
+            encrypt_credentials(&mut table, &encryption.key()?)?;
+        }
+        let content = toml::to_string_pretty(&table)
             .map_err(|e| crate::error::OrbitError::Config(e.to_string()))?;
         std::fs::write(&path, content)?;
         Ok(())
     }
 
-    fn config_path() -> PathBuf {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".orbit")
-            .join("config.toml")
+    /// Location of the config file, in order of precedence: `--config` or
+    /// `ORBIT_CONFIG`, `$XDG_CONFIG_HOME/orbit/config.toml`, then the legacy
+    /// `~/.orbit/config.toml` while it is the only one present. New configs
+    /// are created at the XDG location.
+    pub fn config_path() -> PathBuf {
+        if let Some(path) = CONFIG_PATH_OVERRIDE.get() {
+            return path.clone();
+        }
+        let xdg = xdg_config_path();
+        let legacy = legacy_config_path();
+        if !xdg.exists() && legacy.exists() {
+            legacy
+        } else {
+            xdg
+        }
     }
 }
+
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Points every later config load and save at `path`. Set once at startup
+/// from `--config` / `ORBIT_CONFIG`.
+pub fn set_config_path(path: PathBuf) {
+    let _ = CONFIG_PATH_OVERRIDE.set(path);
+}
+
+/// `$XDG_CONFIG_HOME/orbit/config.toml`, with `XDG_CONFIG_HOME` defaulting to
+/// `~/.config` (relative values are ignored, as the spec requires).
+pub fn xdg_config_path() -> PathBuf {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .unwrap_or_else(|| home_dir().join(".config"));
+    base.join("orbit").join("config.toml")
+}
+
+/// Where configs lived before XDG support; see `orbit config migrate`.
+pub fn legacy_config_path() -> PathBuf {
+    orbit_dir().join("config.toml")
+}
+
+const KEYRING_SERVICE: &str = "orbit";
+
+fn strip_keyring_secrets(section: &mut toml::Table) {
+    if section.contains_key("api_key_keyring") {
+        section.remove("api_key");
+    }
+    if let Some(profiles) = section.get_mut("profiles").and_then(|p| p.as_table_mut()) {
+        for (_, ctx) in profiles.iter_mut() {
+            if let Some(ctx) = ctx.as_table_mut() {
+                strip_keyring_secrets(ctx);
+            }
+        }
+    }
+}
+
+/// Encrypts the credentials `credential_fields` covers, in serialized form.
+fn encrypt_credentials(
+    section: &mut toml::Table,
+    key: &passphrase::Key,
+) -> crate::error::Result<()> {
+    let encrypt = |table: &mut toml::Table, fields: &[&str]| -> crate::error::Result<()> {
+        for field in fields {
+            if let Some(toml::Value::String(value)) = table.get_mut(*field)
+                && !passphrase::is_encrypted(value)
+            {
+                *value = passphrase::encrypt(key, value)?;
+            }
+        }
+        Ok(())
+    };
+    encrypt(section, &["api_key", "hmac_secret"])?;
+    if let Some(token) = section.get_mut("token").and_then(|t| t.as_table_mut()) {
+        encrypt(token, &["access_token", "refresh_token"])?;
+    }
+    if let Some(profiles) = section.get_mut("profiles").and_then(|p| p.as_table_mut()) {
+        for (_, ctx) in profiles.iter_mut() {
+            if let Some(ctx) = ctx.as_table_mut() {
+                encrypt_credentials(ctx, key)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Keyring user holding the API key of context `name` (or the top level).
+/// Contexts are namespaced so one named "default" keeps its own entry.
+fn keyring_user(name: Option<&str>) -> String {
+    name.map_or_else(|| "default".to_string(), |n| format!("context:{n}"))
+}
+
+/// Keyring entry for the API key of context `name` (or the top level).
+fn keyring_entry(name: Option<&str>) -> crate::error::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, &keyring_user(name))
+        .map_err(|e| crate::error::OrbitError::Config(format!("OS keyring unavailable: {e}")))
+}
+
+/// Stores `key` in the OS keyring as the API key of context `name`.
+pub fn store_keyring_api_key(name: Option<&str>, key: &str) -> crate::error::Result<()> {
+    keyring_entry(name)?
+        .set_password(key)
+        .map_err(|e| crate::error::OrbitError::Config(format!("Cannot write to OS keyring: {e}")))
+}
+
+/// Moves the keyring API key of context `old` to context `new`.
+pub fn rename_keyring_api_key(old: &str, new: &str) -> crate::error::Result<()> {
+    let key = keyring_entry(Some(old))?.get_password().map_err(|e| {
+        crate::error::OrbitError::Config(format!("Cannot read API key from OS keyring: {e}"))
+    })?;
+    store_keyring_api_key(Some(new), &key)?;
+    delete_keyring_api_key(Some(old))
+}
+
+/// Removes the API key of context `name` from the OS keyring, if present.
+pub fn delete_keyring_api_key(name: Option<&str>) -> crate::error::Result<()> {
+    match keyring_entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(crate::error::OrbitError::Config(format!(
+            "Cannot remove API key from OS keyring: {e}"
+        ))),
+    }
+}
+
+fn home_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Directory holding orbit's local state (`~/.orbit`): cache, lock override
+/// log, and the legacy config file.
+pub fn orbit_dir() -> PathBuf {
+    home_dir().join(".orbit")
+}
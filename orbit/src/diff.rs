@@ -0,0 +1,150 @@
+//! Line diffs of text, rendered like `diff -u`.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// The lines of `old` and `new` as a shortest edit script (Myers' algorithm).
+pub fn lines<'a>(old: &'a str, new: &'a str) -> Vec<Line<'a>> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+    if max == 0 {
+        return Vec::new();
+    }
+    let at = |k: isize| (k + max) as usize;
+
+    // Furthest x reached on each diagonal k = x - y, kept for every edit
+    // distance d to walk the path back.
+    let mut v = vec![0isize; 2 * max as usize + 2];
+    let mut trace = Vec::new();
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                v[at(k + 1)]
+            } else {
+                v[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut script = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[at(prev_k)];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            script.push(Line::Same(a[x as usize - 1]));
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                script.push(Line::Added(b[y as usize - 1]));
+                y -= 1;
+            } else {
+                script.push(Line::Removed(a[x as usize - 1]));
+                x -= 1;
+            }
+        }
+    }
+    script.reverse();
+    script
+}
+
+/// `old` against `new` as a unified diff with `context` lines around each
+/// change, or `None` when their lines are the same.
+pub fn unified(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    context: usize,
+) -> Option<String> {
+    let script = lines(old, new);
+    let changes: Vec<usize> = script
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, Line::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+    if changes.is_empty() {
+        return None;
+    }
+
+    // Line numbers in old and new before each entry of the script.
+    let mut positions = Vec::with_capacity(script.len() + 1);
+    let (mut o, mut n) = (0, 0);
+    for line in &script {
+        positions.push((o, n));
+        match line {
+            Line::Same(_) => (o, n) = (o + 1, n + 1),
+            Line::Removed(_) => o += 1,
+            Line::Added(_) => n += 1,
+        }
+    }
+    positions.push((o, n));
+
+    let mut out = format!("--- {old_label}\n+++ {new_label}\n");
+    let mut i = 0;
+    while i < changes.len() {
+        // Changes closer than twice the context share a hunk.
+        let first = changes[i].saturating_sub(context);
+        let mut last = changes[i];
+        while i + 1 < changes.len() && changes[i + 1] <= last + 2 * context {
+            i += 1;
+            last = changes[i];
+        }
+        i += 1;
+        let end = (last + 1 + context).min(script.len());
+        let (old_from, new_from) = positions[first];
+        let (old_to, new_to) = positions[end];
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range(old_from, old_to - old_from),
+            range(new_from, new_to - new_from)
+        ));
+        for line in &script[first..end] {
+            let (sign, text) = match line {
+                Line::Same(t) => (' ', t),
+                Line::Removed(t) => ('-', t),
+                Line::Added(t) => ('+', t),
+            };
+            out.push(sign);
+            out.push_str(text);
+            out.push('\n');
+        }
+    }
+    Some(out)
+}
+
+/// A hunk header range: the first line (the one before, when empty) and the
+/// line count.
+fn range(from: usize, count: usize) -> String {
+    match count {
+        0 => format!("{from},0"),
+        1 => format!("{}", from + 1),
+        _ => format!("{},{count}", from + 1),
+    }
+}
@@ -0,0 +1,88 @@
+use crate::error::{OrbitError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+
+/// Parses a compact duration such as `30s`, `15m`, `2h` or `90d`. A bare
+/// number is taken as hours. Durations chrono cannot represent are rejected.
+pub fn parse(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (num, unit) = input.split_at(split);
+    let n: i64 = num
+        .parse()
+        .map_err(|_| OrbitError::Input(format!("Invalid duration '{input}'")))?;
+    let duration = match unit {
+        "s" => Duration::try_seconds(n),
+        "m" => Duration::try_minutes(n),
+        "h" | "" => Duration::try_hours(n),
+        "d" => Duration::try_days(n),
+        "w" => Duration::try_weeks(n),
+        _ => {
+            return Err(OrbitError::Input(format!(
+                "Invalid duration unit in '{input}'. Use s, m, h, d or w."
+            )));
+        }
+    };
+    duration.ok_or_else(|| OrbitError::Input(format!("Duration '{input}' is too long")))
+}
+
+/// The time `duration` before now, failing when that predates what chrono
+/// can represent.
+pub fn ago(duration: Duration) -> Result<DateTime<Utc>> {
+    Utc::now()
+        .checked_sub_signed(duration)
+        .ok_or_else(|| OrbitError::Input("Duration reaches too far into the past".into()))
+}
+
+/// The time `duration` from now, failing when that is beyond what chrono
+/// can represent.
+pub fn from_now(duration: Duration) -> Result<DateTime<Utc>> {
+    Utc::now()
+        .checked_add_signed(duration)
+        .ok_or_else(|| OrbitError::Input("Duration reaches too far into the future".into()))
+}
+
+/// Reads `field` from `item` as an RFC 3339 timestamp.
+pub fn timestamp(item: &Value, field: &str) -> Option<DateTime<Utc>> {
+    item.get(field)
+        .and_then(|v| v.as_str())
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Formats `t` relative to `now` in its largest unit, e.g. `3m ago` or
+/// `in 2d`.
+pub fn relative(t: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - t).num_seconds();
+    let (n, unit) = match secs.abs() {
+        s if s < 60 => (s, "s"),
+        s if s < 3600 => (s / 60, "m"),
+        s if s < 86400 => (s / 3600, "h"),
+        s => (s / 86400, "d"),
+    };
+    match secs {
+        0 => "just now".to_string(),
+        s if s > 0 => format!("{n}{unit} ago"),
+        _ => format!("in {n}{unit}"),
+    }
+}
+
+/// Formats an elapsed duration with its two most significant units, e.g.
+/// `3d 4h` or `12m 5s`.
+pub fn format_age(age: Duration) -> String {
+    let secs = age.num_seconds().max(0);
+    let (d, h, m, s) = (
+        secs / 86400,
+        secs % 86400 / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+    );
+    match (d, h, m) {
+        (0, 0, 0) => format!("{s}s"),
+        (0, 0, _) => format!("{m}m {s}s"),
+        (0, _, _) => format!("{h}h {m}m"),
+        _ => format!("{d}d {h}h"),
+    }
+}
@@ -0,0 +1,96 @@
+//! Per-row follow-up requests for list views.
+//!
+//! Some columns aren't in a list response and need one request per row
+//! (a function's cost, its scaling policy, ...). An [`Enrichment`] names the
+//! request to make for a row and the field its response is stored under;
+//! [`apply`] runs them all concurrently behind a progress bar, and
+//! [`columns`] appends the enrichments' columns to a view's own.
+
+use crate::client::NovaClient;
+use crate::error::OrbitError;
+use crate::output::{self, Column};
+use indicatif::ProgressStyle;
+use serde_json::Value;
+
+/// Follow-up requests in flight at once.
+const CONCURRENCY: usize = 8;
+
+pub struct Enrichment {
+    /// What is being fetched, for the progress bar and warnings
+    pub label: &'static str,
+    /// Field of the row the response is stored under
+    pub field: &'static str,
+    /// Request path for a row, or `None` to leave the row alone
+    pub path: fn(&Value) -> Option<String>,
+    /// Columns showing the fetched data, with paths under `field`
+    pub columns: &'static [Column],
+}
+
+/// `base` followed by the columns of every enrichment.
+pub fn columns(base: &[Column], enrichments: &[&Enrichment]) -> Vec<Column> {
+    base.iter()
+        .chain(enrichments.iter().flat_map(|e| e.columns))
+        .copied()
+        .collect()
+}
+
+/// Fetches every enrichment for every row of `rows` (an array; anything
+/// else is left untouched) and stores the responses in the rows. Rows whose
+/// request fails or finds nothing get no value, and one warning per
+/// enrichment reports how many failed.
+pub async fn apply(client: &NovaClient, rows: &mut Value, enrichments: &[&Enrichment]) {
+    let Some(items) = rows.as_array_mut() else {
+        return;
+    };
+    let tasks: Vec<(usize, usize, String)> = enrichments
+        .iter()
+        .enumerate()
+        .flat_map(|(e, enrichment)| {
+            items
+                .iter()
+                .enumerate()
+                .filter_map(move |(row, item)| Some((row, e, (enrichment.path)(item)?)))
+        })
+        .collect();
+    if tasks.is_empty() {
+        return;
+    }
+
+    let labels: Vec<&str> = enrichments.iter().map(|e| e.label).collect();
+    let progress = output::progress_bar("enrich", Some(tasks.len() as u64));
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.cyan} Fetching {msg} [{bar:30.cyan/blue}] {pos}/{len}")
+            .unwrap(),
+    );
+    progress.set_message(labels.join(", "));
+    let results = client
+        .batch(tasks, CONCURRENCY, |client, (_, _, path)| {
+            let progress = &progress;
+            async move {
+                let result = client.get(&path).await;
+                progress.inc(1);
+                result
+            }
+        })
+        .await;
+    progress.finish_and_clear();
+
+    let mut failed = vec![0usize; enrichments.len()];
+    for ((row, e, _), result) in results {
+        match result {
+            Ok(value) => items[row][enrichments[e].field] = value,
+            // Nothing to show for this row, e.g. no scaling policy.
+            Err(OrbitError::Api { status: 404, .. }) => {}
+            Err(_) => failed[e] += 1,
+        }
+    }
+    for (enrichment, failed) in enrichments.iter().zip(failed) {
+        if failed > 0 {
+            output::print_warning(&format!(
+                "Could not fetch {} for {failed} row(s).",
+                enrichment.label
+            ));
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Client-side envelope encryption of function environment variables.
+//!
+//! Values are sealed to the tenant's X25519 public key (libsodium sealed
+//! box: ephemeral key agreement plus XSalsa20-Poly1305), so only the runtime
+//! holding the tenant's private key can read them. A sealed value is stored
+//! as:
+//!
+//! ```text
+//! enc:v1:<KEY_ID>:<BASE64(SEALED_BOX)>
+//! ```
+
+use crate::client::NovaClient;
+use crate::error::{OrbitError, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use crypto_box::PublicKey;
+use crypto_box::aead::OsRng;
+
+const ALGORITHM: &str = "x25519-sealedbox";
+const PREFIX: &str = "enc:v1:";
+
+pub struct TenantKey {
+    key_id: String,
+    public_key: PublicKey,
+}
+
+impl TenantKey {
+    /// Fetches the public half of the tenant's environment encryption key.
+    pub async fn fetch(client: &NovaClient) -> Result<Self> {
+        let tenant = client.tenant().unwrap_or("default");
+        let resp = client.get(&format!("/tenants/{tenant}/public-key")).await?;
+        let algorithm = resp["algorithm"].as_str().unwrap_or(ALGORITHM);
+        if algorithm != ALGORITHM {
+            return Err(OrbitError::Input(format!(
+                "Unsupported tenant key algorithm '{algorithm}' (expected {ALGORITHM})"
+            )));
+        }
+        let key_id = resp["key_id"]
+            .as_str()
+            .ok_or_else(|| OrbitError::api(500, "Public key response missing 'key_id'"))?
+            .to_string();
+        let bytes = resp["public_key"]
+            .as_str()
+            .and_then(|k| STANDARD.decode(k).ok())
+            .ok_or_else(|| OrbitError::api(500, "Public key response has no valid 'public_key'"))?;
+        let public_key = PublicKey::from_slice(&bytes)
+            .map_err(|_| OrbitError::api(500, "Tenant public key must be 32 bytes"))?;
+        Ok(Self { key_id, public_key })
+    }
+
+    /// Seals `plaintext` into the `enc:v1:` wire format.
+    pub fn seal(&self, plaintext: &str) -> Result<String> {
+        let sealed = self
+            .public_key
+            .seal(&mut OsRng, plaintext.as_bytes())
+            .map_err(|_| OrbitError::Input("Encryption failed".into()))?;
+        Ok(format!(
+            "{PREFIX}{}:{}",
+            self.key_id,
+            STANDARD.encode(sealed)
+        ))
+    }
+}
@@ -3,10 +3,23 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum OrbitError {
     #[error("HTTP request failed: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(reqwest::Error),
+
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
+    #[error("WebSocket error: {0}")]
+    WebSocket(String),
 
     #[error("API error ({status}): {message}")]
-    Api { status: u16, message: String },
+    Api {
+        status: u16,
+        message: String,
+        /// Machine-readable error code from the body, if any
+        code: Option<String>,
+        /// Remediation hints from the body, shown by `--explain`
+        hints: Vec<String>,
+    },
 
     #[error("Configuration error: {0}")]
     Config(String),
@@ -14,6 +27,10 @@ pub enum OrbitError {
     #[error("Invalid input: {0}")]
     Input(String),
 
+    /// A long-running operation that failed or cannot finish on its own.
+    #[error("Operation did not complete: {0}")]
+    Operation(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -26,6 +43,19 @@ impl OrbitError {
         Self::Api {
             status,
             message: message.into(),
+            code: None,
+            hints: Vec::new(),
+        }
+    }
+}
+
+impl From<reqwest::Error> for OrbitError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            let target = e.url().map(|u| u.to_string()).unwrap_or_default();
+            Self::Timeout(target)
+        } else {
+            Self::Http(e)
         }
     }
 }
@@ -0,0 +1,218 @@
+//! `--explain`: guidance for failed commands, from a bundled catalog keyed
+//! by HTTP status and server error code, plus any hints the server put in
+//! the error body.
+
+use crate::error::OrbitError;
+
+struct Entry {
+    status: u16,
+    /// Server error code; `None` matches any code for `status`.
+    code: Option<&'static str>,
+    cause: &'static str,
+    limits: Option<&'static str>,
+    next: &'static [&'static str],
+}
+
+/// Checked in order, so code-specific entries come before the generic entry
+/// for their status.
+const CATALOG: &[Entry] = &[
+    Entry {
+        status: 400,
+        code: None,
+        cause: "The server rejected the request's parameters or body.",
+        limits: None,
+        next: &[
+            "orbit <command> --help",
+            "Re-run with -vvv to see the request body sent",
+        ],
+    },
+    Entry {
+        status: 401,
+        code: None,
+        cause: "No credentials were sent, or the API key or login token is invalid or expired.",
+        limits: None,
+        next: &["orbit whoami", "orbit login", "orbit config get"],
+    },
+    Entry {
+        status: 403,
+        code: None,
+        cause: "You are authenticated, but your role lacks the permission this command needs, or the resource belongs to another tenant.",
+        limits: None,
+        next: &["orbit rbac my-permissions", "orbit whoami"],
+    },
+    Entry {
+        status: 404,
+        code: None,
+        cause: "The resource does not exist, or it lives in a different tenant or namespace than the one selected.",
+        limits: None,
+        next: &[
+            "orbit functions list",
+            "orbit whoami",
+            "Pass --tenant/--namespace if the resource is elsewhere",
+        ],
+    },
+    Entry {
+        status: 409,
+        code: None,
+        cause: "The resource already exists, or it was changed by someone else since you read it.",
+        limits: None,
+        next: &[
+            "orbit functions get <name>",
+            "Re-run the command against the current state",
+        ],
+    },
+    Entry {
+        status: 413,
+        code: None,
+        cause: "The request body is larger than the server accepts.",
+        limits: Some(
+            "Function code and invocation payloads are capped by the server's request size limit.",
+        ),
+        next: &["Upload large code as an archive or layer: orbit layers --help"],
+    },
+    Entry {
+        status: 422,
+        code: None,
+        cause: "The request was well-formed but failed validation (e.g. an unknown runtime or out-of-range value).",
+        limits: None,
+        next: &["orbit runtimes list", "orbit <command> --help"],
+    },
+    Entry {
+        status: 423,
+        code: None,
+        cause: "A change freeze is blocking mutating requests.",
+        limits: None,
+        next: &["orbit locks show"],
+    },
+    Entry {
+        status: 429,
+        code: Some("quota_exceeded"),
+        cause: "The tenant has used up a quota (functions, memory, invocations, ...).",
+        limits: Some("Quotas are set per tenant by an administrator."),
+        next: &[
+            "orbit tenants usage <tenant>",
+            "orbit tenants quotas --help",
+        ],
+    },
+    Entry {
+        status: 429,
+        code: Some("cap_exceeded"),
+        cause: "The function reached its daily invocation or spend cap.",
+        limits: Some("Caps reset at midnight UTC."),
+        next: &["orbit functions caps get <name>"],
+    },
+    Entry {
+        status: 429,
+        code: Some("throttled"),
+        cause: "The function is under a temporary throttle.",
+        limits: None,
+        next: &["orbit functions throttle status <name>"],
+    },
+    Entry {
+        status: 429,
+        code: None,
+        cause: "Too many requests; the server's rate limit was hit.",
+        limits: Some("Wait for the retry-after period shown in the error before retrying."),
+        next: &["orbit rate-limit get"],
+    },
+    Entry {
+        status: 500,
+        code: None,
+        cause: "The server failed while handling the request.",
+        limits: None,
+        next: &[
+            "orbit health",
+            "Re-run with -vv and share the X-Request-Id with the operators",
+        ],
+    },
+    Entry {
+        status: 502,
+        code: None,
+        cause: "The gateway could not reach the control plane, or a backend failed.",
+        limits: None,
+        next: &["orbit health", "orbit backends"],
+    },
+    Entry {
+        status: 503,
+        code: None,
+        cause: "The server is overloaded, starting up or in maintenance.",
+        limits: None,
+        next: &["orbit health", "orbit cluster --help"],
+    },
+    Entry {
+        status: 504,
+        code: None,
+        cause: "The gateway timed out waiting for the control plane or the function.",
+        limits: Some("Invocations are bounded by the function's timeout."),
+        next: &[
+            "orbit functions get <name>",
+            "Use async invocation for long-running work",
+        ],
+    },
+];
+
+fn lookup(status: u16, code: Option<&str>) -> Option<&'static Entry> {
+    CATALOG
+        .iter()
+        .filter(|e| e.status == status)
+        .find(|e| e.code.is_none() || e.code == code)
+}
+
+/// Prints guidance for `error` to stderr; prints nothing for errors the
+/// catalog doesn't cover and that carry no server hints.
+pub fn print(error: &OrbitError) {
+    let (cause, limits, next, hints): (&str, Option<&str>, &[&str], &[String]) = match error {
+        OrbitError::Api {
+            status,
+            code,
+            hints,
+            ..
+        } => match lookup(*status, code.as_deref()) {
+            Some(e) => (e.cause, e.limits, e.next, hints),
+            None if !hints.is_empty() => ("", None, &[], hints),
+            None => return,
+        },
+        OrbitError::Timeout(_) => (
+            "The server did not answer within the request timeout.",
+            None,
+            &[
+                "Raise it with --request-timeout",
+                "Use --wait for long operations",
+                "orbit health",
+            ],
+            &[],
+        ),
+        OrbitError::Http(_) => (
+            "The server could not be reached.",
+            None,
+            &["orbit doctor", "Check --server, proxy and TLS settings"],
+            &[],
+        ),
+        OrbitError::Config(_) => (
+            "The local configuration is missing or invalid.",
+            None,
+            &["orbit config get", "orbit init"],
+            &[],
+        ),
+        _ => return,
+    };
+    eprintln!();
+    if !cause.is_empty() {
+        eprintln!("Likely cause: {cause}");
+    }
+    if let Some(limits) = limits {
+        eprintln!("Limits: {limits}");
+    }
+    if !hints.is_empty() {
+        eprintln!("Server hints:");
+        for hint in hints {
+            eprintln!("  - {hint}");
+        }
+    }
+    if !next.is_empty() {
+        eprintln!("Next steps:");
+        for step in next {
+            eprintln!("  {step}");
+        }
+    }
+}
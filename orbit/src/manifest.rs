@@ -0,0 +1,400 @@
+//! Project manifest (`nova.yaml`) describing the resources `orbit deploy`
+//! manages, and the single-function manifests (`function.toml` or
+//! `nova.yaml`) `orbit functions deploy` reads from a function directory.
+//!
+//! In a monorepo the project manifest lists `workspace` directory globs
+//! instead of (or besides) inline functions; each matching directory with a
+//! function manifest is deployed as a packaged function.
+//!
+//! Besides functions a project can declare the `layers` they use and the
+//! gateway `routes` and topic `subscriptions` pointing at them; `orbit
+//! deploy` applies each after what it depends on.
+//!
+//! Unknown keys are rejected rather than ignored, so a misspelled setting
+//! does not silently deploy with its default.
+
+use crate::error::{OrbitError, Result};
+use crate::package;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_MANIFEST: &str = "nova.yaml";
+
+/// Workspace used when there is no project manifest: one function per
+/// directory under `functions/`.
+pub const DEFAULT_WORKSPACE: &str = "functions/*";
+
+/// Manifests looked for in a function directory, in order.
+pub const FUNCTION_MANIFESTS: &[&str] = &["function.toml", DEFAULT_MANIFEST];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectManifest {
+    #[serde(default)]
+    pub functions: Vec<FunctionSpec>,
+    /// Directory globs (e.g. `functions/*`), relative to the manifest, of
+    /// function directories deployed along with `functions`
+    #[serde(default)]
+    pub workspace: Vec<String>,
+    #[serde(default)]
+    pub layers: Vec<LayerSpec>,
+    #[serde(default)]
+    pub routes: Vec<RouteSpec>,
+    #[serde(default)]
+    pub subscriptions: Vec<SubscriptionSpec>,
+    #[serde(default)]
+    pub hooks: Hooks,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayerSpec {
+    pub name: String,
+    pub runtime: String,
+    pub version: Option<String>,
+}
+
+/// A gateway route to a function of the project or one already deployed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteSpec {
+    pub domain: String,
+    pub path: String,
+    pub function: String,
+    /// Alias of the function to route to instead of its latest version
+    pub alias: Option<String>,
+    #[serde(default)]
+    pub methods: Vec<String>,
+    pub auth: Option<String>,
+}
+
+/// A subscription delivering a topic's messages to a function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubscriptionSpec {
+    pub topic: String,
+    pub name: String,
+    pub function: String,
+    pub max_attempts: Option<i64>,
+    pub max_inflight: Option<i64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    /// Shell commands run before anything is deployed; a failure aborts.
+    #[serde(default)]
+    pub pre_deploy: Vec<String>,
+    /// Shell commands run after every resource has been deployed.
+    #[serde(default)]
+    pub post_deploy: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FunctionSpec {
+    pub name: String,
+    pub runtime: String,
+    /// Source file, relative to the manifest
+    pub code: Option<String>,
+    pub handler: Option<String>,
+    #[serde(alias = "memory")]
+    pub memory_mb: Option<i64>,
+    #[serde(alias = "timeout")]
+    pub timeout_s: Option<i64>,
+    pub min_replicas: Option<i64>,
+    pub max_replicas: Option<i64>,
+    pub mode: Option<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Layer names, attached after the function is deployed
+    #[serde(default)]
+    pub layers: Vec<String>,
+}
+
+impl ProjectManifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            OrbitError::Input(format!("Cannot read manifest {}: {e}", path.display()))
+        })?;
+        let manifest: Self = serde_yaml::from_str(&content)
+            .map_err(|e| OrbitError::Input(format!("Invalid manifest {}: {e}", path.display())))?;
+        let mut names = std::collections::HashSet::new();
+        if let Some(spec) = manifest.functions.iter().find(|f| !names.insert(&f.name)) {
+            return Err(OrbitError::Input(format!(
+                "Invalid manifest {}: function '{}' is defined more than once",
+                path.display(),
+                spec.name
+            )));
+        }
+        Ok(manifest)
+    }
+
+    /// Loads the manifest at `path`, or when there is none but its directory
+    /// has function directories under `functions/`, a manifest of just that
+    /// workspace.
+    pub fn load_or_workspace(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            let workspace = Self {
+                workspace: vec![DEFAULT_WORKSPACE.to_string()],
+                ..Self::default()
+            };
+            if !workspace.members(&base_dir(path)).is_empty() {
+                return Ok(workspace);
+            }
+        }
+        Self::load(path)
+    }
+
+    /// The workspace's function directories under `base`, sorted.
+    pub fn members(&self, base: &Path) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        for pattern in &self.workspace {
+            let mut matched = vec![base.to_path_buf()];
+            for segment in pattern.trim_matches('/').split('/') {
+                let mut next = Vec::new();
+                for dir in &matched {
+                    let Ok(entries) = std::fs::read_dir(dir) else {
+                        continue;
+                    };
+                    for entry in entries.flatten() {
+                        let name = entry.file_name().to_string_lossy().into_owned();
+                        if !name.starts_with('.')
+                            && entry.path().is_dir()
+                            && package::glob_match(segment.as_bytes(), name.as_bytes())
+                        {
+                            next.push(entry.path());
+                        }
+                    }
+                }
+                matched = next;
+            }
+            dirs.extend(matched.into_iter().filter(|dir| {
+                FUNCTION_MANIFESTS
+                    .iter()
+                    .any(|name| dir.join(name).is_file())
+            }));
+        }
+        dirs.sort();
+        dirs.dedup();
+        dirs
+    }
+}
+
+/// Field names accepted in a function spec file besides the API's own.
+const SPEC_FILE_ALIASES: &[(&str, &str)] = &[
+    ("memory", "memory_mb"),
+    ("timeout", "timeout_s"),
+    ("env", "env_vars"),
+    ("scaling", "auto_scale_policy"),
+];
+
+/// Reads a declarative function spec for `functions create -f`/`update -f`
+/// into a request body. The format follows the extension (YAML unless
+/// `.json` or `.toml`), and a `code_path` is read relative to the file.
+pub fn load_request_file(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| OrbitError::Input(format!("Cannot read file {}: {e}", path.display())))?;
+    let invalid = |e: String| OrbitError::Input(format!("Invalid spec {}: {e}", path.display()));
+    let mut body: Value = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?,
+        Some("toml") => toml::from_str(&content).map_err(|e| invalid(e.to_string()))?,
+        _ => serde_yaml::from_str(&content).map_err(|e| invalid(e.to_string()))?,
+    };
+    let fields = body
+        .as_object_mut()
+        .ok_or_else(|| invalid("expected a mapping of function fields".into()))?;
+    for (alias, field) in SPEC_FILE_ALIASES {
+        if let Some(v) = fields.remove(*alias) {
+            fields.entry(*field).or_insert(v);
+        }
+    }
+    if fields.get("code").is_none()
+        && let Some(code_path) = fields.get("code_path").and_then(|c| c.as_str())
+    {
+        let full = base_dir(path).join(code_path);
+        let code = std::fs::read_to_string(&full)
+            .map_err(|e| OrbitError::Input(format!("Cannot read file {}: {e}", full.display())))?;
+        fields.insert("code".into(), json!(code));
+    }
+    Ok(body)
+}
+
+/// Directory that relative paths in the manifest at `path` resolve against.
+pub fn base_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+impl FunctionSpec {
+    /// Loads the manifest of the function in `dir`, returning its path too.
+    /// A `nova.yaml` may hold the function itself or a `functions` list with
+    /// exactly one entry.
+    pub fn load_dir(dir: &Path) -> Result<(PathBuf, Self)> {
+        let path = FUNCTION_MANIFESTS
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|p| p.is_file())
+            .ok_or_else(|| {
+                OrbitError::Input(format!(
+                    "No {} in {}",
+                    FUNCTION_MANIFESTS.join(" or "),
+                    dir.display()
+                ))
+            })?;
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            OrbitError::Input(format!("Cannot read manifest {}: {e}", path.display()))
+        })?;
+        let invalid =
+            |e: String| OrbitError::Input(format!("Invalid manifest {}: {e}", path.display()));
+        let spec = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&content).map_err(|e| invalid(e.to_string()))?
+        } else {
+            let doc: serde_yaml::Value =
+                serde_yaml::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+            if doc.get("functions").is_some() {
+                let mut project: ProjectManifest =
+                    serde_yaml::from_value(doc).map_err(|e| invalid(e.to_string()))?;
+                if project.functions.len() != 1 {
+                    return Err(OrbitError::Input(format!(
+                        "{} describes {} functions; deploy it with `orbit deploy -f`",
+                        path.display(),
+                        project.functions.len()
+                    )));
+                }
+                project.functions.remove(0)
+            } else {
+                serde_yaml::from_value(doc).map_err(|e| invalid(e.to_string()))?
+            }
+        };
+        Ok((path, spec))
+    }
+
+    /// Request body for creating or updating the function with every source
+    /// file under `dir` packaged (see [`crate::package`]): the entry file
+    /// (`code`, or a conventional name) as `code` and the rest as
+    /// `dependency_files`.
+    pub fn to_package_body(&self, dir: &Path) -> Result<Value> {
+        let paths = package::list_files(dir, FUNCTION_MANIFESTS)?;
+        let entry = package::entry_file(&paths, self.code.as_deref())
+            .ok_or_else(|| {
+                OrbitError::Input(match &self.code {
+                    Some(code) => format!("Entry file {code} not found in {}", dir.display()),
+                    None => format!(
+                        "No entry file in {}; set `code` in the manifest",
+                        dir.display()
+                    ),
+                })
+            })?
+            .to_string();
+        let mut files = BTreeMap::new();
+        for relative in paths {
+            let path = dir.join(&relative);
+            let bytes = std::fs::read(&path)
+                .map_err(|e| OrbitError::Input(format!("Cannot read {}: {e}", path.display())))?;
+            let content = String::from_utf8(bytes).map_err(|_| {
+                OrbitError::Input(format!(
+                    "Cannot package {}: only text files are supported",
+                    path.display()
+                ))
+            })?;
+            files.insert(relative, content);
+        }
+        let code = files.remove(&entry).unwrap_or_default();
+        let spec = Self {
+            code: None,
+            ..self.clone()
+        };
+        let mut body = spec.to_body(dir)?;
+        body["code"] = json!(code);
+        if !files.is_empty() {
+            body["dependency_files"] = json!(files);
+        }
+        Ok(body)
+    }
+
+    /// Request body for creating or updating the function, with code read
+    /// relative to `base`.
+    pub fn to_body(&self, base: &Path) -> Result<Value> {
+        let mut body = json!({
+            "name": self.name,
+            "runtime": self.runtime,
+        });
+        if let Some(path) = &self.code {
+            let full = base.join(path);
+            let code = std::fs::read_to_string(&full).map_err(|e| {
+                OrbitError::Input(format!("Cannot read file {}: {e}", full.display()))
+            })?;
+            body["code"] = json!(code);
+        }
+        if let Some(h) = &self.handler {
+            body["handler"] = json!(h);
+        }
+        if let Some(m) = self.memory_mb {
+            body["memory_mb"] = json!(m);
+        }
+        if let Some(t) = self.timeout_s {
+            body["timeout_s"] = json!(t);
+        }
+        if let Some(v) = self.min_replicas {
+            body["min_replicas"] = json!(v);
+        }
+        if let Some(v) = self.max_replicas {
+            body["max_replicas"] = json!(v);
+        }
+        if let Some(m) = &self.mode {
+            body["mode"] = json!(m);
+        }
+        if !self.env.is_empty() {
+            body["env_vars"] = json!(self.env);
+        }
+        Ok(body)
+    }
+}
+
+impl LayerSpec {
+    pub fn to_body(&self) -> Value {
+        let mut body = json!({ "name": self.name, "runtime": self.runtime });
+        if let Some(v) = &self.version {
+            body["version"] = json!(v);
+        }
+        body
+    }
+}
+
+impl RouteSpec {
+    pub fn to_body(&self) -> Value {
+        let mut body = json!({
+            "domain": self.domain,
+            "path": self.path,
+            "function_name": self.function,
+        });
+        if let Some(a) = &self.alias {
+            body["function_alias"] = json!(a);
+        }
+        if !self.methods.is_empty() {
+            body["methods"] = json!(self.methods);
+        }
+        if let Some(a) = &self.auth {
+            body["auth_strategy"] = json!(a);
+        }
+        body
+    }
+}
+
+impl SubscriptionSpec {
+    pub fn to_body(&self) -> Value {
+        let mut body = json!({ "name": self.name, "function_name": self.function });
+        if let Some(m) = self.max_attempts {
+            body["max_attempts"] = json!(m);
+        }
+        if let Some(m) = self.max_inflight {
+            body["max_inflight"] = json!(m);
+        }
+        body
+    }
+}
@@ -0,0 +1,595 @@
+//! Composable layers around every HTTP request [`NovaClient`] sends.
+//!
+//! A [`Middleware`] receives the built request and a [`Next`] handle to the
+//! rest of the chain. It may modify the request, answer it without calling
+//! further, or run `next` more than once (retries). Past the last layer the
+//! request goes to the transport. The client's stack, outermost first:
+//!
+//! 1. [`DryRun`]: prints mutating API requests instead of sending them
+//! 2. [`ChangeFreeze`]: refuses or logs changes during a deploy lock
+//! 3. [`Credentials`]: API key, tenant, namespace and bearer token
+//! 4. [`Cache`]: revalidates GETs against `~/.orbit/cache`
+//! 5. [`RateLimitRetry`]: waits out 429 responses
+//! 6. [`HmacSigning`]: signs each attempt
+//! 7. [`Tracing`]: `-v` logging around the transport
+//!
+//! Layers that attach credentials or state only act on requests to the API
+//! server, so webhooks and OAuth endpoints go out untouched.
+//!
+//! [`NovaClient`]: crate::client::NovaClient
+
+use crate::cache::{CacheEntry, ResponseCache};
+use crate::config::{DeployLock, OrbitConfig, StoredToken};
+use crate::error::{OrbitError, Result};
+use crate::signing::HmacSigner;
+use crate::trace::HttpTracer;
+use futures_util::future::BoxFuture;
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{Client, Method, Request, Response, StatusCode, Url};
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Automatic retries of a request rejected with 429 Too Many Requests.
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Longest server-requested delay that is waited out automatically.
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+
+pub trait Middleware: Send + Sync {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>>;
+}
+
+/// The layers after the current one, ending at the transport.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    client: &'a Client,
+    layers: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub fn new(client: &'a Client, layers: &'a [Arc<dyn Middleware>]) -> Self {
+        Self { client, layers }
+    }
+
+    pub fn run(self, request: Request) -> BoxFuture<'a, Result<Response>> {
+        match self.layers.split_first() {
+            Some((layer, layers)) => layer.handle(
+                request,
+                Next {
+                    client: self.client,
+                    layers,
+                },
+            ),
+            None => Box::pin(async move { Ok(self.client.execute(request).await?) }),
+        }
+    }
+}
+
+/// Whether `request` goes to the API server at `base` rather than a third
+/// party such as a webhook or OAuth provider.
+fn is_api(request: &Request, base: &Url) -> bool {
+    request.url().origin() == base.origin()
+}
+
+pub fn is_mutating(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Delay requested by a rate-limited response, from `Retry-After` (seconds or
+/// an HTTP date) or else `X-RateLimit-Reset` (epoch seconds or seconds left).
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let now = chrono::Utc::now();
+    if let Some(value) = header("retry-after") {
+        if let Ok(secs) = value.trim().parse::<u64>() {
+            return Some(Duration::from_secs(secs));
+        }
+        if let Ok(at) = chrono::DateTime::parse_from_rfc2822(value) {
+            return (at.with_timezone(&chrono::Utc) - now).to_std().ok();
+        }
+    }
+    let reset = header("x-ratelimit-reset")?.trim().parse::<i64>().ok()?;
+    // Values past 2001-09-09 are absolute Unix timestamps.
+    let secs = if reset > 1_000_000_000 {
+        reset - now.timestamp()
+    } else {
+        reset
+    };
+    Some(Duration::from_secs(secs.max(0) as u64))
+}
+
+/// `--dry-run`: prints each mutating API request (method, path, body, with
+/// JSON pretty-printed and gzip bodies decompressed) instead of sending it,
+/// and answers it with a synthetic 200 echoing the request body, so the
+/// command runs to completion and shows its whole plan. Requests to other
+/// origins, such as OAuth endpoints, go out as usual.
+pub struct DryRun {
+    base: Url,
+}
+
+impl DryRun {
+    pub fn new(base: Url) -> Self {
+        Self { base }
+    }
+
+    /// The request body, decompressed if gzipped; `None` when it is
+    /// streamed or absent.
+    fn body(request: &Request) -> Option<Vec<u8>> {
+        let bytes = request.body()?.as_bytes()?;
+        let gzipped = request
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .is_some_and(|v| v == "gzip");
+        let mut decoded = Vec::new();
+        if gzipped
+            && flate2::read::GzDecoder::new(bytes)
+                .read_to_end(&mut decoded)
+                .is_ok()
+        {
+            return Some(decoded);
+        }
+        Some(bytes.to_vec())
+    }
+
+    fn print(request: &Request, body: Option<&[u8]>) {
+        let url = request.url();
+        match url.query() {
+            Some(q) => {
+                crate::output::print_line(&format!("{} {}?{q}", request.method(), url.path()))
+            }
+            None => crate::output::print_line(&format!("{} {}", request.method(), url.path())),
+        }
+        let is_multipart = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("multipart/"));
+        match body {
+            None if request.body().is_some() => crate::output::print_line("<streaming body>"),
+            None => {}
+            Some(bytes) if is_multipart => {
+                crate::output::print_line(&format!("<multipart body, {} bytes>", bytes.len()))
+            }
+            Some(bytes) => match serde_json::from_slice::<Value>(bytes) {
+                Ok(json) => crate::output::print_line(
+                    &serde_json::to_string_pretty(&json).unwrap_or_else(|_| json.to_string()),
+                ),
+                Err(_) => crate::output::print_line(&String::from_utf8_lossy(bytes)),
+            },
+        }
+    }
+
+    /// A successful response echoing the JSON object sent, or `{}`.
+    fn respond(body: Option<&[u8]>) -> Result<Response> {
+        let echoed = body
+            .and_then(|b| serde_json::from_slice::<Value>(b).ok())
+            .filter(Value::is_object)
+            .unwrap_or_else(|| Value::Object(Default::default()));
+        let resp = http::Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(echoed.to_string())
+            .map_err(|e| OrbitError::Config(format!("Invalid dry-run response: {e}")))?;
+        Ok(resp.into())
+    }
+}
+
+impl Middleware for DryRun {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            if is_mutating(request.method()) && is_api(&request, &self.base) {
+                let body = Self::body(&request);
+                Self::print(&request, body.as_deref());
+                return Self::respond(body.as_deref());
+            }
+            next.run(request).await
+        })
+    }
+}
+
+/// Refuses mutating API requests while a change freeze is active, unless it
+/// was overridden, in which case the request is appended to
+/// `~/.orbit/lock-overrides.log`. Invocations are not considered changes.
+pub struct ChangeFreeze {
+    base: Url,
+    lock: DeployLock,
+    reason: Option<String>,
+}
+
+impl ChangeFreeze {
+    pub fn new(base: Url, lock: DeployLock, reason: Option<String>) -> Self {
+        Self { base, lock, reason }
+    }
+
+    fn check(&self, request: &Request) -> Result<()> {
+        let route = request.url().path();
+        if !is_api(request, &self.base)
+            || !is_mutating(request.method())
+            || route.ends_with("/invoke")
+            || route.ends_with("/invoke-async")
+        {
+            return Ok(());
+        }
+        let Some(reason) = &self.reason else {
+            return Err(OrbitError::Input(format!(
+                "Changes are frozen until {} ({}). Re-run with --override-lock --reason \"...\" to proceed",
+                self.lock.until.to_rfc3339(),
+                self.lock.reason
+            )));
+        };
+        let user = std::env::var("USER").unwrap_or_else(|_| "unknown".into());
+        let line = format!(
+            "{}\t{user}\t{} {}\tlock: {}\toverride: {reason}\n",
+            chrono::Utc::now().to_rfc3339(),
+            request.method(),
+            request.url(),
+            self.lock.reason
+        );
+        let dir = crate::config::orbit_dir();
+        std::fs::create_dir_all(&dir)?;
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join("lock-overrides.log"))?
+            .write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl Middleware for ChangeFreeze {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            self.check(&request)?;
+            next.run(request).await
+        })
+    }
+}
+
+/// Attaches the identity headers to API requests: the API key (omitted when
+/// a bearer token is in use), tenant, namespace and any `--header` values,
+/// plus the `orbit login` token, refreshed and saved back to the config
+/// context once expired.
+pub struct Credentials {
+    base: Url,
+    headers: Vec<(String, String)>,
+    token: Option<Mutex<StoredToken>>,
+    context: Option<String>,
+    /// Bounds token refreshes, which are sent on their own.
+    timeout: Option<Duration>,
+}
+
+impl Credentials {
+    pub fn new(
+        base: Url,
+        headers: Vec<(String, String)>,
+        token: Option<StoredToken>,
+        context: Option<String>,
+        timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            base,
+            headers,
+            token: token.map(Mutex::new),
+            context,
+            timeout,
+        }
+    }
+
+    /// Headers sent on every API request, HTTP or WebSocket.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    /// Returns the current bearer token, refreshing and persisting it first
+    /// when it has expired. The refresh is sent through `next`, the layers
+    /// below this one.
+    pub async fn bearer(&self, next: Next<'_>) -> Result<Option<String>> {
+        let Some(token) = &self.token else {
+            return Ok(None);
+        };
+        let mut token = token.lock().await;
+        if token.is_expired() {
+            let Some(refresh) = token.refresh_token.clone() else {
+                return Err(OrbitError::Config(
+                    "Session expired. Run 'orbit login' again.".into(),
+                ));
+            };
+            let form = [
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh.as_str()),
+                ("client_id", token.client_id.as_str()),
+            ];
+            let mut request = next
+                .client
+                .post(&token.token_endpoint)
+                .form(&form)
+                .build()?;
+            *request.timeout_mut() = self.timeout;
+            let resp = next.run(request).await?;
+            let status = resp.status().as_u16();
+            let resp: Value = serde_json::from_str(&resp.text().await?).unwrap_or_default();
+            if status >= 400 {
+                let error = resp["error"].as_str().unwrap_or("unknown error");
+                return Err(OrbitError::api(
+                    status,
+                    format!("Token refresh failed: {error}"),
+                ));
+            }
+            let refreshed = StoredToken::from_response(
+                &resp,
+                &token.token_endpoint,
+                &token.client_id,
+                Some(refresh),
+            )
+            .ok_or_else(|| OrbitError::Config("Token refresh returned no access_token".into()))?;
+            let mut cfg = OrbitConfig::load();
+            cfg.section_mut(self.context.as_deref()).token = Some(refreshed.clone());
+            cfg.save()?;
+            *token = refreshed;
+        }
+        Ok(Some(token.access_token.clone()))
+    }
+}
+
+impl Middleware for Credentials {
+    fn handle<'a>(
+        &'a self,
+        mut request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            if !is_api(&request, &self.base) {
+                return next.run(request).await;
+            }
+            let headers = request.headers_mut();
+            for (name, value) in self.headers() {
+                let value = HeaderValue::from_str(value)
+                    .map_err(|e| OrbitError::Config(format!("Invalid {name} header: {e}")))?;
+                let name = header::HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|e| OrbitError::Config(format!("Invalid header name {name}: {e}")))?;
+                headers.append(name, value);
+            }
+            if let Some(token) = self.bearer(next).await? {
+                let value = HeaderValue::from_str(&format!("Bearer {token}"))
+                    .map_err(|e| OrbitError::Config(format!("Invalid bearer token: {e}")))?;
+                headers.insert(header::AUTHORIZATION, value);
+            }
+            next.run(request).await
+        })
+    }
+}
+
+/// On unless `--no-cache`: sends the stored validator along with API GETs and
+/// answers a 304 Not Modified with the cached body. Keys cover everything
+/// that can change a response: the URL plus the tenant, namespace and
+/// credential headers, so this layer sits below [`Credentials`]. HMAC
+/// signatures are added further down, so the signing key's identity is
+/// passed in and mixed into the key instead.
+pub struct Cache {
+    base: Url,
+    signer: Option<String>,
+    store: ResponseCache,
+}
+
+impl Cache {
+    pub fn new(base: Url, signer: Option<String>) -> Self {
+        Self {
+            base,
+            signer,
+            store: ResponseCache::new(),
+        }
+    }
+
+    fn key(&self, request: &Request) -> String {
+        let header = |name| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|v: &HeaderValue| v.to_str().ok())
+        };
+        let credential = header("authorization").or_else(|| header("x-api-key"));
+        ResponseCache::key(&[
+            Some(request.url().as_str()),
+            header("x-tenant-id"),
+            header("x-namespace"),
+            credential,
+            self.signer.as_deref(),
+        ])
+    }
+
+    fn replay(entry: CacheEntry) -> Result<Response> {
+        let mut builder = http::Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(link) = &entry.link {
+            builder = builder.header(header::LINK, format!("<{link}>; rel=\"next\""));
+        }
+        let resp = builder
+            .body(entry.body)
+            .map_err(|e| OrbitError::Config(format!("Invalid cached response: {e}")))?;
+        Ok(resp.into())
+    }
+}
+
+impl Middleware for Cache {
+    fn handle<'a>(
+        &'a self,
+        mut request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            if request.method() != Method::GET
+                || !is_api(&request, &self.base)
+                || !ResponseCache::cacheable(request.url().path())
+            {
+                return next.run(request).await;
+            }
+            let key = self.key(&request);
+            let cached = self.store.load(&key);
+            if let Some(entry) = &cached {
+                let headers = request.headers_mut();
+                let validators = [
+                    (header::IF_NONE_MATCH, &entry.etag),
+                    (header::IF_MODIFIED_SINCE, &entry.last_modified),
+                ];
+                for (name, value) in validators {
+                    if let Some(value) = value.as_deref().and_then(|v| v.parse().ok()) {
+                        headers.insert(name, value);
+                    }
+                }
+            }
+
+            let resp = next.run(request).await?;
+            if resp.status() == StatusCode::NOT_MODIFIED
+                && let Some(entry) = cached
+            {
+                return Self::replay(entry);
+            }
+            let header = |name| {
+                resp.headers()
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(String::from)
+            };
+            let etag = header(header::ETAG);
+            let last_modified = header(header::LAST_MODIFIED);
+            if !resp.status().is_success() || (etag.is_none() && last_modified.is_none()) {
+                return Ok(resp);
+            }
+            let link = header(header::LINK).and_then(|l| crate::client::next_link(&l));
+
+            // The body has to be read to store it, so the response is rebuilt
+            // around the buffered copy.
+            let mut rebuilt = http::Response::builder().status(resp.status());
+            if let Some(headers) = rebuilt.headers_mut() {
+                *headers = resp.headers().clone();
+            }
+            let body = resp.text().await?;
+            self.store.store(
+                &key,
+                &CacheEntry {
+                    etag,
+                    last_modified,
+                    link,
+                    body: body.clone(),
+                },
+            );
+            let rebuilt = rebuilt
+                .body(body)
+                .map_err(|e| OrbitError::Config(format!("Invalid cached response: {e}")))?;
+            Ok(rebuilt.into())
+        })
+    }
+}
+
+/// Retries requests rejected with 429 after the server-advertised delay, a
+/// bounded number of times, as long as the body can be replayed.
+pub struct RateLimitRetry;
+
+impl Middleware for RateLimitRetry {
+    fn handle<'a>(
+        &'a self,
+        mut request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let mut attempt = 0;
+            loop {
+                let retry = request.try_clone();
+                let resp = next.run(request).await?;
+                if resp.status() != StatusCode::TOO_MANY_REQUESTS
+                    || attempt >= MAX_RATE_LIMIT_RETRIES
+                {
+                    return Ok(resp);
+                }
+                let wait = retry_after(resp.headers())
+                    .unwrap_or_else(|| Duration::from_secs(1 << attempt));
+                let Some(retry) = retry.filter(|_| wait <= MAX_RETRY_WAIT) else {
+                    return Ok(resp);
+                };
+                crate::output::print_warning(&format!(
+                    "Rate limited by server; retrying in {}s ({}/{MAX_RATE_LIMIT_RETRIES})",
+                    wait.as_secs_f32().ceil(),
+                    attempt + 1
+                ));
+                tokio::time::sleep(wait).await;
+                request = retry;
+                attempt += 1;
+            }
+        })
+    }
+}
+
+/// Adds HMAC signature headers to API requests. It sits below
+/// [`RateLimitRetry`] so every attempt carries a fresh timestamp.
+pub struct HmacSigning {
+    base: Url,
+    signer: HmacSigner,
+}
+
+impl HmacSigning {
+    pub fn new(base: Url, signer: HmacSigner) -> Self {
+        Self { base, signer }
+    }
+
+    fn sign(&self, request: &mut Request) -> Result<()> {
+        let url = request.url();
+        let target = match url.query() {
+            Some(q) => format!("{}?{q}", url.path()),
+            None => url.path().to_string(),
+        };
+        let body = match request.body() {
+            Some(b) => b.as_bytes(),
+            None => Some(&[][..]),
+        };
+        for (name, value) in self
+            .signer
+            .headers(request.method().as_str(), &target, body)
+        {
+            let value = HeaderValue::from_str(&value)
+                .map_err(|e| OrbitError::Config(format!("Invalid {name} header: {e}")))?;
+            request.headers_mut().insert(name, value);
+        }
+        Ok(())
+    }
+}
+
+impl Middleware for HmacSigning {
+    fn handle<'a>(
+        &'a self,
+        mut request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            if is_api(&request, &self.base) {
+                self.sign(&mut request)?;
+            }
+            next.run(request).await
+        })
+    }
+}
+
+/// `-v` tracing of each request as it goes to the transport.
+pub struct Tracing(pub HttpTracer);
+
+impl Middleware for Tracing {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            self.0.request(&request);
+            let start = Instant::now();
+            match next.run(request).await {
+                Ok(resp) => {
+                    self.0.response(&resp, start.elapsed());
+                    Ok(resp)
+                }
+                Err(e) => {
+                    self.0.error(&e, start.elapsed());
+                    Err(e)
+                }
+            }
+        })
+    }
+}
@@ -1,10 +1,21 @@
-use comfy_table::{ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use crate::error::OrbitError;
+use comfy_table::{
+    ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS, presets, presets::UTF8_FULL,
+};
 use serde_json::Value;
+use std::cmp::Ordering;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::{Mutex, OnceLock};
 
+#[derive(Clone, Copy)]
 pub struct Column {
     pub header: &'static str,
     pub path: &'static str,
     pub wide_only: bool,
+    /// Width list cells are cut to, if narrower than `--max-col-width`
+    pub max_width: Option<usize>,
 }
 
 impl Column {
@@ -13,6 +24,7 @@ impl Column {
             header,
             path,
             wide_only: false,
+            max_width: None,
         }
     }
 
@@ -21,27 +33,176 @@ impl Column {
             header,
             path,
             wide_only: true,
+            max_width: None,
         }
     }
+
+    /// Cuts this column's cells in list tables to `width` characters, for
+    /// free-form fields such as payloads and errors.
+    pub const fn max_width(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+        self
+    }
 }
 
-fn extract_field(value: &Value, path: &str) -> String {
+/// The value at a dotted `path` (`a.b.0.c`), or null when any step is
+/// missing. An empty path is the value itself.
+fn lookup<'a>(value: &'a Value, path: &str) -> &'a Value {
+    if path.is_empty() {
+        return value;
+    }
     let mut current = value;
     for key in path.split('.') {
-        match current {
-            Value::Object(map) => {
-                current = map.get(key).unwrap_or(&Value::Null);
-            }
-            Value::Array(arr) => {
-                if let Ok(idx) = key.parse::<usize>() {
-                    current = arr.get(idx).unwrap_or(&Value::Null);
-                } else {
-                    return "-".to_string();
-                }
-            }
-            _ => return "-".to_string(),
+        current = match current {
+            Value::Object(map) => map.get(key),
+            Value::Array(arr) => key.parse::<usize>().ok().and_then(|idx| arr.get(idx)),
+            _ => None,
+        }
+        .unwrap_or(&Value::Null);
+    }
+    current
+}
+
+fn extract_field(value: &Value, path: &str) -> String {
+    format_value(lookup(value, path))
+}
+
+/// How RFC 3339 timestamps are shown in tables.
+#[derive(Default, Clone, Copy)]
+pub enum TimestampStyle {
+    /// `3m ago`, `in 2d`
+    #[default]
+    Relative,
+    /// Local time, e.g. `2024-05-01 14:03:22 +02:00`
+    Absolute,
+    /// Seconds since the epoch
+    Unix,
+}
+
+impl TimestampStyle {
+    pub fn parse(style: &str) -> Self {
+        match style {
+            "absolute" => Self::Absolute,
+            "unix" => Self::Unix,
+            _ => Self::Relative,
         }
     }
+}
+
+/// Cuts `cell` to `width` characters with an ellipsis; multi-line values
+/// keep only their first line.
+fn truncate(cell: String, width: usize) -> String {
+    let first = cell.lines().next().unwrap_or_default();
+    if first.len() == cell.len() && console::measure_text_width(&cell) <= width {
+        return cell;
+    }
+    let first = console::truncate_str(first, width.saturating_sub(1), "");
+    format!("{first}…")
+}
+
+/// The unit a numeric field is in, going by the suffix of its name.
+#[derive(Clone, Copy)]
+enum Unit {
+    Mebibytes,
+    Bytes,
+    BytesPerSec,
+    Millis,
+    Secs,
+}
+
+fn unit_of(path: &str) -> Option<Unit> {
+    let field = path.rsplit('.').next().unwrap_or(path);
+    if field.ends_with("_mb") {
+        Some(Unit::Mebibytes)
+    } else if field.ends_with("_bytes") {
+        Some(Unit::Bytes)
+    } else if field.ends_with("_bandwidth") {
+        Some(Unit::BytesPerSec)
+    } else if field.ends_with("_ms") {
+        Some(Unit::Millis)
+    } else if field.ends_with("_s") || field.ends_with("_seconds") {
+        Some(Unit::Secs)
+    } else {
+        None
+    }
+}
+
+/// One decimal place, dropped when it is zero: `1.5`, `2`.
+fn trim_number(n: f64) -> String {
+    let s = format!("{n:.1}");
+    s.strip_suffix(".0").map(String::from).unwrap_or(s)
+}
+
+fn scale(mut n: f64, base: f64, units: &[&str]) -> String {
+    let mut unit = 0;
+    while n.abs() >= base && unit < units.len() - 1 {
+        n /= base;
+        unit += 1;
+    }
+    format!("{} {}", trim_number(n), units[unit])
+}
+
+fn format_unit(n: f64, unit: Unit) -> String {
+    const BINARY: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    match unit {
+        Unit::Mebibytes => scale(n * 1024.0 * 1024.0, 1024.0, BINARY),
+        Unit::Bytes => scale(n, 1024.0, BINARY),
+        // Bandwidth limits use 0 for no limit.
+        Unit::BytesPerSec if n == 0.0 => "unlimited".to_string(),
+        Unit::BytesPerSec => scale(n, 1000.0, &["B/s", "kB/s", "MB/s", "GB/s"]),
+        Unit::Millis if n < 1000.0 => format!("{} ms", trim_number(n)),
+        Unit::Millis if n < 60_000.0 => format!("{} s", trim_number(n / 1000.0)),
+        Unit::Millis => crate::duration::format_age(chrono::Duration::milliseconds(n as i64)),
+        Unit::Secs if n < 60.0 => format!("{} s", trim_number(n)),
+        Unit::Secs => crate::duration::format_age(chrono::Duration::seconds(n as i64)),
+    }
+}
+
+/// A byte count in binary units, e.g. `1.5 KiB`.
+pub fn format_bytes(n: usize) -> String {
+    format_unit(n as f64, Unit::Bytes)
+}
+
+/// A column header for tables; units shown in the header (`Memory (MB)`)
+/// are left off when the values carry their own.
+fn table_header(column: &Column) -> &'static str {
+    if options().raw_values || unit_of(column.path).is_none() {
+        return column.header;
+    }
+    [" (MB)", " (ms)", " (s)", " (bytes)", " MB", " ms"]
+        .iter()
+        .find_map(|unit| column.header.strip_suffix(unit))
+        .unwrap_or(column.header)
+}
+
+/// A table cell: like `extract_field`, but with sizes and durations in
+/// readable units and timestamps in the `--timestamps` style, unless
+/// `--raw-values` is set.
+fn table_cell(value: &Value, path: &str) -> String {
+    let field = lookup(value, path);
+    if options().raw_values {
+        return format_value(field);
+    }
+    if let (Some(n), Some(unit)) = (field.as_f64(), unit_of(path)) {
+        return format_unit(n, unit);
+    }
+    let Some(t) = field
+        .as_str()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+    else {
+        return format_value(field);
+    };
+    match options().timestamps {
+        TimestampStyle::Relative => crate::duration::relative(t.to_utc(), chrono::Utc::now()),
+        TimestampStyle::Absolute => t
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S %:z")
+            .to_string(),
+        TimestampStyle::Unix => t.timestamp().to_string(),
+    }
+}
+
+pub fn format_value(current: &Value) -> String {
     match current {
         Value::Null => "-".to_string(),
         Value::String(s) => s.clone(),
@@ -65,20 +226,846 @@ fn extract_field(value: &Value, path: &str) -> String {
     }
 }
 
-pub fn render(data: &Value, columns: &[Column], format: &str) {
-    match format {
-        "json" => {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(data).unwrap_or_else(|_| data.to_string())
-            );
-        }
-        "yaml" => {
-            println!(
-                "{}",
-                serde_yaml::to_string(data).unwrap_or_else(|_| data.to_string())
-            );
+/// `--out-file` target and the output rendered so far.
+static OUT_FILE: Mutex<Option<(PathBuf, String)>> = Mutex::new(None);
+
+/// Collects rendered output for `path` instead of printing it; called once
+/// from `main`, which writes it with `write_out_file` when the command
+/// succeeds.
+pub fn set_out_file(path: PathBuf) {
+    *OUT_FILE.lock().unwrap() = Some((path, String::new()));
+}
+
+/// Writes the collected output atomically: to a temporary file in the same
+/// directory, then renamed over `path`.
+pub fn write_out_file() -> std::io::Result<()> {
+    let Some((path, content)) = OUT_FILE.lock().unwrap().take() else {
+        return Ok(());
+    };
+    let mut tmp = path.clone().into_os_string();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, &path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+/// Prints rendered output, or collects it under `--out-file`.
+fn emit(text: &str) {
+    if captured(text) {
+        return;
+    }
+    if let Some((_, buffer)) = OUT_FILE.lock().unwrap().as_mut() {
+        return buffer.push_str(text);
+    }
+    match PAGED.lock().unwrap().as_mut() {
+        Some(buffer) => buffer.push_str(text),
+        None => print!("{text}"),
+    }
+}
+
+fn emit_line(line: impl std::fmt::Display) {
+    emit(&format!("{line}\n"));
+}
+
+/// Like `emit`, but lets a serializer write straight to stdout instead of
+/// building the whole document as one string first.
+fn emit_with(write: impl FnOnce(&mut dyn std::io::Write) -> std::io::Result<()>) {
+    use std::io::Write;
+    let buffered = CAPTURE.try_with(|_| ()).is_ok()
+        || OUT_FILE.lock().unwrap().is_some()
+        || PAGED.lock().unwrap().is_some();
+    if buffered {
+        let mut bytes = Vec::new();
+        let _ = write(&mut bytes);
+        return emit(&String::from_utf8_lossy(&bytes));
+    }
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    let _ = write(&mut out).and_then(|_| out.flush());
+}
+
+/// Rows drawn in a table; longer lists are cut off with a note, since
+/// laying out the table gets slow and the result unreadable.
+const TABLE_ROW_LIMIT: usize = 1000;
+
+/// Whether tables are drawn as borderless, space-aligned text: when output
+/// goes to a file or stdout isn't a terminal, so pipes and redirects don't
+/// capture box-drawing characters.
+fn plain_tables() -> bool {
+    OUT_FILE.lock().unwrap().is_some() || !std::io::stdout().is_terminal()
+}
+
+fn new_table() -> Table {
+    let mut table = Table::new();
+    if plain_tables() {
+        table
+            .load_preset(presets::NOTHING)
+            .set_content_arrangement(ContentArrangement::Disabled);
+    } else {
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic);
+    }
+    table
+}
+
+fn emit_table(table: &Table) {
+    if plain_tables() {
+        emit_plain_table(table);
+    } else {
+        emit_line(table);
+    }
+}
+
+fn emit_plain_table(table: &Table) {
+    // The NOTHING preset pads every cell with a leading space.
+    for line in table.to_string().lines() {
+        emit_line(line.strip_prefix(' ').unwrap_or(line).trim_end());
+    }
+}
+
+/// `--color` choice: `Some` for always/never, `None` to decide per stream.
+static COLOR: OnceLock<Option<bool>> = OnceLock::new();
+
+/// Applies `--color auto|always|never`; called once from `main`. `auto`
+/// colors a stream only when it is a terminal and `NO_COLOR` is unset.
+pub fn set_color(mode: &str) {
+    let choice = match mode {
+        "always" => Some(true),
+        "never" => Some(false),
+        _ => None,
+    };
+    let _ = COLOR.set(choice);
+    // Coloring is decided in `paint`, not by the crate's stdout-only check.
+    colored::control::set_override(true);
+}
+
+/// `--quiet`: drop status messages and progress, leaving only data and
+/// errors.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, AtomicOrdering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(AtomicOrdering::Relaxed)
+}
+
+/// `--progress json`: report progress as NDJSON events instead of bars.
+static PROGRESS_JSON: AtomicBool = AtomicBool::new(false);
+
+/// `--progress none`: no bars, without silencing status messages.
+static PROGRESS_HIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// Applies `--progress auto|json|none`; called once from `main`.
+pub fn set_progress(mode: &str) {
+    PROGRESS_JSON.store(mode == "json", AtomicOrdering::Relaxed);
+    PROGRESS_HIDDEN.store(mode == "none", AtomicOrdering::Relaxed);
+}
+
+/// Least time between two `progress` events of one task, so byte counts
+/// of an upload don't flood the stream.
+const PROGRESS_EVENT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Progress of a long operation: an indicatif bar on stderr, hidden under
+/// `--quiet`, or under `--progress json` one JSON object per line on stderr:
+///
+/// ```text
+/// {"current":0,"elapsed_ms":0,"event":"start","task":"benchmark","total":20}
+/// {"current":7,"elapsed_ms":912,"event":"progress","task":"benchmark","total":20}
+/// {"current":20,"elapsed_ms":2611,"event":"finish","task":"benchmark","total":20}
+/// ```
+///
+/// A task dropped before finishing (on an error) ends with an `abort`
+/// event.
+#[derive(Clone)]
+pub struct Progress {
+    bar: indicatif::ProgressBar,
+    events: Option<std::sync::Arc<ProgressEvents>>,
+}
+
+struct ProgressEvents {
+    task: &'static str,
+    bar: indicatif::ProgressBar,
+    last: Mutex<Option<std::time::Instant>>,
+    finished: AtomicBool,
+}
+
+impl ProgressEvents {
+    fn emit(&self, event: &str) {
+        let mut line = serde_json::json!({
+            "event": event,
+            "task": self.task,
+            "current": self.bar.position(),
+            "elapsed_ms": self.bar.elapsed().as_millis() as u64,
+        });
+        if let Some(total) = self.bar.length() {
+            line["total"] = serde_json::json!(total);
+        }
+        let message = self.bar.message();
+        if !message.is_empty() {
+            line["message"] = serde_json::json!(message);
+        }
+        eprintln!("{line}");
+    }
+
+    /// Emits a `progress` event unless one went out too recently.
+    fn progress(&self, force: bool) {
+        let now = std::time::Instant::now();
+        let mut last = self.last.lock().unwrap();
+        if force || last.is_none_or(|t| now - t >= PROGRESS_EVENT_INTERVAL) {
+            *last = Some(now);
+            self.emit("progress");
+        }
+    }
+}
+
+impl Drop for ProgressEvents {
+    fn drop(&mut self) {
+        if !self.finished.load(AtomicOrdering::Relaxed) {
+            self.emit("abort");
+        }
+    }
+}
+
+impl Progress {
+    pub fn set_style(&self, style: indicatif::ProgressStyle) {
+        self.bar.set_style(style);
+    }
+
+    pub fn enable_steady_tick(&self, interval: std::time::Duration) {
+        if self.events.is_none() {
+            self.bar.enable_steady_tick(interval);
+        }
+    }
+
+    pub fn set_message(&self, message: impl Into<std::borrow::Cow<'static, str>>) {
+        self.bar.set_message(message);
+        if let Some(events) = &self.events {
+            events.progress(true);
+        }
+    }
+
+    pub fn set_length(&self, len: u64) {
+        self.bar.set_length(len);
+        if let Some(events) = &self.events {
+            events.progress(true);
+        }
+    }
+
+    pub fn inc(&self, delta: u64) {
+        self.bar.inc(delta);
+        if let Some(events) = &self.events {
+            events.progress(false);
+        }
+    }
+
+    pub fn finish_and_clear(&self) {
+        self.bar.finish_and_clear();
+        if let Some(events) = &self.events
+            && !events.finished.swap(true, AtomicOrdering::Relaxed)
+        {
+            events.emit("finish");
+        }
+    }
+}
+
+/// Starts reporting progress of `task` (a short kebab-case name such as
+/// `upload`, used as the `task` of JSON events) out of `len` steps, or with
+/// a spinner when `len` is `None`.
+pub fn progress_bar(task: &'static str, len: Option<u64>) -> Progress {
+    let json = PROGRESS_JSON.load(AtomicOrdering::Relaxed);
+    let target = if is_quiet() || json || PROGRESS_HIDDEN.load(AtomicOrdering::Relaxed) {
+        indicatif::ProgressDrawTarget::hidden()
+    } else {
+        indicatif::ProgressDrawTarget::stderr()
+    };
+    let bar = indicatif::ProgressBar::with_draw_target(len, target);
+    let events = json.then(|| {
+        let events = ProgressEvents {
+            task,
+            bar: bar.clone(),
+            last: Mutex::new(None),
+            finished: AtomicBool::new(false),
+        };
+        events.emit("start");
+        std::sync::Arc::new(events)
+    });
+    Progress { bar, events }
+}
+
+fn paint(msg: &str, color: colored::Color, terminal: bool) -> String {
+    use colored::Colorize;
+    let enabled =
+        COLOR.get().copied().flatten().unwrap_or_else(|| {
+            terminal && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        });
+    if enabled {
+        msg.color(color).to_string()
+    } else {
+        msg.to_string()
+    }
+}
+
+tokio::task_local! {
+    /// Output of the `orbit batch` operation being polled, collected instead
+    /// of printed.
+    static CAPTURE: std::cell::RefCell<String>;
+}
+
+/// Runs `f`, collecting the output it renders (but not what it prints
+/// directly) instead of printing it.
+pub async fn capture<F: std::future::Future>(f: F) -> (F::Output, String) {
+    let run = async {
+        let result = f.await;
+        (result, CAPTURE.with(|c| c.take()))
+    };
+    CAPTURE
+        .scope(std::cell::RefCell::new(String::new()), run)
+        .await
+}
+
+/// Appends `text` to the output being captured, if any.
+fn captured(text: &str) -> bool {
+    CAPTURE.try_with(|c| c.borrow_mut().push_str(text)).is_ok()
+}
+
+/// Output held back to go through the pager, if paging is enabled.
+static PAGED: Mutex<Option<String>> = Mutex::new(None);
+
+/// Holds rendered output until `flush_pager` when stdout is a terminal;
+/// called once from `main` unless `--no-pager` is given.
+pub fn enable_pager() {
+    if std::io::stdout().is_terminal() {
+        *PAGED.lock().unwrap() = Some(String::new());
+    }
+}
+
+/// Stops holding output for the pager, printing what was held, for
+/// commands that keep printing until interrupted.
+pub fn disable_pager() {
+    if let Some(text) = PAGED.lock().unwrap().take() {
+        print!("{text}");
+    }
+}
+
+/// Prints the held output, through `$ORBIT_PAGER` or `$PAGER` (default
+/// `less -R`) when it is taller than the terminal.
+pub fn flush_pager() {
+    let Some(text) = PAGED.lock().unwrap().take() else {
+        return;
+    };
+    let pager = std::env::var("ORBIT_PAGER")
+        .or_else(|_| std::env::var("PAGER"))
+        .unwrap_or_else(|_| "less -R".into());
+    let fits = console::Term::stdout()
+        .size_checked()
+        .is_none_or(|(rows, cols)| {
+            let cols = usize::from(cols.max(1));
+            let height: usize = text
+                .lines()
+                .map(|line| console::measure_text_width(line).div_ceil(cols).max(1))
+                .sum();
+            height < usize::from(rows)
+        });
+    if fits || matches!(pager.trim(), "" | "cat") || page(&pager, &text).is_err() {
+        print!("{text}");
+    }
+}
+
+fn page(pager: &str, text: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    // Run through the shell so pagers with arguments ("less -R") work.
+    let mut child = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(pager)
+        .stdin(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user quitting the pager early closes the pipe; that's fine.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Prints a document such as source code as is, going through the pager
+/// and `--out-file` like rendered output.
+pub fn print_text(text: &str) {
+    emit_line(text);
+}
+
+/// Prints a line to stdout that isn't part of the rendered result (a
+/// summary or a hint), keeping it in order with output held for the pager.
+pub fn print_line(line: &str) {
+    if captured(&format!("{line}\n")) {
+        return;
+    }
+    match PAGED.lock().unwrap().as_mut() {
+        Some(buffer) => {
+            buffer.push_str(line);
+            buffer.push('\n');
+        }
+        None => println!("{line}"),
+    }
+}
+
+/// Presentation settings from global flags, applied by every `render`.
+#[derive(Default)]
+pub struct RenderOptions {
+    /// Field path array results are sorted by
+    pub sort_by: Option<String>,
+    pub descending: bool,
+    /// Leave out the header row of tables
+    pub no_headers: bool,
+    /// jq expression applied to the response before anything else
+    pub query: Option<String>,
+    /// Conditions every row of an array result must meet
+    pub filters: Vec<Filter>,
+    pub timestamps: TimestampStyle,
+    /// Show values in tables exactly as the API returned them
+    pub raw_values: bool,
+    /// Width list table cells are cut to; `None` with `--truncate=false`
+    pub max_col_width: Option<usize>,
+}
+
+/// A `--filter` condition such as `status=error` or `duration_ms>1000`.
+pub struct Filter {
+    path: String,
+    op: &'static str,
+    value: String,
+}
+
+impl Filter {
+    pub fn parse(expr: &str) -> crate::error::Result<Self> {
+        let invalid = || {
+            crate::error::OrbitError::Input(format!(
+                "Invalid filter '{expr}': expected FIELD=VALUE, FIELD!=VALUE, FIELD>N, FIELD<N, FIELD>=N or FIELD<=N"
+            ))
+        };
+        let at = expr.find(['=', '!', '<', '>']).ok_or_else(invalid)?;
+        let op = ["!=", ">=", "<=", "=", ">", "<"]
+            .into_iter()
+            .find(|op| expr[at..].starts_with(op))
+            .ok_or_else(invalid)?;
+        let path = expr[..at].trim();
+        if path.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            path: field_path(path),
+            op,
+            value: expr[at + op.len()..].trim().to_string(),
+        })
+    }
+
+    /// Equality compares text (numerically when both sides are numbers);
+    /// ordering compares numbers, or text for e.g. RFC 3339 timestamps.
+    /// A missing field only satisfies `!=`.
+    fn matches(&self, item: &Value) -> bool {
+        let field = lookup(item, &self.path);
+        if field.is_null() {
+            return self.op == "!=";
+        }
+        let text = match field {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let ordering = match (field.as_f64(), self.value.parse::<f64>()) {
+            (Some(a), Ok(b)) => a.total_cmp(&b),
+            _ => text.as_str().cmp(self.value.as_str()),
+        };
+        match self.op {
+            "=" => ordering.is_eq(),
+            "!=" => ordering.is_ne(),
+            ">" => ordering.is_gt(),
+            "<" => ordering.is_lt(),
+            ">=" => ordering.is_ge(),
+            _ => ordering.is_le(),
+        }
+    }
+}
+
+static RENDER_OPTIONS: OnceLock<RenderOptions> = OnceLock::new();
+
+/// Sets the options for the rest of the process; called once from `main`.
+pub fn set_options(options: RenderOptions) {
+    let _ = RENDER_OPTIONS.set(options);
+}
+
+fn options() -> &'static RenderOptions {
+    RENDER_OPTIONS.get_or_init(RenderOptions::default)
+}
+
+/// Orders values of mixed JSON types: numbers numerically, then strings,
+/// then booleans, then anything else by its text.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    let rank = |v: &Value| match v {
+        Value::Number(_) => 0,
+        Value::String(_) => 1,
+        Value::Bool(_) => 2,
+        _ => 3,
+    };
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x
+            .as_f64()
+            .unwrap_or(0.0)
+            .total_cmp(&y.as_f64().unwrap_or(0.0)),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        _ => rank(a)
+            .cmp(&rank(b))
+            .then_with(|| a.to_string().cmp(&b.to_string())),
+    }
+}
+
+/// Sorts `items` by the field at `path`; items without it go last in
+/// either direction.
+fn sort_items(items: &mut [Value], path: &str, descending: bool) {
+    let path = field_path(path);
+    items.sort_by(|a, b| match (lookup(a, &path), lookup(b, &path)) {
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Greater,
+        (_, Value::Null) => Ordering::Less,
+        (x, y) if descending => compare_values(y, x),
+        (x, y) => compare_values(x, y),
+    });
+}
+
+/// Checks a `-o` value up front, so a malformed `custom-columns=` or
+/// `jsonpath=` spec fails before any request is made.
+pub fn validate_format(format: &str) -> crate::error::Result<()> {
+    let invalid = |e: String| crate::error::OrbitError::Input(format!("Invalid -o {format}: {e}"));
+    if let Some(spec) = format.strip_prefix("custom-columns=") {
+        parse_custom_columns(spec).map_err(invalid)?;
+    } else if let Some(template) = format.strip_prefix("jsonpath=") {
+        parse_template(template).map_err(invalid)?;
+    } else if let Some(template) = format.strip_prefix("template=") {
+        let source = jinja_source(template);
+        minijinja::Environment::new()
+            .template_from_str(&source)
+            .map_err(|e| invalid(e.to_string()))?;
+    } else if !matches!(format, "table" | "wide" | "json" | "yaml" | "name") {
+        return Err(crate::error::OrbitError::Input(format!(
+            "Unknown output format '{format}': expected table, wide, json, yaml, name, custom-columns=..., jsonpath=... or template=..."
+        )));
+    }
+    Ok(())
+}
+
+/// Formats that print the data as returned by the server, rather than a
+/// summary meant for people.
+pub fn is_machine_readable(format: &str) -> bool {
+    matches!(format, "json" | "yaml" | "name")
+        || format.starts_with("custom-columns=")
+        || format.starts_with("jsonpath=")
+        || format.starts_with("template=")
+}
+
+/// Turns a kubectl-style field reference (`.spec.items[0].name`) into an
+/// `extract_field` path (`spec.items.0.name`).
+fn field_path(expr: &str) -> String {
+    expr.trim()
+        .trim_start_matches('.')
+        .replace('[', ".")
+        .replace(']', "")
+}
+
+/// Parses `NAME:.path,NAME:.path`.
+fn parse_custom_columns(spec: &str) -> Result<Vec<(String, String)>, String> {
+    spec.split(',')
+        .map(|col| {
+            let (name, path) = col
+                .split_once(':')
+                .ok_or_else(|| format!("column '{col}' must be NAME:.path"))?;
+            if name.is_empty() {
+                return Err(format!("column '{col}' has no name"));
+            }
+            Ok((name.to_string(), field_path(path)))
+        })
+        .collect()
+}
+
+fn render_custom_columns(data: &Value, spec: &str) {
+    let Ok(columns) = parse_custom_columns(spec) else {
+        return;
+    };
+    let rows: &[Value] = match data {
+        Value::Array(items) => items,
+        other => std::slice::from_ref(other),
+    };
+    let mut table = Table::new();
+    table
+        .load_preset(presets::NOTHING)
+        .set_content_arrangement(ContentArrangement::Disabled);
+    if !options().no_headers {
+        table.set_header(columns.iter().map(|(name, _)| name.as_str()));
+    }
+    for row in rows {
+        table.add_row(columns.iter().map(|(_, path)| extract_field(row, path)));
+    }
+    emit_plain_table(&table);
+}
+
+enum Step {
+    Key(String),
+    Index(usize),
+    All,
+}
+
+enum Node {
+    Text(String),
+    Path(Vec<Step>),
+    Range(Vec<Step>, Vec<Node>),
+}
+
+fn parse_steps(expr: &str) -> Result<Vec<Step>, String> {
+    let expr = expr.trim().trim_start_matches(['$', '@']);
+    let mut steps = Vec::new();
+    let mut rest = expr;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let (inner, tail) = after
+                .split_once(']')
+                .ok_or_else(|| format!("unclosed '[' in '{expr}'"))?;
+            steps.push(match inner {
+                "*" => Step::All,
+                n => Step::Index(n.parse().map_err(|_| format!("bad index '[{n}]'"))?),
+            });
+            rest = tail;
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            match &after[..end] {
+                "" => {}
+                "*" => steps.push(Step::All),
+                key => steps.push(Step::Key(key.to_string())),
+            }
+            rest = &after[end..];
+        } else {
+            return Err(format!("expected '.' or '[' in '{expr}'"));
+        }
+    }
+    Ok(steps)
+}
+
+/// Parses a kubectl-style JSONPath template: literal text, `{.path}`,
+/// `{"literal"}` and `{range .path}...{end}`.
+fn parse_template(template: &str) -> Result<Vec<Node>, String> {
+    let mut stack: Vec<(Vec<Step>, Vec<Node>)> = Vec::new();
+    let mut nodes = Vec::new();
+    let mut rest = template;
+    while !rest.is_empty() {
+        let Some(open) = rest.find('{') else {
+            nodes.push(Node::Text(rest.to_string()));
+            break;
+        };
+        if open > 0 {
+            nodes.push(Node::Text(rest[..open].to_string()));
+        }
+        let close = rest[open..].find('}').ok_or("unclosed '{'")? + open;
+        let expr = rest[open + 1..close].trim();
+        rest = &rest[close + 1..];
+        if let Some(path) = expr.strip_prefix("range ") {
+            stack.push((parse_steps(path)?, std::mem::take(&mut nodes)));
+        } else if expr == "end" {
+            let (steps, outer) = stack.pop().ok_or("'{end}' without '{range}'")?;
+            let body = std::mem::replace(&mut nodes, outer);
+            nodes.push(Node::Range(steps, body));
+        } else if let Some(literal) = expr.strip_prefix('"').and_then(|e| e.strip_suffix('"')) {
+            nodes.push(Node::Text(
+                literal.replace("\\n", "\n").replace("\\t", "\t"),
+            ));
+        } else {
+            nodes.push(Node::Path(parse_steps(expr)?));
+        }
+    }
+    if !stack.is_empty() {
+        return Err("'{range}' without '{end}'".into());
+    }
+    Ok(nodes)
+}
+
+fn select<'a>(value: &'a Value, steps: &[Step]) -> Vec<&'a Value> {
+    let mut current = vec![value];
+    for step in steps {
+        current = current
+            .into_iter()
+            .flat_map(|v| -> Vec<&Value> {
+                match (step, v) {
+                    (Step::Key(k), Value::Object(map)) => map.get(k).into_iter().collect(),
+                    (Step::Index(i), Value::Array(arr)) => arr.get(*i).into_iter().collect(),
+                    (Step::All, Value::Array(arr)) => arr.iter().collect(),
+                    (Step::All, Value::Object(map)) => map.values().collect(),
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+    }
+    current
+}
+
+fn eval_template(nodes: &[Node], value: &Value, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Path(steps) => {
+                let items: Vec<String> = select(value, steps)
+                    .into_iter()
+                    .map(|v| match v {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect();
+                out.push_str(&items.join(" "));
+            }
+            Node::Range(steps, body) => {
+                for item in select(value, steps) {
+                    eval_template(body, item, out);
+                }
+            }
         }
+    }
+}
+
+fn render_jsonpath(data: &Value, template: &str) {
+    let Ok(nodes) = parse_template(template) else {
+        return;
+    };
+    // Lists are addressed as `.items[*]`, as with kubectl.
+    let root = match data {
+        Value::Array(_) => serde_json::json!({ "items": data }),
+        other => other.clone(),
+    };
+    let mut out = String::new();
+    eval_template(&nodes, &root, &mut out);
+    if out.ends_with('\n') {
+        emit(&out);
+    } else {
+        emit_line(out);
+    }
+}
+
+/// Accepts Go-template style field references (`{{.name}}`) as well as
+/// plain Jinja (`{{ name }}`).
+fn jinja_source(template: &str) -> String {
+    template.replace("{{.", "{{ ").replace("{{ .", "{{ ")
+}
+
+/// Renders `-o template=...` with minijinja: once per item for lists, each
+/// on its own line, with the item's fields as variables.
+fn render_template(data: &Value, template: &str) -> crate::error::Result<()> {
+    let source = jinja_source(template);
+    let env = minijinja::Environment::new();
+    let template = env
+        .template_from_str(&source)
+        .map_err(|e| OrbitError::Input(format!("Template error: {e}")))?;
+    let items: &[Value] = match data {
+        Value::Array(items) => items,
+        Value::Null => &[],
+        other => std::slice::from_ref(other),
+    };
+    for item in items {
+        let text = template
+            .render(item)
+            .map_err(|e| OrbitError::Input(format!("Template error: {e}")))?;
+        emit_line(text);
+    }
+    Ok(())
+}
+
+/// Prints a `--query` result that is not a resource, or a list of them, the
+/// way `jq -r` does: strings unquoted, one value per line.
+fn print_raw(value: &Value) -> bool {
+    let scalar = |v: &Value| !v.is_object() && !v.is_array();
+    let values: Vec<&Value> = match value {
+        Value::Array(items) if items.iter().all(scalar) => items.iter().collect(),
+        v if scalar(v) => vec![v],
+        _ => return false,
+    };
+    for v in values {
+        match v {
+            Value::String(s) => emit_line(s),
+            other => emit_line(other),
+        }
+    }
+    true
+}
+
+/// Renders `data` in `format`, after `--query`, `--filter` and `--sort-by`.
+/// Fails on a query or template that cannot be evaluated.
+pub fn render(data: &Value, columns: &[Column], format: &str) -> crate::error::Result<()> {
+    let options = options();
+    let queried;
+    let data = match &options.query {
+        Some(expr) => {
+            queried = crate::query::run(expr, data)?;
+            if !is_machine_readable(format) && print_raw(&queried) {
+                return Ok(());
+            }
+            &queried
+        }
+        None => data,
+    };
+    if let Value::Array(items) = data
+        && (options.sort_by.is_some() || !options.filters.is_empty())
+    {
+        let mut items: Vec<Value> = items
+            .iter()
+            .filter(|item| options.filters.iter().all(|f| f.matches(item)))
+            .cloned()
+            .collect();
+        if let Some(path) = &options.sort_by {
+            sort_items(&mut items, path, options.descending);
+        }
+        return render_rows(&Value::Array(items), columns, format);
+    }
+    render_rows(data, columns, format)
+}
+
+/// The identifier `-o name` prints for a resource: its `name`, else its
+/// `id`, else the first column.
+fn identifier(item: &Value, columns: &[Column]) -> String {
+    ["name", "id"]
+        .into_iter()
+        .chain(columns.first().map(|c| c.path))
+        .map(|path| lookup(item, path))
+        .find(|v| !v.is_null())
+        .map(format_value)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn render_rows(data: &Value, columns: &[Column], format: &str) -> crate::error::Result<()> {
+    if let Some(spec) = format.strip_prefix("custom-columns=") {
+        render_custom_columns(data, spec);
+        return Ok(());
+    }
+    if let Some(template) = format.strip_prefix("jsonpath=") {
+        render_jsonpath(data, template);
+        return Ok(());
+    }
+    if let Some(template) = format.strip_prefix("template=") {
+        return render_template(data, template);
+    }
+    match format {
+        "json" => emit_with(|out| {
+            serde_json::to_writer_pretty(&mut *out, data)?;
+            writeln!(out)
+        }),
+        "yaml" => emit_with(|out| {
+            serde_yaml::to_writer(&mut *out, data).map_err(std::io::Error::other)?;
+            writeln!(out)
+        }),
+        "name" => match data {
+            Value::Array(items) => {
+                for item in items {
+                    emit_line(identifier(item, columns));
+                }
+            }
+            Value::Null => {}
+            other => emit_line(identifier(other, columns)),
+        },
         _ => {
             let wide = format == "wide";
             let active_columns: Vec<&Column> =
@@ -87,60 +1074,202 @@ pub fn render(data: &Value, columns: &[Column], format: &str) {
             match data {
                 Value::Array(items) => {
                     if items.is_empty() {
-                        println!("No resources found.");
-                        return;
+                        emit_line("No resources found.");
+                        return Ok(());
+                    }
+                    let mut table = new_table();
+                    if !options().no_headers {
+                        table.set_header(active_columns.iter().map(|c| table_header(c)));
                     }
-                    let mut table = Table::new();
-                    table
-                        .load_preset(UTF8_FULL)
-                        .apply_modifier(UTF8_ROUND_CORNERS)
-                        .set_content_arrangement(ContentArrangement::Dynamic);
-
-                    let headers: Vec<&str> = active_columns.iter().map(|c| c.header).collect();
-                    table.set_header(headers);
 
-                    for item in items {
+                    let widths: Vec<Option<usize>> = active_columns
+                        .iter()
+                        .map(|c| {
+                            let max = options().max_col_width?;
+                            Some(c.max_width.map_or(max, |w| w.min(max)))
+                        })
+                        .collect();
+                    for item in items.iter().take(TABLE_ROW_LIMIT) {
                         let row: Vec<String> = active_columns
                             .iter()
-                            .map(|c| extract_field(item, c.path))
+                            .zip(&widths)
+                            .map(|(c, width)| match width {
+                                Some(width) => truncate(table_cell(item, c.path), *width),
+                                None => table_cell(item, c.path),
+                            })
                             .collect();
                         table.add_row(row);
                     }
-                    println!("{table}");
+                    emit_table(&table);
+                    if items.len() > TABLE_ROW_LIMIT {
+                        print_warning(&format!(
+                            "Showing first {TABLE_ROW_LIMIT} of {} rows. Narrow the list with --limit, --filter or --query, or use -o json to get every row.",
+                            items.len()
+                        ));
+                    }
                 }
                 Value::Object(_) => {
-                    let mut table = Table::new();
-                    table
-                        .load_preset(UTF8_FULL)
-                        .apply_modifier(UTF8_ROUND_CORNERS)
-                        .set_content_arrangement(ContentArrangement::Dynamic);
-                    table.set_header(vec!["Field", "Value"]);
+                    let mut table = new_table();
+                    if !options().no_headers {
+                        table.set_header(vec!["Field", "Value"]);
+                    }
                     for col in &active_columns {
-                        table.add_row(vec![col.header.to_string(), extract_field(data, col.path)]);
+                        table.add_row(vec![
+                            table_header(col).to_string(),
+                            table_cell(data, col.path),
+                        ]);
                     }
-                    println!("{table}");
+                    emit_table(&table);
                 }
                 _ => {
-                    println!(
-                        "{}",
-                        serde_json::to_string_pretty(data).unwrap_or_else(|_| data.to_string())
+                    emit_line(
+                        serde_json::to_string_pretty(data).unwrap_or_else(|_| data.to_string()),
                     );
                 }
             }
         }
     }
+    Ok(())
 }
 
-pub fn render_single(data: &Value, columns: &[Column], format: &str) {
-    render(data, columns, format);
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes `rows` to `path` as CSV with every column, including wide-only
+/// ones. Missing values are left empty.
+pub fn write_csv(path: &str, rows: &[Value], columns: &[Column]) -> std::io::Result<()> {
+    let mut out = String::new();
+    let header: Vec<String> = columns.iter().map(|c| csv_field(c.header)).collect();
+    out.push_str(&header.join(","));
+    out.push('\n');
+    for row in rows {
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|c| match extract_field(row, c.path) {
+                s if s == "-" => String::new(),
+                s => csv_field(&s),
+            })
+            .collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    std::fs::write(path, out)
 }
 
+pub fn render_single(data: &Value, columns: &[Column], format: &str) -> crate::error::Result<()> {
+    render(data, columns, format)
+}
+
+/// Reports that something was done. Like every status message it goes to
+/// stderr, keeping stdout for data, and is dropped under `--quiet`.
 pub fn print_success(msg: &str) {
-    use colored::Colorize;
-    println!("{}", msg.green());
+    if is_quiet() {
+        return;
+    }
+    let terminal = std::io::stderr().is_terminal();
+    eprintln!("{}", paint(msg, colored::Color::Green, terminal));
+}
+
+/// Prints a progress note or hint to stderr, unless `--quiet`.
+pub fn print_status(msg: &str) {
+    if !is_quiet() {
+        eprintln!("{msg}");
+    }
 }
 
 pub fn print_error(msg: &str) {
-    use colored::Colorize;
-    eprintln!("{}", msg.red());
+    let terminal = std::io::stderr().is_terminal();
+    eprintln!("{}", paint(msg, colored::Color::Red, terminal));
+}
+
+pub fn print_warning(msg: &str) {
+    let terminal = std::io::stderr().is_terminal();
+    eprintln!("{}", paint(msg, colored::Color::Yellow, terminal));
+}
+
+/// Reports every failed item of a [`NovaClient::batch`] run followed by a
+/// one-line summary, and returns the number of failures.
+///
+/// [`NovaClient::batch`]: crate::client::NovaClient::batch
+pub fn print_batch_summary<T: std::fmt::Display>(
+    results: &[(T, crate::error::Result<Value>)],
+    action: &str,
+    noun: &str,
+) -> usize {
+    let mut failed = 0;
+    for (item, result) in results {
+        if let Err(e) = result {
+            print_error(&format!("{item}: {e}"));
+            failed += 1;
+        }
+    }
+    let done = results.len() - failed;
+    if failed == 0 {
+        print_success(&format!("{action} {done} {noun}."));
+    } else {
+        print_warning(&format!(
+            "{action} {done} of {} {noun}; {failed} failed.",
+            results.len()
+        ));
+    }
+    failed
+}
+
+/// Prints a unified diff, colored on a terminal like `git diff`. Like
+/// rendered output it goes through the pager and `--out-file`, uncolored in
+/// the file.
+pub fn print_diff(diff: &str) {
+    let terminal = std::io::stdout().is_terminal() && OUT_FILE.lock().unwrap().is_none();
+    for line in diff.lines() {
+        let color = if line.starts_with("+++") || line.starts_with("---") {
+            colored::Color::White
+        } else if line.starts_with('+') {
+            colored::Color::Green
+        } else if line.starts_with('-') {
+            colored::Color::Red
+        } else if line.starts_with("@@") {
+            colored::Color::Cyan
+        } else {
+            emit_line(line);
+            continue;
+        };
+        emit_line(paint(line, color, terminal));
+    }
+}
+
+/// Draws `values` as a one-line trend, scaled between their minimum and
+/// maximum.
+pub fn sparkline(values: &[f64]) -> String {
+    const TICKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|v| {
+            if max > min {
+                TICKS[((v - min) / (max - min) * 7.0).round() as usize]
+            } else {
+                TICKS[0]
+            }
+        })
+        .collect()
+}
+
+/// Asks a yes/no question on stderr; anything but `y`/`yes` declines.
+pub fn confirm(prompt: &str) -> bool {
+    use std::io::Write;
+    // Show what is being confirmed before asking.
+    flush_pager();
+    eprint!("{prompt} [y/N] ");
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
 }
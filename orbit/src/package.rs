@@ -0,0 +1,196 @@
+//! Packaging a function directory for upload: the files to include, honoring
+//! `.orbitignore`, zipped into a single archive.
+//!
+//! `.orbitignore` takes gitignore-style lines: `*` and `?` wildcards, a
+//! trailing `/` for directories only, a leading `/` (or any inner `/`) to
+//! match from the directory root, and `!` to include a path again. The last
+//! matching line wins. Hidden entries and build directories are never
+//! packaged.
+
+use crate::error::{OrbitError, Result};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+
+pub const IGNORE_FILE: &str = ".orbitignore";
+
+/// Directories never packaged with a function's source.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "__pycache__", "venv"];
+
+/// Entry files tried, in order, when none is named explicitly.
+const ENTRY_FILES: &[&str] = &[
+    "main.py",
+    "handler.py",
+    "index.js",
+    "handler.js",
+    "index.ts",
+    "main.go",
+    "src/main.rs",
+    "main.rs",
+    "handler.rb",
+    "index.php",
+];
+
+/// A zipped function directory.
+pub struct Package {
+    pub archive: Vec<u8>,
+    /// One `{path, size_bytes, sha256}` entry per packaged file
+    pub manifest: Vec<Value>,
+}
+
+struct Pattern {
+    glob: String,
+    anchored: bool,
+    dir_only: bool,
+    negate: bool,
+}
+
+struct Ignore(Vec<Pattern>);
+
+impl Ignore {
+    fn load(dir: &Path) -> Self {
+        let content = std::fs::read_to_string(dir.join(IGNORE_FILE)).unwrap_or_default();
+        let patterns = content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|line| {
+                let (negate, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                Pattern {
+                    anchored: line.contains('/'),
+                    glob: line.trim_start_matches('/').to_string(),
+                    dir_only,
+                    negate,
+                }
+            })
+            .collect();
+        Self(patterns)
+    }
+
+    /// Whether `relative` (`/`-separated, from the package root) is excluded.
+    fn is_ignored(&self, relative: &str, is_dir: bool) -> bool {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        let mut ignored = false;
+        for p in &self.0 {
+            if p.dir_only && !is_dir {
+                continue;
+            }
+            let subject = if p.anchored { relative } else { name };
+            if glob_match(p.glob.as_bytes(), subject.as_bytes()) {
+                ignored = !p.negate;
+            }
+        }
+        ignored
+    }
+}
+
+/// Matches `*` (any run of characters other than `/`) and `?` (one
+/// character).
+pub fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob_match(&pattern[1..], text)
+                || (text.first().is_some_and(|c| *c != b'/') && glob_match(pattern, &text[1..]))
+        }
+        (Some(b'?'), Some(c)) if *c != b'/' => glob_match(&pattern[1..], &text[1..]),
+        (Some(p), Some(c)) if p == c => glob_match(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+/// The files under `dir` to package, as sorted `/`-separated relative paths.
+/// Names in `exclude_root` are left out when directly in `dir`.
+pub fn list_files(dir: &Path, exclude_root: &[&str]) -> Result<Vec<String>> {
+    let ignore = Ignore::load(dir);
+    let mut files = Vec::new();
+    walk(dir, "", &ignore, exclude_root, &mut files)?;
+    Ok(files)
+}
+
+fn walk(
+    dir: &Path,
+    prefix: &str,
+    ignore: &Ignore,
+    exclude_root: &[&str],
+    files: &mut Vec<String>,
+) -> Result<()> {
+    let read_err =
+        |e: std::io::Error| OrbitError::Input(format!("Cannot read {}: {e}", dir.display()));
+    let mut entries = std::fs::read_dir(dir)
+        .map_err(read_err)?
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(read_err)?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') || (prefix.is_empty() && exclude_root.contains(&name.as_str())) {
+            continue;
+        }
+        let relative = format!("{prefix}{name}");
+        let path = entry.path();
+        let is_dir = path.is_dir();
+        if ignore.is_ignored(&relative, is_dir) {
+            continue;
+        }
+        if is_dir {
+            if !SKIPPED_DIRS.contains(&name.as_str()) {
+                walk(&path, &format!("{relative}/"), ignore, exclude_root, files)?;
+            }
+        } else {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// The entry file among `files`: `explicit` if given and present, else the
+/// first of the conventional names found.
+pub fn entry_file<'a>(files: &'a [String], explicit: Option<&str>) -> Option<&'a str> {
+    match explicit {
+        Some(e) => files.iter().find(|f| *f == e),
+        None => ENTRY_FILES
+            .iter()
+            .find_map(|e| files.iter().find(|f| f == e)),
+    }
+    .map(String::as_str)
+}
+
+/// Zips the packaged files of `dir`.
+pub fn zip_dir(dir: &Path) -> Result<Package> {
+    let files = list_files(dir, &[])?;
+    if files.is_empty() {
+        return Err(OrbitError::Input(format!(
+            "No files to package in {}",
+            dir.display()
+        )));
+    }
+    let zip_err =
+        |e: zip::result::ZipError| OrbitError::Input(format!("Cannot build archive: {e}"));
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut manifest = Vec::with_capacity(files.len());
+    for file in &files {
+        let path = dir.join(file);
+        let content = std::fs::read(&path)
+            .map_err(|e| OrbitError::Input(format!("Cannot read {}: {e}", path.display())))?;
+        writer.start_file(file.as_str(), options).map_err(zip_err)?;
+        writer.write_all(&content)?;
+        manifest.push(json!({
+            "path": file,
+            "size_bytes": content.len(),
+            "sha256": hex::encode(Sha256::digest(&content)),
+        }));
+    }
+    let archive = writer.finish().map_err(zip_err)?.into_inner();
+    Ok(Package { archive, manifest })
+}
@@ -0,0 +1,94 @@
+//! Passphrase encryption of credentials stored in the config file.
+//!
+//! The key is derived from the passphrase with Argon2id and a per-file salt;
+//! each value is sealed with AES-256-GCM under a fresh nonce and stored as:
+//!
+//! ```text
+//! enc:pass:v1:<BASE64(NONCE || CIPHERTEXT)>
+//! ```
+
+use crate::error::{OrbitError, Result};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, OsRng};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+const PREFIX: &str = "enc:pass:v1:";
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+
+/// Environment variable read instead of prompting for the passphrase.
+pub const PASSPHRASE_ENV: &str = "ORBIT_CONFIG_PASSPHRASE";
+
+pub type Key = [u8; 32];
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+/// A random salt, base64-encoded for the config file.
+pub fn new_salt() -> String {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    STANDARD.encode(salt)
+}
+
+pub fn derive_key(passphrase: &str, salt: &str) -> Result<Key> {
+    let salt = STANDARD
+        .decode(salt)
+        .map_err(|_| OrbitError::Config("Invalid encryption salt in config".into()))?;
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+        .map_err(|e| OrbitError::Config(format!("Key derivation failed: {e}")))?;
+    Ok(key)
+}
+
+pub fn encrypt(key: &Key, plaintext: &str) -> Result<String> {
+    let cipher = Aes256Gcm::new(key.into());
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| OrbitError::Config("Encryption failed".into()))?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(format!("{PREFIX}{}", STANDARD.encode(sealed)))
+}
+
+pub fn decrypt(key: &Key, value: &str) -> Result<String> {
+    let invalid = || OrbitError::Config("Malformed encrypted value in config".into());
+    let sealed = value
+        .strip_prefix(PREFIX)
+        .and_then(|v| STANDARD.decode(v).ok())
+        .filter(|s| s.len() > NONCE_LEN)
+        .ok_or_else(invalid)?;
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let plaintext = Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| OrbitError::Config("Wrong config passphrase".into()))?;
+    String::from_utf8(plaintext).map_err(|_| invalid())
+}
+
+/// Reads the passphrase from `ORBIT_CONFIG_PASSPHRASE`, else prompts on the
+/// terminal, asking twice when `confirm` is set.
+pub fn read_passphrase(confirm: bool) -> Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    let prompt = |label: &str| {
+        rpassword::prompt_password(label).map_err(|_| {
+            OrbitError::Config(format!(
+                "Config credentials are encrypted; set {PASSPHRASE_ENV} when no terminal is available"
+            ))
+        })
+    };
+    let passphrase = prompt("Config passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(OrbitError::Input("Passphrase cannot be empty".into()));
+    }
+    if confirm && prompt("Repeat passphrase: ")? != passphrase {
+        return Err(OrbitError::Input("Passphrases do not match".into()));
+    }
+    Ok(passphrase)
+}
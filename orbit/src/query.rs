@@ -0,0 +1,87 @@
+//! `--query`: jq expressions applied to a response before it is rendered,
+//! evaluated with jaq (jq's standard library included).
+
+use crate::error::{OrbitError, Result};
+use jaq_core::load::{Arena, File, Loader};
+use jaq_core::{Compiler, Ctx, Native, RcIter};
+use jaq_json::Val;
+use serde_json::Value;
+
+type Filter = jaq_core::Filter<Native<Val>>;
+
+fn invalid(expr: &str, reason: impl std::fmt::Display) -> OrbitError {
+    OrbitError::Input(format!("Invalid --query '{expr}': {reason}"))
+}
+
+fn expected(what: &str, at: &str) -> String {
+    match at {
+        "" => format!("expected {what} at end of input"),
+        at => format!("expected {what} at '{at}'"),
+    }
+}
+
+fn compile(expr: &str) -> Result<Filter> {
+    let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+    let arena = Arena::default();
+    let modules = loader
+        .load(
+            &arena,
+            File {
+                code: expr,
+                path: (),
+            },
+        )
+        .map_err(|errors| {
+            let reason = errors
+                .into_iter()
+                .flat_map(|(_, e)| match e {
+                    jaq_core::load::Error::Io(e) => e.into_iter().map(|(_, e)| e).collect(),
+                    jaq_core::load::Error::Lex(e) => e
+                        .into_iter()
+                        .map(|(expect, at)| expected(expect.as_str(), at))
+                        .collect(),
+                    jaq_core::load::Error::Parse(e) => e
+                        .into_iter()
+                        .map(|(expect, at)| expected(expect.as_str(), at))
+                        .collect::<Vec<_>>(),
+                })
+                .next()
+                .unwrap_or_else(|| "syntax error".into());
+            invalid(expr, reason)
+        })?;
+    Compiler::default()
+        .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+        .compile(modules)
+        .map_err(|errors| {
+            let reason = errors
+                .into_iter()
+                .flat_map(|(_, e)| e)
+                .map(|(name, kind)| format!("undefined {} '{name}'", kind.as_str()))
+                .next()
+                .unwrap_or_else(|| "compile error".into());
+            invalid(expr, reason)
+        })
+}
+
+/// Checks an expression up front so a typo fails before any request.
+pub fn validate(expr: &str) -> Result<()> {
+    compile(expr).map(drop)
+}
+
+/// Runs `expr` on `input`. A single result is returned as is; several
+/// (e.g. from `.[]`) are collected into an array.
+pub fn run(expr: &str, input: &Value) -> Result<Value> {
+    let filter = compile(expr)?;
+    let inputs = RcIter::new(core::iter::empty());
+    let mut results = filter
+        .run((Ctx::new([], &inputs), Val::from(input.clone())))
+        .map(|r| {
+            r.map(Value::from)
+                .map_err(|e| OrbitError::Input(format!("--query failed: {e}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(match results.len() {
+        1 => results.remove(0),
+        _ => Value::Array(results),
+    })
+}
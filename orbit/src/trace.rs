@@ -0,0 +1,143 @@
+//! HTTP debug tracing for `-v/--verbose`.
+//!
+//! Level 1 logs the request line, response status, and timing; level 2 adds
+//! headers; level 3 adds request and response bodies. Everything goes to
+//! stderr with credentials redacted.
+
+use reqwest::header::HeaderMap;
+use reqwest::{Request, Response};
+use serde_json::Value;
+use std::time::Duration;
+
+const REDACTED: &str = "***";
+
+/// Header and JSON field names whose values are never printed.
+pub(crate) fn is_sensitive(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    matches!(
+        name.as_str(),
+        "authorization" | "proxy-authorization" | "cookie" | "set-cookie" | "x-api-key" | "key"
+    ) || ["token", "secret", "password", "api_key", "apikey"]
+        .iter()
+        .any(|s| name.contains(s))
+}
+
+fn redact_url(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    if url.password().is_some() {
+        let _ = url.set_password(Some(REDACTED));
+    }
+    url.to_string()
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if is_sensitive(key) && !v.is_null() {
+                    *v = Value::String(REDACTED.into());
+                } else {
+                    redact_json(v);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+fn format_body(body: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut json) => {
+            redact_json(&mut json);
+            json.to_string()
+        }
+        Err(_) => String::from_utf8_lossy(body).into_owned(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpTracer {
+    level: u8,
+}
+
+impl HttpTracer {
+    pub fn new(level: u8) -> Self {
+        Self { level }
+    }
+
+    fn log_headers(&self, prefix: &str, headers: &HeaderMap) {
+        if self.level < 2 {
+            return;
+        }
+        for (name, value) in headers {
+            let value = if is_sensitive(name.as_str()) {
+                REDACTED.into()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            eprintln!("{prefix} {name}: {value}");
+        }
+    }
+
+    pub fn request(&self, request: &Request) {
+        if self.level == 0 {
+            return;
+        }
+        eprintln!("> {} {}", request.method(), redact_url(request.url()));
+        self.log_headers(">", request.headers());
+        if self.level >= 3 {
+            match request.body().map(|b| b.as_bytes()) {
+                Some(Some(bytes)) => eprintln!("> {}", format_body(bytes)),
+                Some(None) => eprintln!("> <streaming body>"),
+                None => {}
+            }
+        }
+    }
+
+    pub fn websocket(&self, url: &str) {
+        if self.level > 0 {
+            eprintln!("> GET {url} (websocket)");
+        }
+    }
+
+    pub fn response(&self, response: &Response, elapsed: Duration) {
+        if self.level == 0 {
+            return;
+        }
+        eprintln!(
+            "< {} {} ({} ms)",
+            response.status(),
+            redact_url(response.url()),
+            elapsed.as_millis()
+        );
+        let headers = response.headers();
+        let value = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("?")
+        };
+        if headers.contains_key("x-ratelimit-remaining") {
+            eprintln!(
+                "< rate limit: {}/{} remaining, resets {}",
+                value("x-ratelimit-remaining"),
+                value("x-ratelimit-limit"),
+                value("x-ratelimit-reset"),
+            );
+        }
+        self.log_headers("<", headers);
+    }
+
+    pub fn response_body(&self, body: &str) {
+        if self.level >= 3 && !body.is_empty() {
+            eprintln!("< {}", format_body(body.as_bytes()));
+        }
+    }
+
+    pub fn error(&self, error: &crate::error::OrbitError, elapsed: Duration) {
+        if self.level > 0 {
+            eprintln!("! request failed after {} ms: {error}", elapsed.as_millis());
+        }
+    }
+}